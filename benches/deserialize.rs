@@ -0,0 +1,153 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Compares deserializing a document directly (`from_str`) with parsing it
+//! into the AST first (`parse_from_str`) and then converting the AST
+//! (`AsonNode::deserialize_into`).
+//!
+//! Run with `cargo bench --bench deserialize`.
+
+use std::collections::HashMap;
+
+use ason::{from_str, parse_from_str, to_string};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Registry {
+    name: String,
+    version: (u16, u16, u16),
+    mirrors: Vec<String>,
+    packages: Vec<Package>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Package {
+    id: u32,
+    name: String,
+    description: Option<String>,
+    downloads: u64,
+    rating: f64,
+    license: License,
+    keywords: Vec<String>,
+    dependencies: HashMap<String, Dependency>,
+    checksum: serde_bytes::ByteBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Dependency {
+    version: String,
+    optional: bool,
+    features: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum License {
+    Mit,
+    Apache,
+    Custom(String),
+    Dual { first: String, second: String },
+}
+
+// builds a registry of `count` packages, the content is deterministic
+// so the numbers of different runs are comparable.
+fn build_registry(count: u32) -> Registry {
+    let packages = (0..count)
+        .map(|id| {
+            let dependencies = (0..id % 5)
+                .map(|index| {
+                    (
+                        format!("dep-{}-{}", id, index),
+                        Dependency {
+                            version: format!("{}.{}.{}", index, id % 10, id % 3),
+                            optional: index % 2 == 0,
+                            features: vec!["std".to_owned(), format!("feature-{}", index)],
+                        },
+                    )
+                })
+                .collect();
+
+            let license = match id % 4 {
+                0 => License::Mit,
+                1 => License::Apache,
+                2 => License::Custom(format!("License of package {}", id)),
+                _ => License::Dual {
+                    first: "MIT".to_owned(),
+                    second: "Apache-2.0".to_owned(),
+                },
+            };
+
+            Package {
+                id,
+                name: format!("package-{}", id),
+                description: (id % 3 != 0)
+                    .then(|| format!("The package number {}, with \"quotes\" and\ttabs.", id)),
+                downloads: u64::from(id) * 7919,
+                rating: f64::from(id % 50) / 10.0,
+                license,
+                keywords: (0..id % 4)
+                    .map(|index| format!("keyword{}", index))
+                    .collect(),
+                dependencies,
+                checksum: serde_bytes::ByteBuf::from(
+                    (0..16)
+                        .map(|index| (id as u8).wrapping_mul(index))
+                        .collect::<Vec<u8>>(),
+                ),
+            }
+        })
+        .collect();
+
+    Registry {
+        name: "benchmark".to_owned(),
+        version: (1, 4, 0),
+        mirrors: vec![
+            "https://mirror-a.example.com".to_owned(),
+            "https://mirror-b.example.com".to_owned(),
+        ],
+        packages,
+    }
+}
+
+fn bench_deserialize(c: &mut Criterion) {
+    let registry = build_registry(500);
+    let document = to_string(&registry).unwrap();
+
+    // make sure all paths produce the same value
+    assert_eq!(from_str::<Registry>(&document).unwrap(), registry);
+    assert_eq!(
+        parse_from_str(&document)
+            .unwrap()
+            .deserialize_into::<Registry>()
+            .unwrap(),
+        registry
+    );
+
+    let mut group = c.benchmark_group("deserialize");
+    group.throughput(Throughput::Bytes(document.len() as u64));
+
+    group.bench_function("from_str", |b| {
+        b.iter(|| from_str::<Registry>(black_box(&document)).unwrap())
+    });
+
+    group.bench_function("parse_from_str", |b| {
+        b.iter(|| parse_from_str(black_box(&document)).unwrap())
+    });
+
+    group.bench_function("parse_from_str + deserialize_into", |b| {
+        b.iter(|| {
+            parse_from_str(black_box(&document))
+                .unwrap()
+                .deserialize_into::<Registry>()
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_deserialize);
+criterion_main!(benches);
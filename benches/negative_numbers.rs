@@ -0,0 +1,44 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Measures parsing a number-heavy document of negative integers, which
+//! exercises the negation of integer literals in the normalizer.
+//!
+//! Run with `cargo bench --bench negative_numbers`.
+
+use ason::parse_from_str;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+// builds a list of `count` negative integers of all signed types,
+// the content is deterministic so the numbers of different runs are comparable.
+fn build_document(count: u32) -> String {
+    let items = (0..count)
+        .map(|index| match index % 4 {
+            0 => format!("-{}_i8", index % 129),
+            1 => format!("-{}_i16", index % 32769),
+            2 => format!("-{}", index * 7919),
+            _ => format!("-{}_i64", u64::from(index) * 1_000_000_007),
+        })
+        .collect::<Vec<String>>();
+
+    format!("[{}]", items.join(", "))
+}
+
+fn bench_negative_numbers(c: &mut Criterion) {
+    let document = build_document(100_000);
+
+    let mut group = c.benchmark_group("negative_numbers");
+    group.throughput(Throughput::Bytes(document.len() as u64));
+
+    group.bench_function("parse_from_str", |b| {
+        b.iter(|| parse_from_str(black_box(&document)).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_negative_numbers);
+criterion_main!(benches);
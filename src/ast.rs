@@ -4,8 +4,16 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
+use std::{cmp::Ordering, collections::HashMap, fmt::Display};
+
 use chrono::{DateTime, FixedOffset};
 
+use crate::{location::Location, printer::print_to_string, token::Comment, AsonError};
+
+/// Note that the equality of the floating-point numbers follows the
+/// IEEE 754 semantics, i.e. `NaN` is not equal to anything (including
+/// itself), and `0.0` equals to `-0.0`. As a result, two nodes which
+/// contain `NaN` are never equal, even if they are cloned from the same node.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Number {
     // it is possible for literal to overflow for signed numbers,
@@ -20,23 +28,25 @@ pub enum Number {
     U32(u32),
     I64(i64),
     U64(u64),
+    I128(i128),
+    U128(u128),
     F32(f32),
     F64(f64),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct KeyValuePair {
     pub key: String,
     pub value: Box<AsonNode>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct NameValuePair {
     pub name: Box<AsonNode>,
     pub value: Box<AsonNode>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Variant {
     // variant type name, e.g. the "Option" of "Option::None"
     pub type_name: String,
@@ -47,7 +57,7 @@ pub struct Variant {
     pub value: VariantValue,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum VariantValue {
     Empty,                     // unit variant
     Value(Box<AsonNode>),      // new type variant
@@ -55,7 +65,7 @@ pub enum VariantValue {
     Object(Vec<KeyValuePair>), // struct variant
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum AsonNode {
     Number(Number),
     Boolean(bool),
@@ -68,6 +78,10 @@ pub enum AsonNode {
     Tuple(Vec<AsonNode>),
     Object(Vec<KeyValuePair>),
     Map(Vec<NameValuePair>),
+
+    // custom literal `@name(value)`, it is produced by
+    // the extension handler of parser.
+    Extension(String, Box<AsonNode>),
 }
 
 impl KeyValuePair {
@@ -115,4 +129,1123 @@ impl Variant {
             value: VariantValue::Object(key_value_pairs),
         }
     }
+
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    pub fn member_name(&self) -> &str {
+        &self.member_name
+    }
+
+    /// Returns `Some(())` if it is a unit variant, e.g. `Option::None`.
+    pub fn as_unit(&self) -> Option<()> {
+        match &self.value {
+            VariantValue::Empty => Some(()),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the single value variant, e.g. `Option::Some(123)`.
+    pub fn as_newtype(&self) -> Option<&AsonNode> {
+        match &self.value {
+            VariantValue::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the values of the tuple variant, e.g. `Color::RGB(255, 127, 63)`.
+    pub fn as_tuple(&self) -> Option<&[AsonNode]> {
+        match &self.value {
+            VariantValue::Tuple(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Returns the key-value pairs of the struct variant,
+    /// e.g. `Shape::Rect{width: 200, height: 100}`.
+    pub fn as_object(&self) -> Option<&[KeyValuePair]> {
+        match &self.value {
+            VariantValue::Object(key_value_pairs) => Some(key_value_pairs),
+            _ => None,
+        }
+    }
+}
+
+/// The kind of node, i.e. the variant of `AsonNode` without data.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum NodeKind {
+    Number,
+    Boolean,
+    Char,
+    String,
+    DateTime,
+    Variant,
+    HexByteData,
+    List,
+    Tuple,
+    Object,
+    Map,
+    Extension,
+}
+
+impl Display for NodeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            NodeKind::Number => "Number",
+            NodeKind::Boolean => "Boolean",
+            NodeKind::Char => "Char",
+            NodeKind::String => "String",
+            NodeKind::DateTime => "DateTime",
+            NodeKind::Variant => "Variant",
+            NodeKind::HexByteData => "Bytes",
+            NodeKind::List => "List",
+            NodeKind::Tuple => "Tuple",
+            NodeKind::Object => "Object",
+            NodeKind::Map => "Map",
+            NodeKind::Extension => "Extension",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The location of a node in the source text, along with the locations
+/// of its child nodes, see `parse_from_str_with_ranges`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NodeRange {
+    /// The range from the first token to the last token of the node,
+    /// e.g. an object covers `{` through `}`.
+    pub range: Location,
+
+    /// The child nodes in the order of the source text, i.e. the items of
+    /// lists and tuples, the values of objects, the names and values of maps
+    /// (alternately), and the values of variants.
+    pub children: Vec<NodeRange>,
+}
+
+/// The comments of a document, see `parse_from_str_with_comments`
+/// and `print_to_string_with_comments`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Comments {
+    /// The comments attached to the nodes, the key is the indices of the
+    /// elements from the root to the node, e.g. `[]` is the root node and
+    /// `[1, 0]` is the first element of the second element of the root.
+    ///
+    /// The elements are the items of lists, the key-value pairs of objects
+    /// (and struct variants) and the name-value pairs of maps, the values
+    /// of tuples (and tuple variants) take an index but can not carry comments.
+    /// The name and value of a map entry take the sub-indices `0` and `1`,
+    /// e.g. `[0, 1, 0]` is the first element of the value of the first entry.
+    pub nodes: HashMap<Vec<usize>, NodeComments>,
+
+    /// The comments following the root node on the subsequent lines.
+    pub end: Vec<Comment>,
+}
+
+/// The comments attached to a node (or a key-value pair).
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct NodeComments {
+    /// The comments on the lines above the node.
+    pub leading: Vec<Comment>,
+
+    /// The comments following the node on the same line,
+    /// e.g. `id: 123 // the id`.
+    pub trailing: Vec<Comment>,
+
+    /// The comments after the last element of a list, object or map,
+    /// i.e. on the lines above the closing bracket.
+    pub dangling: Vec<Comment>,
+}
+
+/// The complexity metrics of a node tree, see `AsonNode::metrics`.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct NodeMetrics {
+    /// The total number of nodes, including the root node,
+    /// the names of maps and the values of variants.
+    pub node_count: usize,
+
+    /// The depth of the deepest node, the root node is at depth `1`.
+    pub max_depth: usize,
+
+    /// The number of nodes of each kind, the kinds which
+    /// do not appear are absent.
+    pub kind_counts: HashMap<NodeKind, usize>,
+}
+
+impl Number {
+    /// Converts the number to `f64`, note that the `i64` and `u64`
+    /// numbers which are larger than 2^53 may lose precision.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::I8(v) => *v as f64,
+            Number::U8(v) => *v as f64,
+            Number::I16(v) => *v as f64,
+            Number::U16(v) => *v as f64,
+            Number::I32(v) => *v as f64,
+            Number::U32(v) => *v as f64,
+            Number::I64(v) => *v as f64,
+            Number::U64(v) => *v as f64,
+            Number::I128(v) => *v as f64,
+            Number::U128(v) => *v as f64,
+            Number::F32(v) => *v as f64,
+            Number::F64(v) => *v,
+        }
+    }
+
+    /// Compares two numbers by value, the integers are compared exactly and
+    /// `NaN` is greater than any other number (including `Inf`) and equals to itself.
+    /// The numbers of the same value are ordered by the type, in the order
+    /// of `i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, f32, f64`.
+    pub fn total_cmp(&self, other: &Number) -> Ordering {
+        let by_value = match (self.as_integer(), other.as_integer()) {
+            (Some(left), Some(right)) => left.cmp(&right),
+            _ => {
+                let (left, right) = (self.to_f64(), other.to_f64());
+                match (left.is_nan(), right.is_nan()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    // `0.0` equals to `-0.0`
+                    (false, false) => left.partial_cmp(&right).unwrap(),
+                }
+            }
+        };
+
+        by_value.then_with(|| self.type_rank().cmp(&other.type_rank()))
+    }
+
+    fn as_integer(&self) -> Option<IntegerValue> {
+        let v = match self {
+            Number::I8(v) => *v as i128,
+            Number::U8(v) => *v as i128,
+            Number::I16(v) => *v as i128,
+            Number::U16(v) => *v as i128,
+            Number::I32(v) => *v as i128,
+            Number::U32(v) => *v as i128,
+            Number::I64(v) => *v as i128,
+            Number::U64(v) => *v as i128,
+            Number::I128(v) => *v,
+            Number::U128(v) => {
+                return Some(
+                    i128::try_from(*v).map_or(IntegerValue::Large(*v), IntegerValue::Signed),
+                )
+            }
+            Number::F32(_) | Number::F64(_) => return None,
+        };
+
+        Some(IntegerValue::Signed(v))
+    }
+
+    fn type_rank(&self) -> u8 {
+        match self {
+            Number::I8(_) => 0,
+            Number::U8(_) => 1,
+            Number::I16(_) => 2,
+            Number::U16(_) => 3,
+            Number::I32(_) => 4,
+            Number::U32(_) => 5,
+            Number::I64(_) => 6,
+            Number::U64(_) => 7,
+            Number::I128(_) => 8,
+            Number::U128(_) => 9,
+            Number::F32(_) => 10,
+            Number::F64(_) => 11,
+        }
+    }
+}
+
+// the value of integers of all types, the `u128` numbers which
+// are out of the range of `i128` are greater than any `Signed` value.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum IntegerValue {
+    Signed(i128),
+    Large(u128),
+}
+
+impl AsonNode {
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            AsonNode::Number(_) => NodeKind::Number,
+            AsonNode::Boolean(_) => NodeKind::Boolean,
+            AsonNode::Char(_) => NodeKind::Char,
+            AsonNode::String(_) => NodeKind::String,
+            AsonNode::DateTime(_) => NodeKind::DateTime,
+            AsonNode::Variant(_) => NodeKind::Variant,
+            AsonNode::HexByteData(_) => NodeKind::HexByteData,
+            AsonNode::List(_) => NodeKind::List,
+            AsonNode::Tuple(_) => NodeKind::Tuple,
+            AsonNode::Object(_) => NodeKind::Object,
+            AsonNode::Map(_) => NodeKind::Map,
+            AsonNode::Extension(..) => NodeKind::Extension,
+        }
+    }
+
+    /// Compares two nodes with a total ordering, e.g. for sorting a list
+    /// of nodes by `nodes.sort_by(AsonNode::total_cmp)`.
+    ///
+    /// The nodes of different kinds are ordered by the kind, in the order of
+    /// `Boolean < Number < Char < String < DateTime < HexByteData < Variant
+    /// < List < Tuple < Object < Map < Extension`.
+    ///
+    /// The nodes of the same kind are ordered naturally: numbers by value
+    /// (see `Number::total_cmp`, `NaN` is the last), strings and chars
+    /// lexicographically, date times chronologically, and the others
+    /// element by element (the key, then the value, for objects and maps).
+    pub fn total_cmp(&self, other: &AsonNode) -> Ordering {
+        match (self, other) {
+            (AsonNode::Number(left), AsonNode::Number(right)) => left.total_cmp(right),
+            (AsonNode::Boolean(left), AsonNode::Boolean(right)) => left.cmp(right),
+            (AsonNode::Char(left), AsonNode::Char(right)) => left.cmp(right),
+            (AsonNode::String(left), AsonNode::String(right)) => left.cmp(right),
+            (AsonNode::DateTime(left), AsonNode::DateTime(right)) => left.cmp(right),
+            (AsonNode::HexByteData(left), AsonNode::HexByteData(right)) => left.cmp(right),
+            (AsonNode::Variant(left), AsonNode::Variant(right)) => left
+                .type_name
+                .cmp(&right.type_name)
+                .then_with(|| left.member_name.cmp(&right.member_name))
+                .then_with(|| cmp_variant_values(&left.value, &right.value)),
+            (AsonNode::List(left), AsonNode::List(right))
+            | (AsonNode::Tuple(left), AsonNode::Tuple(right)) => cmp_nodes(left, right),
+            (AsonNode::Object(left), AsonNode::Object(right)) => cmp_key_value_pairs(left, right),
+            (AsonNode::Map(left), AsonNode::Map(right)) => cmp_slices(left, right, |l, r| {
+                l.name
+                    .total_cmp(&r.name)
+                    .then_with(|| l.value.total_cmp(&r.value))
+            }),
+            (AsonNode::Extension(left_name, left), AsonNode::Extension(right_name, right)) => {
+                left_name
+                    .cmp(right_name)
+                    .then_with(|| left.total_cmp(right))
+            }
+            _ => kind_rank(self.kind()).cmp(&kind_rank(other.kind())),
+        }
+    }
+
+    /// Gets the value of an object by key, it returns `None` if the key
+    /// does not exist or the node is not an object.
+    pub fn get(&self, key: &str) -> Option<&AsonNode> {
+        match self {
+            AsonNode::Object(kvps) => find_value(kvps, key),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the node is an object and has the key.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the key-value pairs if the node is an object.
+    pub fn as_object(&self) -> Option<&[KeyValuePair]> {
+        match self {
+            AsonNode::Object(kvps) => Some(kvps),
+            _ => None,
+        }
+    }
+
+    /// Returns the items if the node is a list.
+    pub fn as_list(&self) -> Option<&[AsonNode]> {
+        match self {
+            AsonNode::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Returns the items if the node is a tuple.
+    pub fn as_tuple(&self) -> Option<&[AsonNode]> {
+        match self {
+            AsonNode::Tuple(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes if the node is a byte data, i.e. `h"..."`.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            AsonNode::HexByteData(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the key-value pairs if the node is an object,
+    /// it yields nothing for the other kinds of node.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &AsonNode)> {
+        self.as_object()
+            .unwrap_or(&[])
+            .iter()
+            .map(|kvp| (kvp.key.as_str(), kvp.value.as_ref()))
+    }
+
+    /// Iterates over the items if the node is a list or a tuple,
+    /// it yields nothing for the other kinds of node.
+    pub fn items(&self) -> impl Iterator<Item = &AsonNode> {
+        self.as_list()
+            .or_else(|| self.as_tuple())
+            .unwrap_or(&[])
+            .iter()
+    }
+
+    /// Sorts the key-value pairs of objects (including struct variants) by key,
+    /// and the entries of maps by the serialized text of the name, recursively.
+    ///
+    /// The order of the items of lists and tuples is preserved.
+    pub fn sort_keys(&mut self) {
+        match self {
+            AsonNode::List(items) | AsonNode::Tuple(items) => {
+                items.iter_mut().for_each(AsonNode::sort_keys);
+            }
+            AsonNode::Object(kvps) => sort_key_value_pairs(kvps),
+            AsonNode::Map(nvps) => {
+                for nvp in nvps.iter_mut() {
+                    nvp.name.sort_keys();
+                    nvp.value.sort_keys();
+                }
+                nvps.sort_by_cached_key(|nvp| print_to_string(&nvp.name));
+            }
+            AsonNode::Variant(Variant { value, .. }) => match value {
+                VariantValue::Empty => {}
+                VariantValue::Value(v) => v.sort_keys(),
+                VariantValue::Tuple(items) => items.iter_mut().for_each(AsonNode::sort_keys),
+                VariantValue::Object(kvps) => sort_key_value_pairs(kvps),
+            },
+            AsonNode::Extension(_, v) => v.sort_keys(),
+            _ => {}
+        }
+    }
+
+    /// Counts the nodes (per kind) and measures the depth of the tree.
+    pub fn metrics(&self) -> NodeMetrics {
+        let mut metrics = NodeMetrics::default();
+        self.collect_metrics(1, &mut metrics);
+        metrics
+    }
+
+    fn collect_metrics(&self, depth: usize, metrics: &mut NodeMetrics) {
+        metrics.node_count += 1;
+        metrics.max_depth = metrics.max_depth.max(depth);
+        *metrics.kind_counts.entry(self.kind()).or_insert(0) += 1;
+
+        let depth = depth + 1;
+        match self {
+            AsonNode::List(items) | AsonNode::Tuple(items) => {
+                items
+                    .iter()
+                    .for_each(|item| item.collect_metrics(depth, metrics));
+            }
+            AsonNode::Object(kvps) => {
+                kvps.iter()
+                    .for_each(|kvp| kvp.value.collect_metrics(depth, metrics));
+            }
+            AsonNode::Map(nvps) => {
+                for nvp in nvps {
+                    nvp.name.collect_metrics(depth, metrics);
+                    nvp.value.collect_metrics(depth, metrics);
+                }
+            }
+            AsonNode::Variant(Variant { value, .. }) => match value {
+                VariantValue::Empty => {}
+                VariantValue::Value(v) => v.collect_metrics(depth, metrics),
+                VariantValue::Tuple(items) => {
+                    items
+                        .iter()
+                        .for_each(|item| item.collect_metrics(depth, metrics));
+                }
+                VariantValue::Object(kvps) => {
+                    kvps.iter()
+                        .for_each(|kvp| kvp.value.collect_metrics(depth, metrics));
+                }
+            },
+            AsonNode::Extension(_, v) => v.collect_metrics(depth, metrics),
+            _ => {}
+        }
+    }
+
+    /// Gets the descendant node by the path.
+    ///
+    /// The path consists of segments separated by dots, e.g. `foo.bar.0`:
+    ///
+    /// - a segment selects the value of an object (or a struct variant) by key,
+    ///   or the value of a map by the string name.
+    /// - a number segment selects the item of a list or a tuple (or a tuple variant)
+    ///   by index, the value of a single value variant is index `0`.
+    ///
+    /// An empty path selects the node itself.
+    pub fn get_path(&self, path: &str) -> Option<&AsonNode> {
+        self.walk_path(path).ok()
+    }
+
+    // walks through the path, returns the node and the segment
+    // where it stops if the path does not exist.
+    fn walk_path<'a, 'p>(&'a self, path: &'p str) -> Result<&'a AsonNode, (&'a AsonNode, &'p str)> {
+        if path.is_empty() {
+            return Ok(self);
+        }
+
+        let mut node = self;
+        for segment in path.split('.') {
+            node = node.get_child(segment).ok_or((node, segment))?;
+        }
+
+        Ok(node)
+    }
+
+    fn get_child(&self, segment: &str) -> Option<&AsonNode> {
+        match self {
+            AsonNode::Object(kvps) => find_value(kvps, segment),
+            AsonNode::Map(nvps) => nvps
+                .iter()
+                .find(|nvp| matches!(nvp.name.as_ref(), AsonNode::String(name) if name == segment))
+                .map(|nvp| nvp.value.as_ref()),
+            AsonNode::List(items) | AsonNode::Tuple(items) => {
+                items.get(segment.parse::<usize>().ok()?)
+            }
+            AsonNode::Variant(Variant { value, .. }) => match value {
+                VariantValue::Empty => None,
+                VariantValue::Value(v) => (segment == "0").then_some(v.as_ref()),
+                VariantValue::Tuple(items) => items.get(segment.parse::<usize>().ok()?),
+                VariantValue::Object(kvps) => find_value(kvps, segment),
+            },
+            _ => None,
+        }
+    }
+
+    // the same as `get_path`, but reports the segment which is not
+    // a valid index of list or tuple, e.g. the `x` of `items.x`.
+    fn get_path_or_error(&self, path: &str) -> Result<&AsonNode, AsonError> {
+        self.walk_path(path).map_err(|(node, segment)| {
+            let is_indexed = matches!(
+                node,
+                AsonNode::List(_)
+                    | AsonNode::Tuple(_)
+                    | AsonNode::Variant(Variant {
+                        value: VariantValue::Value(_) | VariantValue::Tuple(_),
+                        ..
+                    })
+            );
+
+            if is_indexed && segment.parse::<usize>().is_err() {
+                AsonError::Message(format!(
+                    "The segment \"{}\" of path \"{}\" is not a valid index.",
+                    segment, path
+                ))
+            } else {
+                AsonError::Message(format!("The path \"{}\" does not exist.", path))
+            }
+        })
+    }
+
+    /// Checks that the node at the path is a number within `[min, max]`.
+    pub fn validate_number_range(&self, path: &str, min: f64, max: f64) -> Result<(), AsonError> {
+        match self.get_path_or_error(path)? {
+            AsonNode::Number(number) => {
+                let v = number.to_f64();
+                if v >= min && v <= max {
+                    Ok(())
+                } else {
+                    Err(AsonError::Message(format!(
+                        "The number {} at path \"{}\" is out of range [{}, {}].",
+                        v, path, min, max
+                    )))
+                }
+            }
+            _ => Err(AsonError::Message(format!(
+                "Expect a \"Number\" at path \"{}\".",
+                path
+            ))),
+        }
+    }
+
+    /// Checks that the node at the path is an object which contains the key.
+    pub fn validate_required_key(&self, path: &str, key: &str) -> Result<(), AsonError> {
+        match self.get_path_or_error(path)? {
+            AsonNode::Object(kvps) => {
+                if kvps.iter().any(|kvp| kvp.key == key) {
+                    Ok(())
+                } else {
+                    Err(AsonError::Message(format!(
+                        "Missing the required key \"{}\" at path \"{}\".",
+                        key, path
+                    )))
+                }
+            }
+            _ => Err(AsonError::Message(format!(
+                "Expect an \"Object\" at path \"{}\".",
+                path
+            ))),
+        }
+    }
+
+    /// Checks that the node at the path is of the specified kind.
+    pub fn validate_kind(&self, path: &str, kind: NodeKind) -> Result<(), AsonError> {
+        let node = self.get_path_or_error(path)?;
+        if node.kind() == kind {
+            Ok(())
+        } else {
+            Err(AsonError::Message(format!(
+                "Expect a \"{}\" at path \"{}\", actual \"{}\".",
+                kind,
+                path,
+                node.kind()
+            )))
+        }
+    }
+}
+
+impl From<Vec<u8>> for AsonNode {
+    fn from(bytes: Vec<u8>) -> Self {
+        AsonNode::HexByteData(bytes)
+    }
+}
+
+impl From<&[u8]> for AsonNode {
+    fn from(bytes: &[u8]) -> Self {
+        AsonNode::HexByteData(bytes.to_vec())
+    }
+}
+
+/// The ordering follows `AsonNode::total_cmp`, except that the nodes
+/// containing `NaN` may be incomparable (i.e. `None`), which keeps it
+/// consistent with the IEEE 754 equality of `PartialEq`.
+impl PartialOrd for AsonNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.total_cmp(other) {
+            Ordering::Equal if self != other => None,
+            ordering => Some(ordering),
+        }
+    }
+}
+
+fn kind_rank(kind: NodeKind) -> u8 {
+    match kind {
+        NodeKind::Boolean => 0,
+        NodeKind::Number => 1,
+        NodeKind::Char => 2,
+        NodeKind::String => 3,
+        NodeKind::DateTime => 4,
+        NodeKind::HexByteData => 5,
+        NodeKind::Variant => 6,
+        NodeKind::List => 7,
+        NodeKind::Tuple => 8,
+        NodeKind::Object => 9,
+        NodeKind::Map => 10,
+        NodeKind::Extension => 11,
+    }
+}
+
+fn cmp_slices<T>(left: &[T], right: &[T], cmp: impl Fn(&T, &T) -> Ordering) -> Ordering {
+    left.iter()
+        .zip(right)
+        .map(|(l, r)| cmp(l, r))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| left.len().cmp(&right.len()))
+}
+
+fn cmp_nodes(left: &[AsonNode], right: &[AsonNode]) -> Ordering {
+    cmp_slices(left, right, AsonNode::total_cmp)
+}
+
+fn cmp_key_value_pairs(left: &[KeyValuePair], right: &[KeyValuePair]) -> Ordering {
+    cmp_slices(left, right, |l, r| {
+        l.key.cmp(&r.key).then_with(|| l.value.total_cmp(&r.value))
+    })
+}
+
+fn cmp_variant_values(left: &VariantValue, right: &VariantValue) -> Ordering {
+    match (left, right) {
+        (VariantValue::Empty, VariantValue::Empty) => Ordering::Equal,
+        (VariantValue::Value(l), VariantValue::Value(r)) => l.total_cmp(r),
+        (VariantValue::Tuple(l), VariantValue::Tuple(r)) => cmp_nodes(l, r),
+        (VariantValue::Object(l), VariantValue::Object(r)) => cmp_key_value_pairs(l, r),
+        _ => {
+            let rank = |value: &VariantValue| match value {
+                VariantValue::Empty => 0,
+                VariantValue::Value(_) => 1,
+                VariantValue::Tuple(_) => 2,
+                VariantValue::Object(_) => 3,
+            };
+            rank(left).cmp(&rank(right))
+        }
+    }
+}
+
+fn sort_key_value_pairs(kvps: &mut [KeyValuePair]) {
+    for kvp in kvps.iter_mut() {
+        kvp.value.sort_keys();
+    }
+    kvps.sort_by(|left, right| left.key.cmp(&right.key));
+}
+
+fn find_value<'a>(kvps: &'a [KeyValuePair], key: &str) -> Option<&'a AsonNode> {
+    kvps.iter()
+        .find(|kvp| kvp.key == key)
+        .map(|kvp| kvp.value.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use std::{cmp::Ordering, collections::HashMap};
+
+    use crate::{parser::parse_from_str, printer::print_to_string, AsonError};
+
+    use super::{AsonNode, KeyValuePair, NodeKind, NodeMetrics, Number, Variant};
+
+    #[test]
+    fn test_variant_accessors() {
+        let v0 = Variant::new("Option", "None");
+        assert_eq!(v0.type_name(), "Option");
+        assert_eq!(v0.member_name(), "None");
+        assert_eq!(v0.as_unit(), Some(()));
+        assert_eq!(v0.as_newtype(), None);
+        assert_eq!(v0.as_tuple(), None);
+        assert_eq!(v0.as_object(), None);
+
+        let v1 = Variant::with_value("Option", "Some", AsonNode::Number(Number::I32(11)));
+        assert_eq!(v1.member_name(), "Some");
+        assert_eq!(v1.as_unit(), None);
+        assert_eq!(v1.as_newtype(), Some(&AsonNode::Number(Number::I32(11))));
+        assert_eq!(v1.as_tuple(), None);
+
+        let v2 = Variant::with_tuple(
+            "Color",
+            "RGB",
+            vec![
+                AsonNode::Number(Number::U8(255)),
+                AsonNode::Number(Number::U8(127)),
+            ],
+        );
+        assert_eq!(v2.type_name(), "Color");
+        assert_eq!(
+            v2.as_tuple(),
+            Some(
+                &[
+                    AsonNode::Number(Number::U8(255)),
+                    AsonNode::Number(Number::U8(127)),
+                ][..]
+            )
+        );
+        assert_eq!(v2.as_newtype(), None);
+        assert_eq!(v2.as_object(), None);
+
+        let v3 = Variant::with_object(
+            "Shape",
+            "Rect",
+            vec![KeyValuePair::new(
+                "width",
+                AsonNode::Number(Number::I32(200)),
+            )],
+        );
+        assert_eq!(v3.member_name(), "Rect");
+        assert_eq!(
+            v3.as_object(),
+            Some(
+                &[KeyValuePair::new(
+                    "width",
+                    AsonNode::Number(Number::I32(200))
+                )][..]
+            )
+        );
+        assert_eq!(v3.as_unit(), None);
+        assert_eq!(v3.as_tuple(), None);
+    }
+
+    #[test]
+    fn test_get_path() {
+        let node = parse_from_str(
+            r#"{
+                id: 123
+                orders: [11, (13, "foo")]
+                shape: Shape::Rect{width: 17, height: 19}
+                option: Option::Some(23)
+                names: ["a": 29]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(node.get_path(""), Some(&node));
+        assert_eq!(
+            node.get_path("id"),
+            Some(&AsonNode::Number(Number::I32(123)))
+        );
+        assert_eq!(
+            node.get_path("orders.0"),
+            Some(&AsonNode::Number(Number::I32(11)))
+        );
+        assert_eq!(
+            node.get_path("orders.1.1"),
+            Some(&AsonNode::String("foo".to_owned()))
+        );
+        assert_eq!(
+            node.get_path("shape.height"),
+            Some(&AsonNode::Number(Number::I32(19)))
+        );
+        assert_eq!(
+            node.get_path("option.0"),
+            Some(&AsonNode::Number(Number::I32(23)))
+        );
+        assert_eq!(
+            node.get_path("names.a"),
+            Some(&AsonNode::Number(Number::I32(29)))
+        );
+
+        assert_eq!(node.get_path("name"), None);
+        assert_eq!(node.get_path("orders.2"), None);
+        assert_eq!(node.get_path("id.0"), None);
+    }
+
+    #[test]
+    fn test_get_and_accessors() {
+        let node = parse_from_str(
+            r#"{
+                id: 123
+                orders: [13, 17]
+                point: (19, 23)
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(node.get("id"), Some(&AsonNode::Number(Number::I32(123))));
+        assert!(node.contains_key("orders"));
+
+        // missing key
+        assert_eq!(node.get("name"), None);
+        assert!(!node.contains_key("name"));
+
+        assert_eq!(node.as_object().map(|kvps| kvps.len()), Some(3));
+        assert_eq!(
+            node.get("orders").and_then(|n| n.as_list()),
+            Some(
+                &[
+                    AsonNode::Number(Number::I32(13)),
+                    AsonNode::Number(Number::I32(17))
+                ][..]
+            )
+        );
+        assert_eq!(
+            node.get("point").and_then(|n| n.as_tuple()),
+            Some(
+                &[
+                    AsonNode::Number(Number::I32(19)),
+                    AsonNode::Number(Number::I32(23))
+                ][..]
+            )
+        );
+
+        // entries and items
+        assert_eq!(
+            node.entries().map(|(key, _)| key).collect::<Vec<_>>(),
+            vec!["id", "orders", "point"]
+        );
+        assert_eq!(
+            node.entries().next(),
+            Some(("id", &AsonNode::Number(Number::I32(123))))
+        );
+
+        let orders = node.get("orders").unwrap();
+        assert_eq!(
+            orders.items().collect::<Vec<_>>(),
+            vec![
+                &AsonNode::Number(Number::I32(13)),
+                &AsonNode::Number(Number::I32(17))
+            ]
+        );
+        assert_eq!(node.get("point").unwrap().items().count(), 2);
+
+        // non-object
+        assert_eq!(orders.get("0"), None);
+        assert!(!orders.contains_key("0"));
+        assert_eq!(orders.as_object(), None);
+        assert_eq!(orders.as_tuple(), None);
+        assert_eq!(node.as_list(), None);
+
+        // mismatched kinds yield nothing
+        assert_eq!(orders.entries().count(), 0);
+        assert_eq!(node.items().count(), 0);
+        assert_eq!(AsonNode::Boolean(true).items().count(), 0);
+    }
+
+    #[test]
+    fn test_byte_data_conversions() {
+        let node = AsonNode::from(vec![0x11_u8, 0x13, 0xab]);
+        assert_eq!(node, AsonNode::HexByteData(vec![0x11, 0x13, 0xab]));
+        assert_eq!(print_to_string(&node), r#"h"11 13 ab""#);
+        assert_eq!(node.as_bytes(), Some(&[0x11_u8, 0x13, 0xab][..]));
+
+        // from slice
+        let bytes: &[u8] = &[0x17, 0x19];
+        assert_eq!(AsonNode::from(bytes).as_bytes(), Some(bytes));
+
+        // round trip
+        let parsed = parse_from_str(&print_to_string(&node)).unwrap();
+        assert_eq!(parsed.as_bytes(), node.as_bytes());
+
+        // empty byte data
+        assert_eq!(AsonNode::from(vec![]).as_bytes(), Some(&[][..]));
+
+        // non-byte data
+        assert_eq!(AsonNode::String("11".to_owned()).as_bytes(), None);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let node = parse_from_str(
+            r#"
+            {
+                id:123
+                name:"hello"
+                orders: [
+                    (1, "foo", true)
+                    (2, "bar", false)
+                ]
+                group: {
+                    active: true
+                    permissions:[
+                        {number:11, title: "read"}
+                        {number:13, title: "write"}
+                    ]
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            node.metrics(),
+            NodeMetrics {
+                node_count: 21,
+                max_depth: 5,
+                kind_counts: HashMap::from([
+                    (NodeKind::Object, 4),
+                    (NodeKind::Number, 5),
+                    (NodeKind::String, 5),
+                    (NodeKind::Boolean, 3),
+                    (NodeKind::List, 2),
+                    (NodeKind::Tuple, 2),
+                ]),
+            }
+        );
+
+        // map names and variant values are counted
+        let node = parse_from_str(r#"[1: Option::Some(true)]"#).unwrap();
+        let metrics = node.metrics();
+        assert_eq!(metrics.node_count, 4);
+        assert_eq!(metrics.max_depth, 3);
+        assert_eq!(metrics.kind_counts.get(&NodeKind::Variant), Some(&1));
+
+        // a single value
+        assert_eq!(
+            AsonNode::Boolean(true).metrics(),
+            NodeMetrics {
+                node_count: 1,
+                max_depth: 1,
+                kind_counts: HashMap::from([(NodeKind::Boolean, 1)]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut node = parse_from_str(
+            r#"{
+                name: "foo"
+                id: 123
+                orders: [
+                    {qty: 3, code: "b"}
+                    {qty: 2, code: "a"}
+                ]
+                point: (19, 11)
+                shape: Shape::Rect{width: 17, height: 19}
+                names: [
+                    "z": {y: 2, x: 1}
+                    "a": 29
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        node.sort_keys();
+
+        let expect = parse_from_str(
+            r#"{
+                id: 123
+                name: "foo"
+                names: [
+                    "a": 29
+                    "z": {x: 1, y: 2}
+                ]
+                orders: [
+                    {code: "b", qty: 3}
+                    {code: "a", qty: 2}
+                ]
+                point: (19, 11)
+                shape: Shape::Rect{height: 19, width: 17}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(node, expect);
+    }
+
+    #[test]
+    fn test_total_cmp() {
+        let mut nodes = parse_from_str(
+            r#"[
+                "foo"
+                NaN
+                [2, 1]
+                'a'
+                (1, 2)
+                true
+                17_u8
+                -Inf
+                "bar"
+                {id: 1}
+                3.5
+                [1, 2, 3]
+                Option::None
+                false
+                d"2024-03-16"
+                -11
+                [1, 2]
+            ]"#,
+        )
+        .unwrap()
+        .as_list()
+        .unwrap()
+        .to_vec();
+
+        nodes.sort_by(AsonNode::total_cmp);
+
+        let expect = parse_from_str(
+            r#"[
+                false
+                true
+                -Inf
+                -11
+                3.5
+                17_u8
+                NaN
+                'a'
+                "bar"
+                "foo"
+                d"2024-03-16"
+                Option::None
+                [1, 2]
+                [1, 2, 3]
+                [2, 1]
+                (1, 2)
+                {id: 1}
+            ]"#,
+        )
+        .unwrap();
+
+        // compare by `total_cmp` since `NaN != NaN`
+        let expect_nodes = expect.as_list().unwrap();
+        assert_eq!(nodes.len(), expect_nodes.len());
+        assert!(nodes
+            .iter()
+            .zip(expect_nodes)
+            .all(|(left, right)| left.total_cmp(right).is_eq()));
+
+        // NaN is the last number
+        let nan = AsonNode::Number(Number::F64(f64::NAN));
+        let inf = AsonNode::Number(Number::F64(f64::INFINITY));
+        assert!(nan.total_cmp(&inf).is_gt());
+        assert!(nan.total_cmp(&nan).is_eq());
+
+        // integers of different types are compared by value
+        assert!(AsonNode::Number(Number::U64(u64::MAX))
+            .total_cmp(&AsonNode::Number(Number::I64(i64::MAX)))
+            .is_gt());
+        assert!(AsonNode::Number(Number::I8(-1))
+            .total_cmp(&AsonNode::Number(Number::U8(0)))
+            .is_lt());
+        assert!(AsonNode::Number(Number::U128(u128::MAX))
+            .total_cmp(&AsonNode::Number(Number::I128(i128::MAX)))
+            .is_gt());
+        assert!(AsonNode::Number(Number::I128(i128::MIN))
+            .total_cmp(&AsonNode::Number(Number::I64(i64::MIN)))
+            .is_lt());
+
+        // partial ordering
+        assert!(AsonNode::String("a".to_owned()) < AsonNode::String("b".to_owned()));
+        assert!(AsonNode::Boolean(true) < AsonNode::Char('a'));
+        assert_eq!(nan.partial_cmp(&nan), None);
+        assert_eq!(
+            AsonNode::Number(Number::F64(0.0)).partial_cmp(&AsonNode::Number(Number::F64(-0.0))),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            AsonNode::Number(Number::I32(1)).partial_cmp(&AsonNode::Number(Number::I64(1))),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let node = parse_from_str(
+            r#"{
+                server: {
+                    host: "localhost"
+                    port: 80800
+                }
+                timeout: 1.5
+            }"#,
+        )
+        .unwrap();
+
+        // number range
+        assert!(node.validate_number_range("timeout", 0.0, 60.0).is_ok());
+        assert_eq!(
+            node.validate_number_range("server.port", 1.0, 65535.0),
+            Err(AsonError::Message(
+                "The number 80800 at path \"server.port\" is out of range [1, 65535].".to_owned()
+            ))
+        );
+        assert!(node.validate_number_range("server.host", 1.0, 2.0).is_err());
+
+        // required key
+        assert!(node.validate_required_key("server", "host").is_ok());
+        assert_eq!(
+            node.validate_required_key("server", "user"),
+            Err(AsonError::Message(
+                "Missing the required key \"user\" at path \"server\".".to_owned()
+            ))
+        );
+
+        // kind
+        assert!(node.validate_kind("server.host", NodeKind::String).is_ok());
+        assert!(node.validate_kind("server", NodeKind::Object).is_ok());
+        assert_eq!(
+            node.validate_kind("timeout", NodeKind::String),
+            Err(AsonError::Message(
+                "Expect a \"String\" at path \"timeout\", actual \"Number\".".to_owned()
+            ))
+        );
+
+        // path does not exist
+        assert_eq!(
+            node.validate_kind("server.user", NodeKind::String),
+            Err(AsonError::Message(
+                "The path \"server.user\" does not exist.".to_owned()
+            ))
+        );
+
+        // invalid index
+        let node = parse_from_str(r#"{items: [1.5, 2.5]}"#).unwrap();
+        assert!(node.validate_kind("items.1", NodeKind::Number).is_ok());
+        assert_eq!(
+            node.validate_kind("items.x", NodeKind::Number),
+            Err(AsonError::Message(
+                "The segment \"x\" of path \"items.x\" is not a valid index.".to_owned()
+            ))
+        );
+        assert_eq!(
+            node.validate_kind("items.2", NodeKind::Number),
+            Err(AsonError::Message(
+                "The path \"items.2\" does not exist.".to_owned()
+            ))
+        );
+    }
 }
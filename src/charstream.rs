@@ -11,6 +11,7 @@ where
     R: Read,
 {
     bufreader: BufReader<&'a mut R>,
+    is_start: bool,
 }
 
 impl<'a, R> CharStream<'a, R>
@@ -20,6 +21,7 @@ where
     pub fn new(reader: &'a mut R) -> Self {
         Self {
             bufreader: BufReader::new(reader),
+            is_start: true,
         }
     }
 }
@@ -172,6 +174,16 @@ where
     type Item = char;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.is_start {
+            self.is_start = false;
+
+            // skip the UTF-8 BOM (bytes `EF BB BF`) at the start of stream
+            match self.read_char() {
+                Some('\u{feff}') => {}
+                other => return other,
+            }
+        }
+
         self.read_char()
     }
 }
@@ -206,5 +218,21 @@ mod tests {
             assert_eq!(charstream.next(), Some('c'));
             assert_eq!(charstream.next(), None);
         }
+
+        // the leading BOM is skipped
+        {
+            let mut bytes = b"\xef\xbb\xbfa\xef\xbb\xbf" as &[u8];
+            let mut charstream = CharStream::new(&mut bytes);
+
+            assert_eq!(charstream.next(), Some('a'));
+            assert_eq!(charstream.next(), Some('\u{feff}'));
+            assert_eq!(charstream.next(), None);
+        }
+
+        {
+            let mut bytes = b"\xef\xbb\xbf" as &[u8];
+            let mut charstream = CharStream::new(&mut bytes);
+            assert_eq!(charstream.next(), None);
+        }
     }
 }
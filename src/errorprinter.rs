@@ -69,10 +69,20 @@ fn calculate_snippet_range(
     }
 }
 
+// the number of columns which the char occupies in the snippet.
+fn display_width(c: char, tab_width: usize) -> usize {
+    if c == '\t' {
+        tab_width
+    } else {
+        1
+    }
+}
+
 fn generate_snippet_and_indented_detail(
     chars: &mut dyn Iterator<Item = char>,
     snippet_range: &SnippetRange,
     detail: &str,
+    tab_width: usize,
 ) -> (String, String) {
     // build snippet
     let mut snippet = String::new();
@@ -82,12 +92,14 @@ fn generate_snippet_and_indented_detail(
     }
     let selection_chars = chars
         .skip(snippet_range.selection_start)
-        .take(snippet_range.selection_length);
+        .take(snippet_range.selection_length)
+        .collect::<Vec<char>>();
     let selection_string = selection_chars
+        .iter()
         .map(|c| match c {
-            '\n' => ' ',
-            '\t' => ' ',
-            _ => c,
+            '\n' => " ".to_owned(),
+            '\t' => " ".repeat(tab_width),
+            _ => c.to_string(),
         })
         .collect::<String>();
     snippet.push_str(&selection_string);
@@ -101,10 +113,25 @@ fn generate_snippet_and_indented_detail(
     if snippet_range.prefix {
         indented_detail.push_str("   ");
     }
-    indented_detail.push_str(&" ".repeat(snippet_range.offset));
+
+    // the offset and length in columns, since the tab may occupy more than one column.
+    let offset = selection_chars
+        .iter()
+        .take(snippet_range.offset)
+        .map(|c| display_width(*c, tab_width))
+        .sum::<usize>()
+        + snippet_range.offset.saturating_sub(selection_chars.len());
+    let length = selection_chars
+        .iter()
+        .skip(snippet_range.offset)
+        .take(snippet_range.length)
+        .map(|c| display_width(*c, tab_width))
+        .sum::<usize>();
+
+    indented_detail.push_str(&" ".repeat(offset));
     indented_detail.push('^');
     if snippet_range.length > 0 {
-        indented_detail.push_str(&"^".repeat(snippet_range.length - 1));
+        indented_detail.push_str(&"^".repeat(length.max(1) - 1));
     } else {
         indented_detail.push_str("____");
     }
@@ -116,6 +143,16 @@ fn generate_snippet_and_indented_detail(
 
 impl AsonError {
     pub fn with_source(&self, source: &str) -> String {
+        self.with_source_and_tab_width(source, 1)
+    }
+
+    /// Prints the human readable error message with the source, and each tab
+    /// is expanded to `tab_width` columns, so that the caret aligns with the
+    /// source text as it is displayed in editors.
+    ///
+    /// The column in the message is also the display column, while the
+    /// `Location` of the error is always counted in chars.
+    pub fn with_source_and_tab_width(&self, source: &str, tab_width: usize) -> String {
         // print human readable error message with the source
 
         let source_total_length = source.chars().count();
@@ -131,25 +168,44 @@ impl AsonError {
 
         match self {
             AsonError::Message(msg) => msg.to_owned(),
+            AsonError::Io(..) => self.to_string(),
             AsonError::UnexpectedEndOfDocument(detail) => {
                 let msg = "Unexpected to reach the end of document.";
                 let snippet_range =
                     calculate_snippet_range(source_total_length, 0, source_total_length);
-                let (snippet, indented_detail) =
-                    generate_snippet_and_indented_detail(&mut chars, &snippet_range, detail);
+                let (snippet, indented_detail) = generate_snippet_and_indented_detail(
+                    &mut chars,
+                    &snippet_range,
+                    detail,
+                    tab_width,
+                );
                 format!("{}\n{}\n{}", msg, snippet, indented_detail)
             }
             AsonError::MessageWithLocation(detail, location) => {
+                // the tabs between the start of line and the location
+                let line_start = location.index.saturating_sub(location.column);
+                let tab_count = source
+                    .chars()
+                    .skip(line_start)
+                    .take(location.index - line_start)
+                    .filter(|c| *c == '\t')
+                    .count();
+                let display_column = location.column + tab_count * (tab_width.max(1) - 1);
+
                 let msg = format!(
                     "Error at line: {}, column: {}",
                     location.line + 1,
-                    location.column + 1
+                    display_column + 1
                 );
 
                 let snippet_range =
                     calculate_snippet_range(location.index, location.length, source_total_length);
-                let (snippet, indented_detail) =
-                    generate_snippet_and_indented_detail(&mut chars, &snippet_range, detail);
+                let (snippet, indented_detail) = generate_snippet_and_indented_detail(
+                    &mut chars,
+                    &snippet_range,
+                    detail,
+                    tab_width,
+                );
                 format!("{}\n{}\n{}", msg, snippet, indented_detail)
             }
         }
@@ -447,4 +503,27 @@ mod tests {
 |                                            ^____ abcde"#
         );
     }
+
+    #[test]
+    fn test_error_with_source_and_tab_width() {
+        // "{\n\t\tid: 1x\n}"
+        //  0 1 2 3456789 0 1    // index
+        let source = "{\n\t\tid: 1x\n}";
+        let error =
+            AsonError::MessageWithLocation("abcde".to_owned(), Location::new_range(8, 1, 6, 2));
+
+        assert_eq!(
+            error.with_source(source),
+            "Error at line: 2, column: 7
+| {   id: 1x }
+|         ^^ abcde"
+        );
+
+        assert_eq!(
+            error.with_source_and_tab_width(source, 4),
+            "Error at line: 2, column: 13
+| {         id: 1x }
+|               ^^ abcde"
+        );
+    }
 }
@@ -12,22 +12,127 @@ use crate::{
     charwithposition::CharWithPosition,
     location::Location,
     peekableiter::PeekableIter,
-    token::{Comment, NumberToken, NumberType, Token, TokenWithRange},
+    token::{
+        Comment, CommentStyle, FloatType, IntegerType, LineCommentOpener, NumberToken, NumberType,
+        StringStyle, Token, TokenWithRange,
+    },
     AsonError,
 };
 
+/// The default maximum number of digits of a numeric literal.
+pub const DEFAULT_MAX_NUMBER_DIGITS: usize = 512;
+
+/// Checks whether the text can be written as a bare identifier, e.g. the key
+/// of object, the identifiers which are lexed as other tokens (e.g. `true`
+/// and `NaN`) are excluded.
+pub(crate) fn is_valid_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    let is_valid_first_char = matches!(
+        chars.next(),
+        Some('a'..='z' | 'A'..='Z' | '_' | '\u{a0}'..='\u{d7ff}' | '\u{e000}'..='\u{10ffff}')
+    );
+
+    is_valid_first_char
+        && chars.all(|c| {
+            matches!(
+                c,
+                '0'..='9'
+                    | 'a'..='z'
+                    | 'A'..='Z'
+                    | '_'
+                    | '\u{a0}'..='\u{d7ff}'
+                    | '\u{e000}'..='\u{10ffff}'
+            )
+        })
+        && !matches!(
+            s,
+            "true" | "false" | "NaN" | "NaN_f32" | "NaN_f64" | "Inf" | "Inf_f32" | "Inf_f64"
+        )
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LexerOptions {
+    pub comment_style: CommentStyle,
+
+    /// Reject the null char (`'\0'`, `'\u{0}'` or a literal NUL)
+    /// in chars and strings, for consumers that can not handle
+    /// interior nulls, e.g. C strings through FFI.
+    pub forbid_interior_nulls: bool,
+
+    /// The type of the integer literals without type suffix.
+    pub default_integer_type: IntegerType,
+
+    /// The type of the floating-point literals without type suffix.
+    pub default_float_type: FloatType,
+
+    /// Report an error when a numeric literal has more digits than this
+    /// count (the underscores, signs and type suffix are not counted),
+    /// since parsing a very long literal is slow and pointless.
+    pub max_number_digits: usize,
+
+    /// Treat the shebang line (e.g. `#!/usr/bin/env ason`) as a line comment,
+    /// it is only recognized at the very beginning of the document.
+    pub allow_shebang: bool,
+
+    /// Treat the document marker line (`---`, e.g. the separator of the
+    /// YAML-style front matter) as a line comment, it is only recognized
+    /// at the very beginning of the document.
+    pub allow_document_marker: bool,
+
+    /// Keep the unknown escape sequences of strings verbatim instead
+    /// of reporting an error, e.g. `"\q"` is lexed as `\q` (the backslash is
+    /// kept as well). The escape sequences of chars are not affected.
+    pub lenient_escapes: bool,
+
+    /// Lex the char as `Token::DocumentSeparator`, it is the custom separator
+    /// between top-level documents, see `DocumentSeparator::Custom`.
+    /// Only the char which has no other meaning in ASON is recognized, e.g. `;`.
+    pub document_separator: Option<char>,
+}
+
+impl Default for LexerOptions {
+    fn default() -> Self {
+        Self {
+            comment_style: CommentStyle::default(),
+            forbid_interior_nulls: false,
+            default_integer_type: IntegerType::default(),
+            default_float_type: FloatType::default(),
+            max_number_digits: DEFAULT_MAX_NUMBER_DIGITS,
+            allow_shebang: false,
+            allow_document_marker: false,
+            lenient_escapes: false,
+            document_separator: None,
+        }
+    }
+}
+
 pub struct Lexer<'a> {
     upstream: &'a mut PeekableIter<'a, CharWithPosition>,
     last_position: Location,
     saved_positions: Vec<Location>,
+    options: LexerOptions,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(upstream: &'a mut PeekableIter<'a, CharWithPosition>) -> Self {
+        Self::with_options(upstream, LexerOptions::default())
+    }
+
+    pub fn with_options(
+        upstream: &'a mut PeekableIter<'a, CharWithPosition>,
+        options: LexerOptions,
+    ) -> Self {
+        debug_assert!(
+            upstream.capacity() >= LEXER_PEEK_CHAR_MAX_COUNT,
+            "The capacity of the upstream of lexer is too small."
+        );
+
         Self {
             upstream,
             last_position: Location::new_position(/*0,*/ 0, 0, 0),
             saved_positions: vec![],
+            options,
         }
     }
 
@@ -212,6 +317,14 @@ impl Lexer<'_> {
                     1,
                 ))
             }
+            '-' if self.options.allow_document_marker
+                && self.peek_char_and_equals(1, '-')
+                && self.peek_char_and_equals(2, '-')
+                && self.peek_position(0).unwrap().index == 0 =>
+            {
+                // document marker line
+                self.lex_line_comment(LineCommentOpener::DocumentMarker)
+            }
             '-' => {
                 self.next_char(); // consule '-'
 
@@ -221,6 +334,15 @@ impl Lexer<'_> {
                     1,
                 ))
             }
+            '@' => {
+                self.next_char(); // consule '@'
+
+                Ok(TokenWithRange::from_position_and_length(
+                    Token::At,
+                    &self.last_position,
+                    1,
+                ))
+            }
             '0'..='9' => {
                 // number
                 self.lex_number()
@@ -257,7 +379,18 @@ impl Lexer<'_> {
             }
             '/' if self.peek_char_and_equals(1, '/') => {
                 // line comment
-                self.lex_line_comment()
+                self.lex_line_comment(LineCommentOpener::DoubleSlash)
+            }
+            '#' if self.options.allow_shebang
+                && self.peek_char_and_equals(1, '!')
+                && self.peek_position(0).unwrap().index == 0 =>
+            {
+                // shebang line
+                self.lex_line_comment(LineCommentOpener::Shebang)
+            }
+            '#' if self.options.comment_style == CommentStyle::Hash => {
+                // hash style line comment
+                self.lex_line_comment(LineCommentOpener::Hash)
             }
             '/' if self.peek_char_and_equals(1, '*') => {
                 // block comment
@@ -267,6 +400,15 @@ impl Lexer<'_> {
                 // identifier/name/keyword
                 self.lex_identifier()
             }
+            current_char if Some(*current_char) == self.options.document_separator => {
+                self.next_char(); // consume the separator
+
+                Ok(TokenWithRange::from_position_and_length(
+                    Token::DocumentSeparator,
+                    &self.last_position,
+                    1,
+                ))
+            }
             current_char => Err(AsonError::MessageWithLocation(
                 format!("Unexpected char '{}'.", current_char),
                 *self.peek_position(0).unwrap(),
@@ -340,6 +482,14 @@ impl Lexer<'_> {
                     // terminator chars
                     break;
                 }
+                '#' if self.options.comment_style == CommentStyle::Hash => {
+                    // the hash style line comment is also a terminator
+                    break;
+                }
+                _ if self.options.document_separator == Some(*current_char) => {
+                    // the custom document separator is also a terminator
+                    break;
+                }
                 _ => {
                     return Err(AsonError::MessageWithLocation(
                         format!("Invalid char '{}' for identifier.", current_char),
@@ -361,9 +511,16 @@ impl Lexer<'_> {
             match name_string.as_str() {
                 "true" => Token::Boolean(true),
                 "false" => Token::Boolean(false),
-                "NaN" | "NaN_f64" => Token::Number(NumberToken::F64(f64::NAN)), // the default floating-point type is f64
+                // the default floating-point type is f64 (or f32 if it is configured)
+                "NaN" if self.options.default_float_type == FloatType::F32 => {
+                    Token::Number(NumberToken::F32(f32::NAN))
+                }
+                "Inf" if self.options.default_float_type == FloatType::F32 => {
+                    Token::Number(NumberToken::F32(f32::INFINITY))
+                }
+                "NaN" | "NaN_f64" => Token::Number(NumberToken::F64(f64::NAN)),
                 "NaN_f32" => Token::Number(NumberToken::F32(f32::NAN)),
-                "Inf" | "Inf_f64" => Token::Number(NumberToken::F64(f64::INFINITY)), // the default floating-point type is f64
+                "Inf" | "Inf_f64" => Token::Number(NumberToken::F64(f64::INFINITY)),
                 "Inf_f32" => Token::Number(NumberToken::F32(f32::INFINITY)),
                 _ => Token::Identifier(name_string),
             }
@@ -381,7 +538,10 @@ impl Lexer<'_> {
 
         if self.peek_char_and_equals(0, '0') && self.peek_char_and_equals(1, 'b') {
             // '0b...'
-            self.lex_number_binary()
+            self.lex_number_with_radix(2, "binary")
+        } else if self.peek_char_and_equals(0, '0') && self.peek_char_and_equals(1, 'o') {
+            // '0o...'
+            self.lex_number_with_radix(8, "octal")
         } else if self.peek_char_and_equals(0, '0') && self.peek_char_and_equals(1, 'x') {
             // '0x...'
             self.lex_number_hex()
@@ -403,6 +563,7 @@ impl Lexer<'_> {
         let mut num_type: Option<NumberType> = None; // "_ixx", "_uxx", "_fxx"
         let mut found_point = false; // to indicated whether char '.' is found
         let mut found_e = false; // to indicated whether char 'e' is found
+        let mut digit_count = 0;
 
         // samples:
         //
@@ -418,6 +579,8 @@ impl Lexer<'_> {
             match current_char {
                 '0'..='9' => {
                     // valid digits for decimal number
+                    digit_count += 1;
+                    self.check_number_digits(digit_count)?;
                     num_string.push(*current_char);
 
                     self.next_char(); // consume digit
@@ -462,6 +625,14 @@ impl Lexer<'_> {
                     // terminator chars
                     break;
                 }
+                '#' if self.options.comment_style == CommentStyle::Hash => {
+                    // the hash style line comment is also a terminator
+                    break;
+                }
+                _ if self.options.document_separator == Some(*current_char) => {
+                    // the custom document separator is also a terminator
+                    break;
+                }
                 _ => {
                     return Err(AsonError::MessageWithLocation(
                         format!("Invalid char '{}' for decimal number.", current_char),
@@ -491,6 +662,17 @@ impl Lexer<'_> {
             &self.last_position,
         );
 
+        // the floating-point number without type suffix is f32
+        // if it is configured.
+        let num_type = match num_type {
+            None if (found_point || found_e)
+                && self.options.default_float_type == FloatType::F32 =>
+            {
+                Some(NumberType::F32)
+            }
+            _ => num_type,
+        };
+
         let num_token: NumberToken = if let Some(nt) = num_type {
             // numbers with explicit type
             match nt {
@@ -574,6 +756,26 @@ impl Lexer<'_> {
 
                     NumberToken::U64(v)
                 }
+                NumberType::I128 => {
+                    let v = num_string.parse::<u128>().map_err(|_| {
+                        AsonError::MessageWithLocation(
+                            format!("Can not convert \"{}\" to i128 integer number.", num_string),
+                            num_range,
+                        )
+                    })?;
+
+                    NumberToken::I128(v)
+                }
+                NumberType::U128 => {
+                    let v = num_string.parse::<u128>().map_err(|_| {
+                        AsonError::MessageWithLocation(
+                            format!("Can not convert \"{}\" to u128 integer number.", num_string),
+                            num_range,
+                        )
+                    })?;
+
+                    NumberToken::U128(v)
+                }
                 NumberType::F32 => {
                     let v = num_string.parse::<f32>().map_err(|_| {
                         AsonError::MessageWithLocation(
@@ -640,21 +842,44 @@ impl Lexer<'_> {
 
             NumberToken::F64(v)
         } else {
-            // the default integer number type is i32
-
-            let v = num_string.parse::<u32>().map_err(|_| {
-                AsonError::MessageWithLocation(
-                    format!("Can not convert \"{}\" to i32 integer number.", num_string,),
-                    num_range,
-                )
-            })?;
-
-            NumberToken::I32(v)
+            // the default integer number type is i32 (or i64 if it is configured)
+            self.lex_default_integer(&num_string, 10, num_range)?
         };
 
         Ok(TokenWithRange::new(Token::Number(num_token), num_range))
     }
 
+    // converts the integer literal without type suffix to the default integer type.
+    fn lex_default_integer(
+        &self,
+        num_string: &str,
+        radix: u32,
+        num_range: Location,
+    ) -> Result<NumberToken, AsonError> {
+        match self.options.default_integer_type {
+            IntegerType::I32 => {
+                let v = u32::from_str_radix(num_string, radix).map_err(|_| {
+                    AsonError::MessageWithLocation(
+                        format!("Can not convert \"{}\" to i32 integer number.", num_string),
+                        num_range,
+                    )
+                })?;
+
+                Ok(NumberToken::I32(v))
+            }
+            IntegerType::I64 => {
+                let v = u64::from_str_radix(num_string, radix).map_err(|_| {
+                    AsonError::MessageWithLocation(
+                        format!("Can not convert \"{}\" to i64 integer number.", num_string),
+                        num_range,
+                    )
+                })?;
+
+                Ok(NumberToken::I64(v))
+            }
+        }
+    }
+
     fn lex_number_type_suffix(&mut self) -> Result<NumberType, AsonError> {
         // iddT  //
         // ^^ ^__// to here
@@ -716,6 +941,7 @@ impl Lexer<'_> {
 
         let mut found_point: bool = false; // to indicated whether char '.' is found
         let mut found_p: bool = false; // to indicated whether char 'p' is found
+        let mut digit_count = 0;
 
         while let Some(current_char) = self.peek_char(0) {
             match current_char {
@@ -731,6 +957,8 @@ impl Lexer<'_> {
                 }
                 '0'..='9' | 'a'..='f' | 'A'..='F' => {
                     // valid digits for hex number
+                    digit_count += 1;
+                    self.check_number_digits(digit_count)?;
                     num_string.push(*current_char);
 
                     self.next_char(); // consume digit
@@ -784,6 +1012,14 @@ impl Lexer<'_> {
                     // terminator chars
                     break;
                 }
+                '#' if self.options.comment_style == CommentStyle::Hash => {
+                    // the hash style line comment is also a terminator
+                    break;
+                }
+                _ if self.options.document_separator == Some(*current_char) => {
+                    // the custom document separator is also a terminator
+                    break;
+                }
                 _ => {
                     return Err(AsonError::MessageWithLocation(
                         format!("Invalid char '{}' for hexadecimal number.", current_char),
@@ -816,8 +1052,8 @@ impl Lexer<'_> {
         }
 
         let num_token = if found_p {
-            // the default type for floating-point is f64
-            let mut to_f64 = true;
+            // the default type for floating-point is f64 (or f32 if it is configured)
+            let mut to_f64 = self.options.default_float_type == FloatType::F64;
 
             if let Some(nt) = num_type {
                 match nt {
@@ -949,6 +1185,26 @@ impl Lexer<'_> {
 
                     NumberToken::U64(v)
                 }
+                NumberType::I128 => {
+                    let v = u128::from_str_radix(&num_string, 16).map_err(|_| {
+                        AsonError::MessageWithLocation(
+                            format!("Can not convert \"{}\" to i128 integer number.", num_string),
+                            num_range,
+                        )
+                    })?;
+
+                    NumberToken::I128(v)
+                }
+                NumberType::U128 => {
+                    let v = u128::from_str_radix(&num_string, 16).map_err(|_| {
+                        AsonError::MessageWithLocation(
+                            format!("Can not convert \"{}\" to u128 integer number.", num_string),
+                            num_range,
+                        )
+                    })?;
+
+                    NumberToken::U128(v)
+                }
                 NumberType::F32 | NumberType::F64 => {
                     // '0x..f32' and '0x..f64' would only be parsed
                     // as ordinary hex digits
@@ -957,21 +1213,20 @@ impl Lexer<'_> {
             }
         } else {
             // default
-            // convert to i32
-            let v = u32::from_str_radix(&num_string, 16).map_err(|_| {
-                AsonError::MessageWithLocation(
-                    format!("Can not convert \"{}\" to i32 integer number.", num_string),
-                    num_range,
-                )
-            })?;
-
-            NumberToken::I32(v)
+            // convert to i32 (or i64 if it is configured)
+            self.lex_default_integer(&num_string, 16, num_range)?
         };
 
         Ok(TokenWithRange::new(Token::Number(num_token), num_range))
     }
 
-    fn lex_number_binary(&mut self) -> Result<TokenWithRange, AsonError> {
+    // lexes the binary number (`radix` is 2) or the octal number (`radix` is 8),
+    // the `name` is used in error messages, e.g. "binary" and "octal".
+    fn lex_number_with_radix(
+        &mut self,
+        radix: u32,
+        name: &str,
+    ) -> Result<TokenWithRange, AsonError> {
         // 0b1010T  //
         // ^^    ^__// to here
         // ||_______// validated
@@ -982,15 +1237,18 @@ impl Lexer<'_> {
         self.push_peek_position();
 
         self.next_char(); // consume '0'
-        self.next_char(); // consume 'b'
+        self.next_char(); // consume 'b' or 'o'
 
         let mut num_string = String::new();
         let mut num_type: Option<NumberType> = None;
+        let mut digit_count = 0;
 
         while let Some(current_char) = self.peek_char(0) {
             match current_char {
-                '0' | '1' => {
-                    // valid digits for binary number
+                '0'..='9' if current_char.is_digit(radix) => {
+                    // valid digits for binary or octal number
+                    digit_count += 1;
+                    self.check_number_digits(digit_count)?;
                     num_string.push(*current_char);
 
                     self.next_char(); // consume digit
@@ -998,7 +1256,7 @@ impl Lexer<'_> {
                 '_' => {
                     self.next_char(); // consume '_'
                 }
-                // binary form only supports integer numbers, does not support floating-point numbers
+                // binary and octal forms only support integer numbers, does not support floating-point numbers
                 'i' | 'u' if num_type.is_none() && matches!(self.peek_char(1), Some('0'..='9')) => {
                     let nt = self.lex_number_type_suffix()?;
                     num_type.replace(nt);
@@ -1009,9 +1267,17 @@ impl Lexer<'_> {
                     // terminator chars
                     break;
                 }
+                '#' if self.options.comment_style == CommentStyle::Hash => {
+                    // the hash style line comment is also a terminator
+                    break;
+                }
+                _ if self.options.document_separator == Some(*current_char) => {
+                    // the custom document separator is also a terminator
+                    break;
+                }
                 _ => {
                     return Err(AsonError::MessageWithLocation(
-                        format!("Invalid char '{}' for binary number.", current_char),
+                        format!("Invalid char '{}' for {} number.", current_char, name),
                         *self.peek_position(0).unwrap(),
                     ));
                 }
@@ -1025,7 +1291,7 @@ impl Lexer<'_> {
 
         if num_string.is_empty() {
             return Err(AsonError::MessageWithLocation(
-                "Empty binary number.".to_owned(),
+                format!("Empty {} number.", name),
                 num_range,
             ));
         }
@@ -1033,7 +1299,7 @@ impl Lexer<'_> {
         let num_token = if let Some(nt) = num_type {
             match nt {
                 NumberType::I8 => {
-                    let v = u8::from_str_radix(&num_string, 2).map_err(|_| {
+                    let v = u8::from_str_radix(&num_string, radix).map_err(|_| {
                         AsonError::MessageWithLocation(
                             format!("Can not convert \"{}\" to i8 integer number.", num_string,),
                             num_range,
@@ -1043,7 +1309,7 @@ impl Lexer<'_> {
                     NumberToken::I8(v)
                 }
                 NumberType::U8 => {
-                    let v = u8::from_str_radix(&num_string, 2).map_err(|_| {
+                    let v = u8::from_str_radix(&num_string, radix).map_err(|_| {
                         AsonError::MessageWithLocation(
                             format!("Can not convert \"{}\" to u8 integer number.", num_string,),
                             num_range,
@@ -1053,7 +1319,7 @@ impl Lexer<'_> {
                     NumberToken::U8(v)
                 }
                 NumberType::I16 => {
-                    let v = u16::from_str_radix(&num_string, 2).map_err(|_| {
+                    let v = u16::from_str_radix(&num_string, radix).map_err(|_| {
                         AsonError::MessageWithLocation(
                             format!("Can not convert \"{}\" to i16 integer number.", num_string,),
                             num_range,
@@ -1063,7 +1329,7 @@ impl Lexer<'_> {
                     NumberToken::I16(v)
                 }
                 NumberType::U16 => {
-                    let v = u16::from_str_radix(&num_string, 2).map_err(|_| {
+                    let v = u16::from_str_radix(&num_string, radix).map_err(|_| {
                         AsonError::MessageWithLocation(
                             format!("Can not convert \"{}\" to u16 integer number.", num_string,),
                             num_range,
@@ -1073,7 +1339,7 @@ impl Lexer<'_> {
                     NumberToken::U16(v)
                 }
                 NumberType::I32 => {
-                    let v = u32::from_str_radix(&num_string, 2).map_err(|_| {
+                    let v = u32::from_str_radix(&num_string, radix).map_err(|_| {
                         AsonError::MessageWithLocation(
                             format!("Can not convert \"{}\" to i32 integer number.", num_string),
                             num_range,
@@ -1083,7 +1349,7 @@ impl Lexer<'_> {
                     NumberToken::I32(v)
                 }
                 NumberType::U32 => {
-                    let v = u32::from_str_radix(&num_string, 2).map_err(|_| {
+                    let v = u32::from_str_radix(&num_string, radix).map_err(|_| {
                         AsonError::MessageWithLocation(
                             format!("Can not convert \"{}\" to u32 integer number.", num_string),
                             num_range,
@@ -1093,7 +1359,7 @@ impl Lexer<'_> {
                     NumberToken::U32(v)
                 }
                 NumberType::I64 => {
-                    let v = u64::from_str_radix(&num_string, 2).map_err(|_| {
+                    let v = u64::from_str_radix(&num_string, radix).map_err(|_| {
                         AsonError::MessageWithLocation(
                             format!("Can not convert \"{}\" to i64 integer number.", num_string),
                             num_range,
@@ -1103,7 +1369,7 @@ impl Lexer<'_> {
                     NumberToken::I64(v)
                 }
                 NumberType::U64 => {
-                    let v = u64::from_str_radix(&num_string, 2).map_err(|_| {
+                    let v = u64::from_str_radix(&num_string, radix).map_err(|_| {
                         AsonError::MessageWithLocation(
                             format!("Can not convert \"{}\" to u64 integer number.", num_string),
                             num_range,
@@ -1112,22 +1378,34 @@ impl Lexer<'_> {
 
                     NumberToken::U64(v)
                 }
+                NumberType::I128 => {
+                    let v = u128::from_str_radix(&num_string, radix).map_err(|_| {
+                        AsonError::MessageWithLocation(
+                            format!("Can not convert \"{}\" to i128 integer number.", num_string),
+                            num_range,
+                        )
+                    })?;
+
+                    NumberToken::I128(v)
+                }
+                NumberType::U128 => {
+                    let v = u128::from_str_radix(&num_string, radix).map_err(|_| {
+                        AsonError::MessageWithLocation(
+                            format!("Can not convert \"{}\" to u128 integer number.", num_string),
+                            num_range,
+                        )
+                    })?;
+
+                    NumberToken::U128(v)
+                }
                 NumberType::F32 | NumberType::F64 => {
                     unreachable!()
                 }
             }
         } else {
             // default
-            // convert to i32
-
-            let v = u32::from_str_radix(&num_string, 2).map_err(|_| {
-                AsonError::MessageWithLocation(
-                    format!("Can not convert \"{}\" to i32 integer number.", num_string),
-                    num_range,
-                )
-            })?;
-
-            NumberToken::I32(v)
+            // convert to i32 (or i64 if it is configured)
+            self.lex_default_integer(&num_string, radix, num_range)?
         };
 
         Ok(TokenWithRange::new(Token::Number(num_token), num_range))
@@ -1147,6 +1425,8 @@ impl Lexer<'_> {
                 match prev_previous_char {
                     '\\' => {
                         // escape chars
+                        let escape_position = self.last_position;
+
                         match self.next_char() {
                             Some(previous_char) => {
                                 match previous_char {
@@ -1171,12 +1451,21 @@ impl Lexer<'_> {
                                     }
                                     '0' => {
                                         // null char
+                                        self.check_null_char('\0', &escape_position)?;
                                         '\0'
                                     }
+                                    'x' => {
+                                        // ascii char, e.g. '\x41'
+                                        let ch = self.unescape_hex_byte(&escape_position)?;
+                                        self.check_null_char(ch, &escape_position)?;
+                                        ch
+                                    }
                                     'u' => {
                                         if self.peek_char_and_equals(0, '{') {
                                             // unicode code point, e.g. '\u{2d}', '\u{6587}'
-                                            self.unescape_unicode()?
+                                            let ch = self.unescape_unicode()?;
+                                            self.check_null_char(ch, &escape_position)?;
+                                            ch
                                         } else {
                                             return Err(AsonError::MessageWithLocation(
                                                 "Missing the brace for unicode escape sequence."
@@ -1216,6 +1505,7 @@ impl Lexer<'_> {
                     }
                     _ => {
                         // ordinary char
+                        self.check_null_char(prev_previous_char, &self.last_position)?;
                         prev_previous_char
                     }
                 }
@@ -1255,6 +1545,53 @@ impl Lexer<'_> {
         Ok(TokenWithRange::new(Token::Char(character), character_range))
     }
 
+    fn unescape_hex_byte(&mut self, escape_position: &Location) -> Result<char, AsonError> {
+        // \x41?  //
+        //   ^ ^__// to here
+        //   |____// current char
+
+        let mut hex_string = String::new();
+
+        for _ in 0..2 {
+            match self.next_char() {
+                Some(previous_char) => match previous_char {
+                    '0'..='9' | 'a'..='f' | 'A'..='F' => hex_string.push(previous_char),
+                    _ => {
+                        return Err(AsonError::MessageWithLocation(
+                            format!(
+                                "Invalid character '{}' for hex escape sequence.",
+                                previous_char
+                            ),
+                            self.last_position,
+                        ));
+                    }
+                },
+                None => {
+                    // EOF
+                    return Err(AsonError::UnexpectedEndOfDocument(
+                        "Incomplete hex escape sequence.".to_owned(),
+                    ));
+                }
+            }
+        }
+
+        let code = u8::from_str_radix(&hex_string, 16).unwrap();
+
+        if code.is_ascii() {
+            Ok(code as char)
+        } else {
+            // only ASCII chars are allowed, to avoid the ambiguity
+            // with the bytes of UTF-8.
+            Err(AsonError::MessageWithLocation(
+                "Hex escape sequence exceeds the ASCII range.".to_owned(),
+                Location::from_position_pair_with_end_included(
+                    escape_position,
+                    &self.last_position,
+                ),
+            ))
+        }
+    }
+
     fn unescape_unicode(&mut self) -> Result<char, AsonError> {
         // \u{6587}?  //
         //   ^     ^__// to here
@@ -1347,6 +1684,8 @@ impl Lexer<'_> {
                     match prev_previous_char {
                         '\\' => {
                             // escape chars
+                            let escape_position = self.last_position;
+
                             match self.next_char() {
                                 Some(previous_char) => {
                                     match previous_char {
@@ -1375,12 +1714,20 @@ impl Lexer<'_> {
                                         }
                                         '0' => {
                                             // null char
+                                            self.check_null_char('\0', &escape_position)?;
                                             final_string.push('\0');
                                         }
+                                        'x' => {
+                                            // ascii char, e.g. "\x41"
+                                            let ch = self.unescape_hex_byte(&escape_position)?;
+                                            self.check_null_char(ch, &escape_position)?;
+                                            final_string.push(ch);
+                                        }
                                         'u' => {
                                             if self.peek_char_and_equals(0, '{') {
                                                 // unicode code point, e.g. '\u{2d}', '\u{6587}'
                                                 let ch = self.unescape_unicode()?;
+                                                self.check_null_char(ch, &escape_position)?;
                                                 final_string.push(ch);
                                             } else {
                                                 return Err(AsonError::MessageWithLocation(
@@ -1399,6 +1746,15 @@ impl Lexer<'_> {
                                             // (single line) long string
                                             self.consume_all_leading_whitespaces()?;
                                         }
+                                        _ if self.options.lenient_escapes => {
+                                            // keep the unknown escape sequence verbatim
+                                            self.check_null_char(
+                                                previous_char,
+                                                &self.last_position,
+                                            )?;
+                                            final_string.push('\\');
+                                            final_string.push(previous_char);
+                                        }
                                         _ => {
                                             return Err(AsonError::MessageWithLocation(
                                                 format!(
@@ -1427,6 +1783,7 @@ impl Lexer<'_> {
                         }
                         _ => {
                             // ordinary char
+                            self.check_null_char(prev_previous_char, &self.last_position)?;
                             final_string.push(prev_previous_char);
                         }
                     }
@@ -1446,7 +1803,7 @@ impl Lexer<'_> {
         );
 
         Ok(TokenWithRange::new(
-            Token::String(final_string),
+            Token::String(final_string, StringStyle::Normal),
             final_string_range,
         ))
     }
@@ -1503,6 +1860,7 @@ impl Lexer<'_> {
                         }
                         _ => {
                             // ordinary char
+                            self.check_null_char(previous_char, &self.last_position)?;
                             final_string.push(previous_char);
                         }
                     }
@@ -1522,7 +1880,7 @@ impl Lexer<'_> {
         );
 
         Ok(TokenWithRange::new(
-            Token::String(final_string),
+            Token::String(final_string, StringStyle::Raw),
             final_string_range,
         ))
     }
@@ -1555,6 +1913,7 @@ impl Lexer<'_> {
                         }
                         _ => {
                             // ordinary char
+                            self.check_null_char(previous_char, &self.last_position)?;
                             final_string.push(previous_char);
                         }
                     }
@@ -1574,7 +1933,7 @@ impl Lexer<'_> {
         );
 
         Ok(TokenWithRange::new(
-            Token::String(final_string),
+            Token::String(final_string, StringStyle::Raw),
             final_string_range,
         ))
     }
@@ -1644,6 +2003,7 @@ impl Lexer<'_> {
                         }
                         _ => {
                             // ordinary char
+                            self.check_null_char(previous_char, &self.last_position)?;
                             current_line.push(previous_char);
                         }
                     }
@@ -1663,7 +2023,10 @@ impl Lexer<'_> {
         );
 
         if lines.is_empty() {
-            return Ok(TokenWithRange::new(Token::String(String::new()), range));
+            return Ok(TokenWithRange::new(
+                Token::String(String::new(), StringStyle::AutoTrimmed),
+                range,
+            ));
         }
 
         // calculate leading spaces of each line
@@ -1718,7 +2081,10 @@ impl Lexer<'_> {
             .collect::<Vec<String>>()
             .join("");
 
-        Ok(TokenWithRange::new(Token::String(content), range))
+        Ok(TokenWithRange::new(
+            Token::String(content, StringStyle::AutoTrimmed),
+            range,
+        ))
     }
 
     fn lex_datetime(&mut self) -> Result<TokenWithRange, AsonError> {
@@ -1809,50 +2175,45 @@ impl Lexer<'_> {
         // ||_______________// validated
         // |________________// current char, validated
 
+        // block comments are allowed between the bytes, e.g. the offsets
+        // of the hexdump-style layout `h"/* 0000 */ 00 11"`, they are treated as whitespaces.
         let consume_zero_or_more_whitespaces = |iter: &mut Lexer| -> Result<usize, AsonError> {
             // exit when encounting non-whitespaces or EOF
             let mut amount: usize = 0;
 
-            while let Some(' ' | '\t' | '\r' | '\n') = iter.peek_char(0) {
+            loop {
+                match iter.peek_char(0) {
+                    Some(' ' | '\t' | '\r' | '\n') => {
+                        iter.next_char();
+                    }
+                    Some('/') if iter.peek_char_and_equals(1, '*') => {
+                        iter.lex_block_comment()?;
+                    }
+                    _ => break,
+                }
+
                 amount += 1;
-                iter.next_char();
             }
 
             Ok(amount)
         };
 
         let consume_one_or_more_whitespaces = |iter: &mut Lexer| -> Result<usize, AsonError> {
-            let mut amount: usize = 0;
-
-            loop {
-                match iter.peek_char(0) {
-                    Some(current_char) => {
-                        match current_char {
-                            ' ' | '\t' | '\r' | '\n' => {
-                                // consume whitespace
-                                iter.next_char();
-                                amount += 1;
-                            }
-                            _ => {
-                                if amount > 0 {
-                                    break;
-                                } else {
-                                    return Err(AsonError::MessageWithLocation(
-                                        "Expect a whitespace between the hexadecimal byte data digits."
-                                            .to_owned(),
-                                        iter.last_position.move_position_forward()
-                                    ));
-                                }
-                            }
-                        }
-                    }
+            let amount = consume_zero_or_more_whitespaces(iter)?;
+
+            if amount == 0 {
+                return match iter.peek_char(0) {
+                    Some(_) => Err(AsonError::MessageWithLocation(
+                        "Expect a whitespace between the hexadecimal byte data digits.".to_owned(),
+                        iter.last_position.move_position_forward(),
+                    )),
                     None => {
                         // h"...EOF
-                        return Err(AsonError::UnexpectedEndOfDocument(
+                        Err(AsonError::UnexpectedEndOfDocument(
                             "Incomplete hexadecimal byte data.".to_owned(),
-                        ));
+                        ))
                     }
-                }
+                };
             }
 
             Ok(amount)
@@ -1919,18 +2280,46 @@ impl Lexer<'_> {
         Ok(TokenWithRange::new(Token::HexByteData(bytes), bytes_range))
     }
 
-    fn lex_line_comment(&mut self) -> Result<TokenWithRange, AsonError> {
+    // the current char is the digit being counted, and
+    // the start position of the number has been saved.
+    fn check_number_digits(&self, digit_count: usize) -> Result<(), AsonError> {
+        if digit_count > self.options.max_number_digits {
+            Err(AsonError::MessageWithLocation(
+                "Number exceeds maximum digit count.".to_owned(),
+                Location::from_position_pair_with_end_included(
+                    self.saved_positions.last().unwrap(),
+                    self.peek_position(0).unwrap(),
+                ),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_null_char(&self, ch: char, start_position: &Location) -> Result<(), AsonError> {
+        if ch == '\0' && self.options.forbid_interior_nulls {
+            Err(AsonError::MessageWithLocation(
+                "Null char is not allowed.".to_owned(),
+                Location::from_position_pair_with_end_included(start_position, &self.last_position),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn lex_line_comment(&mut self, opener: LineCommentOpener) -> Result<TokenWithRange, AsonError> {
         // xx...[\r]\n?  //
         // ^^         ^__// to here ('?' = any char or EOF)
         // ||____________// validated
         // |_____________// current char, validated
         //
-        // x = '/'
+        // xx = "//" or "#"
 
         self.push_peek_position();
 
-        self.next_char(); // consume the 1st '/'
-        self.next_char(); // consume the 2nd '/'
+        for _ in 0..opener.as_str().len() {
+            self.next_char(); // consume '/' or '#'
+        }
 
         let mut comment_string = String::new();
 
@@ -1959,7 +2348,7 @@ impl Lexer<'_> {
         );
 
         Ok(TokenWithRange::new(
-            Token::Comment(Comment::Line(comment_string)),
+            Token::Comment(Comment::Line(comment_string, opener)),
             comment_range,
         ))
     }
@@ -2043,13 +2432,15 @@ mod tests {
     use crate::{
         charwithposition::CharsWithPositionIter,
         // charstream::CharStreamFromCharIter,
-        lexer::{Comment, NumberToken, TokenWithRange},
+        lexer::{
+            Comment, CommentStyle, LineCommentOpener, NumberToken, StringStyle, TokenWithRange,
+        },
         location::Location,
         peekableiter::PeekableIter,
         AsonError,
     };
 
-    use super::{Lexer, Token, LEXER_PEEK_CHAR_MAX_COUNT};
+    use super::{Lexer, LexerOptions, Token, DEFAULT_MAX_NUMBER_DIGITS, LEXER_PEEK_CHAR_MAX_COUNT};
 
     impl Token {
         pub fn new_variant(type_name: &str, member_name: &str) -> Self {
@@ -2061,16 +2452,31 @@ mod tests {
         }
 
         pub fn new_string(s: &str) -> Self {
-            Token::String(s.to_owned())
+            Token::String(s.to_owned(), StringStyle::Normal)
+        }
+
+        pub fn new_raw_string(s: &str) -> Self {
+            Token::String(s.to_owned(), StringStyle::Raw)
+        }
+
+        pub fn new_auto_trimmed_string(s: &str) -> Self {
+            Token::String(s.to_owned(), StringStyle::AutoTrimmed)
         }
     }
 
     fn lex_from_str(s: &str) -> Result<Vec<TokenWithRange>, AsonError> {
+        lex_from_str_with_options(s, LexerOptions::default())
+    }
+
+    fn lex_from_str_with_options(
+        s: &str,
+        options: LexerOptions,
+    ) -> Result<Vec<TokenWithRange>, AsonError> {
         let mut chars = s.chars();
         let mut char_position_iter = CharsWithPositionIter::new(&mut chars);
         let mut peekable_char_position_iter =
             PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
-        let lexer = Lexer::new(&mut peekable_char_position_iter);
+        let lexer = Lexer::with_options(&mut peekable_char_position_iter, options);
 
         // do not use `iter.collect::<Vec<_>>()` because the `Lexer` throws
         // exceptions though the function `next() -> Option<Result<...>>`,
@@ -2161,7 +2567,7 @@ mod tests {
     #[test]
     fn test_lex_punctuations() {
         assert_eq!(
-            lex_from_str_without_location(",:{}[]()+-").unwrap(),
+            lex_from_str_without_location(",:{}[]()+-@").unwrap(),
             vec![
                 Token::Comma,
                 Token::Colon,
@@ -2172,7 +2578,8 @@ mod tests {
                 Token::LeftParen,
                 Token::RightParen,
                 Token::Plus,
-                Token::Minus
+                Token::Minus,
+                Token::At
             ]
         );
     }
@@ -3218,51 +3625,166 @@ mod tests {
     }
 
     #[test]
-    fn test_lex_binary_number_with_explicit_type() {
-        // general
-        {
-            assert_eq!(
-                lex_from_str_without_location("0b11i8").unwrap(),
-                vec![Token::Number(NumberToken::I8(0b11))]
-            );
-
-            assert_eq!(
-                lex_from_str_without_location("0b11_i8").unwrap(),
-                vec![Token::Number(NumberToken::I8(0b11))]
-            );
+    fn test_lex_128_bit_integer() {
+        assert_eq!(
+            lex_from_str_without_location("170141183460469231731687303715884105727_i128").unwrap(),
+            vec![Token::Number(NumberToken::I128(i128::MAX as u128))]
+        );
 
-            assert_eq!(
-                lex_from_str_without_location("0b11__i8").unwrap(),
-                vec![Token::Number(NumberToken::I8(0b11))]
-            );
+        assert_eq!(
+            lex_from_str_without_location("340282366920938463463374607431768211455_u128").unwrap(),
+            vec![Token::Number(NumberToken::U128(u128::MAX))]
+        );
 
-            // location
+        assert_eq!(
+            lex_from_str_without_location("0xffff_ffff_ffff_ffff_ffff_u128").unwrap(),
+            vec![Token::Number(NumberToken::U128(0xffff_ffff_ffff_ffff_ffff))]
+        );
 
-            // "0b101_i16 0b1010_u32"
-            //  01234567890123456789  // index
-            assert_eq!(
-                lex_from_str("0b101_i16 0b1010_u32").unwrap(),
-                vec![
-                    TokenWithRange::from_position_and_length(
-                        Token::Number(NumberToken::I16(0b101)),
-                        &Location::new_position(/*0,*/ 0, 0, 0),
-                        9
-                    ),
-                    TokenWithRange::from_position_and_length(
-                        Token::Number(NumberToken::U32(0b1010)),
-                        &Location::new_position(/*0,*/ 10, 0, 10),
-                        10
-                    ),
-                ]
-            );
-        }
+        assert_eq!(
+            lex_from_str_without_location("0b1010_i128").unwrap(),
+            vec![Token::Number(NumberToken::I128(0b1010))]
+        );
 
-        // byte
-        {
-            assert_eq!(
-                lex_from_str_without_location("0b0111_1111_i8").unwrap(),
-                vec![Token::Number(NumberToken::I8(0x7f_i8 as u8))]
-            );
+        assert_eq!(
+            lex_from_str_without_location("0o777_u128").unwrap(),
+            vec![Token::Number(NumberToken::U128(0o777))]
+        );
+
+        // err: unsigned overflow
+        assert!(matches!(
+            lex_from_str_without_location("340282366920938463463374607431768211456_u128"),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 44
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_lex_octal_number() {
+        assert_eq!(
+            lex_from_str_without_location("0o755").unwrap(),
+            vec![Token::Number(NumberToken::I32(0o755))]
+        );
+
+        assert_eq!(
+            lex_from_str_without_location("-0o17").unwrap(),
+            vec![Token::Minus, Token::Number(NumberToken::I32(0o17))]
+        );
+
+        assert_eq!(
+            lex_from_str_without_location("0o1_777_u16").unwrap(),
+            vec![Token::Number(NumberToken::U16(0o1777))]
+        );
+
+        assert_eq!(
+            lex_from_str_without_location("0o377i8").unwrap(),
+            vec![Token::Number(NumberToken::I8(0o377))]
+        );
+
+        assert_eq!(
+            lex_from_str("0o10 0o7").unwrap(),
+            vec![
+                TokenWithRange::from_position_and_length(
+                    Token::Number(NumberToken::I32(0o10)),
+                    &Location::new_position(0, 0, 0),
+                    4
+                ),
+                TokenWithRange::from_position_and_length(
+                    Token::Number(NumberToken::I32(0o7)),
+                    &Location::new_position(5, 0, 5),
+                    3
+                ),
+            ]
+        );
+
+        // err: invalid char for octal number
+        assert!(matches!(
+            lex_from_str_without_location("0o178"),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 4,
+                    line: 0,
+                    column: 4,
+                    length: 0
+                }
+            ))
+        ));
+
+        // err: empty octal number
+        assert!(matches!(
+            lex_from_str_without_location("0o"),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 2
+                }
+            ))
+        ));
+
+        // err: octal number overflow
+        assert!(matches!(
+            lex_from_str_without_location("0o400_u8"),
+            Err(AsonError::MessageWithLocation(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_lex_binary_number_with_explicit_type() {
+        // general
+        {
+            assert_eq!(
+                lex_from_str_without_location("0b11i8").unwrap(),
+                vec![Token::Number(NumberToken::I8(0b11))]
+            );
+
+            assert_eq!(
+                lex_from_str_without_location("0b11_i8").unwrap(),
+                vec![Token::Number(NumberToken::I8(0b11))]
+            );
+
+            assert_eq!(
+                lex_from_str_without_location("0b11__i8").unwrap(),
+                vec![Token::Number(NumberToken::I8(0b11))]
+            );
+
+            // location
+
+            // "0b101_i16 0b1010_u32"
+            //  01234567890123456789  // index
+            assert_eq!(
+                lex_from_str("0b101_i16 0b1010_u32").unwrap(),
+                vec![
+                    TokenWithRange::from_position_and_length(
+                        Token::Number(NumberToken::I16(0b101)),
+                        &Location::new_position(/*0,*/ 0, 0, 0),
+                        9
+                    ),
+                    TokenWithRange::from_position_and_length(
+                        Token::Number(NumberToken::U32(0b1010)),
+                        &Location::new_position(/*0,*/ 10, 0, 10),
+                        10
+                    ),
+                ]
+            );
+        }
+
+        // byte
+        {
+            assert_eq!(
+                lex_from_str_without_location("0b0111_1111_i8").unwrap(),
+                vec![Token::Number(NumberToken::I8(0x7f_i8 as u8))]
+            );
 
             assert_eq!(
                 lex_from_str_without_location("0b1111_1111_u8").unwrap(),
@@ -3578,9 +4100,17 @@ mod tests {
             ))
         ));
 
-        // err: unsupported hex escape "\x.."
+        // hex escape "\x.."
+        assert_eq!(
+            lex_from_str_without_location(r#"'\x41'"#).unwrap(),
+            vec![Token::Char('A')]
+        );
+
+        // err: hex escape out of the ASCII range
+        // "'\x80'"
+        //  01234     // index
         assert!(matches!(
-            lex_from_str_without_location(r#"'\x33'"#),
+            lex_from_str_without_location(r#"'\x80'"#),
             Err(AsonError::MessageWithLocation(
                 _,
                 Location {
@@ -3588,7 +4118,22 @@ mod tests {
                     index: 1,
                     line: 0,
                     column: 1,
-                    length: 2
+                    length: 4
+                }
+            ))
+        ));
+
+        // err: invalid char for hex escape
+        assert!(matches!(
+            lex_from_str_without_location(r#"'\xG1'"#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    // unit: 0,
+                    index: 3,
+                    line: 0,
+                    column: 3,
+                    length: 0
                 }
             ))
         ));
@@ -3816,9 +4361,17 @@ mod tests {
             ))
         ));
 
-        // err: unsupported hex escape "\x.."
+        // hex escape "\x.."
+        assert_eq!(
+            lex_from_str_without_location(r#""abc\x41\x7axyz""#).unwrap(),
+            vec![Token::String("abcAzxyz".to_owned(), StringStyle::Normal)]
+        );
+
+        // err: hex escape out of the ASCII range
+        // "abc\x80xyz"
+        //  012345678     // index
         assert!(matches!(
-            lex_from_str_without_location(r#""abc\x33xyz""#),
+            lex_from_str_without_location(r#""abc\x80xyz""#),
             Err(AsonError::MessageWithLocation(
                 _,
                 Location {
@@ -3826,11 +4379,32 @@ mod tests {
                     index: 4,
                     line: 0,
                     column: 4,
-                    length: 2
+                    length: 4
+                }
+            ))
+        ));
+
+        // err: invalid char for hex escape
+        assert!(matches!(
+            lex_from_str_without_location(r#""abc\xG1xyz""#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    // unit: 0,
+                    index: 6,
+                    line: 0,
+                    column: 6,
+                    length: 0
                 }
             ))
         ));
 
+        // err: incomplete hex escape
+        assert!(matches!(
+            lex_from_str_without_location(r#""abc\x4"#),
+            Err(AsonError::UnexpectedEndOfDocument(_))
+        ));
+
         // err: empty unicode escape string
         // "abc\u{}"
         // 012345678    // index
@@ -4029,7 +4603,7 @@ mod tests {
                 "r\"abc\ndef\n    uvw\r\n\t escape: \\r\\n\\t\\\\ unicode: \\u{1234} xyz\""
             )
             .unwrap(),
-            vec![Token::new_string(
+            vec![Token::new_raw_string(
                 "abc\ndef\n    uvw\r\n\t escape: \\r\\n\\t\\\\ unicode: \\u{1234} xyz"
             )]
         );
@@ -4040,12 +4614,12 @@ mod tests {
             lex_from_str("r\"abc\n    xyz\" r\"foo\\nbar\"").unwrap(),
             vec![
                 TokenWithRange::from_position_and_length(
-                    Token::new_string("abc\n    xyz"),
+                    Token::new_raw_string("abc\n    xyz"),
                     &Location::new_position(/*0,*/ 0, 0, 0),
                     14
                 ),
                 TokenWithRange::from_position_and_length(
-                    Token::new_string("foo\\nbar"),
+                    Token::new_raw_string("foo\\nbar"),
                     &Location::new_position(/*0,*/ 15, 1, 9),
                     11
                 )
@@ -4077,7 +4651,7 @@ mod tests {
             lex_from_str_without_location(
                 "r#\"abc\ndef\n    uvw\r\n\t escape: \\r\\n\\t\\\\ unicode: \\u{1234} xyz quote: \"foo\"\"#"
             ).unwrap(),
-            vec![Token::new_string(
+            vec![Token::new_raw_string(
                 "abc\ndef\n    uvw\r\n\t escape: \\r\\n\\t\\\\ unicode: \\u{1234} xyz quote: \"foo\""
             )]
         );
@@ -4088,12 +4662,12 @@ mod tests {
             lex_from_str("r#\"abc\n    xyz\"# r#\"foo\\nbar\"#").unwrap(),
             vec![
                 TokenWithRange::from_position_and_length(
-                    Token::new_string("abc\n    xyz"),
+                    Token::new_raw_string("abc\n    xyz"),
                     &Location::new_position(/*0,*/ 0, 0, 0),
                     16
                 ),
                 TokenWithRange::from_position_and_length(
-                    Token::new_string("foo\\nbar"),
+                    Token::new_raw_string("foo\\nbar"),
                     &Location::new_position(/*0,*/ 17, 1, 10),
                     13
                 )
@@ -4135,7 +4709,7 @@ mod tests {
             .unwrap(),
             vec![
                 Token::NewLine,
-                Token::new_string("one\n  two\n    three\nend"),
+                Token::new_auto_trimmed_string("one\n  two\n    three\nend"),
                 Token::NewLine,
             ]
         );
@@ -4154,7 +4728,7 @@ mod tests {
             .unwrap(),
             vec![
                 Token::NewLine,
-                Token::new_string("    one\n  two\nthree\n    end"),
+                Token::new_auto_trimmed_string("    one\n  two\nthree\n    end"),
                 Token::NewLine,
             ]
         );
@@ -4173,7 +4747,7 @@ mod tests {
             .unwrap(),
             vec![
                 Token::NewLine,
-                Token::new_string("one\\\\\\\"\\t\\r\\n\\u{1234}\n\nend"),
+                Token::new_auto_trimmed_string("one\\\\\\\"\\t\\r\\n\\u{1234}\n\nend"),
                 Token::NewLine,
             ]
         );
@@ -4191,7 +4765,7 @@ mod tests {
             .unwrap(),
             vec![
                 Token::NewLine,
-                Token::new_string("one\"\"\"\ntwo"),
+                Token::new_auto_trimmed_string("one\"\"\"\ntwo"),
                 Token::NewLine,
             ]
         );
@@ -4209,7 +4783,7 @@ mod tests {
             vec![
                 Token::NewLine,
                 Token::Number(NumberToken::I32(11)),
-                Token::new_string("abc"),
+                Token::new_auto_trimmed_string("abc"),
                 Token::Number(NumberToken::I32(13)),
                 Token::NewLine,
             ]
@@ -4235,7 +4809,7 @@ mod tests {
                     1
                 ),
                 TokenWithRange::from_position_and_length(
-                    Token::new_string("foo\nbar"),
+                    Token::new_auto_trimmed_string("foo\nbar"),
                     &Location::new_position(/*0,*/ 1, 0, 1),
                     23
                 ),
@@ -4245,7 +4819,7 @@ mod tests {
                     1
                 ),
                 TokenWithRange::from_position_and_length(
-                    Token::new_string("hello\nworld"),
+                    Token::new_auto_trimmed_string("hello\nworld"),
                     &Location::new_position(/*0,*/ 26, 3, 5),
                     27
                 ),
@@ -4392,6 +4966,12 @@ hello
             ]
         );
 
+        // block comments between bytes
+        assert_eq!(
+            lex_from_str_without_location("h\"/* 0000 */ 11 13 /* 0002 */17/* .. */\"").unwrap(),
+            vec![Token::HexByteData(vec![0x11, 0x13, 0x17])]
+        );
+
         // err: not enough digits
         assert!(matches!(
             lex_from_str_without_location("h\"11 1\""),
@@ -4486,16 +5066,28 @@ hello
             vec![
                 Token::NewLine,
                 Token::Number(NumberToken::I32(7)),
-                Token::Comment(Comment::Line("11".to_owned())),
+                Token::Comment(Comment::Line(
+                    "11".to_owned(),
+                    LineCommentOpener::DoubleSlash
+                )),
                 Token::NewLine,
                 Token::Number(NumberToken::I32(13)),
                 Token::Number(NumberToken::I32(17)),
-                Token::Comment(Comment::Line(" 19 23".to_owned())),
+                Token::Comment(Comment::Line(
+                    " 19 23".to_owned(),
+                    LineCommentOpener::DoubleSlash
+                )),
                 Token::NewLine,
-                Token::Comment(Comment::Line("  29".to_owned())),
+                Token::Comment(Comment::Line(
+                    "  29".to_owned(),
+                    LineCommentOpener::DoubleSlash
+                )),
                 Token::NewLine,
                 Token::Number(NumberToken::I32(31)),
-                Token::Comment(Comment::Line("    37".to_owned())),
+                Token::Comment(Comment::Line(
+                    "    37".to_owned(),
+                    LineCommentOpener::DoubleSlash
+                )),
                 Token::NewLine,
             ]
         );
@@ -4511,7 +5103,10 @@ hello
                     3
                 ),
                 TokenWithRange::from_position_and_length(
-                    Token::Comment(Comment::Line(" bar".to_owned())),
+                    Token::Comment(Comment::Line(
+                        " bar".to_owned(),
+                        LineCommentOpener::DoubleSlash
+                    )),
                     &Location::new_position(/*0,*/ 4, 0, 4),
                     6
                 ),
@@ -4527,7 +5122,10 @@ hello
                     3
                 ),
                 TokenWithRange::from_position_and_length(
-                    Token::Comment(Comment::Line(" def".to_owned())),
+                    Token::Comment(Comment::Line(
+                        " def".to_owned(),
+                        LineCommentOpener::DoubleSlash
+                    )),
                     &Location::new_position(/*0,*/ 4, 0, 4),
                     6
                 ),
@@ -4537,7 +5135,10 @@ hello
                     1
                 ),
                 TokenWithRange::from_position_and_length(
-                    Token::Comment(Comment::Line(" xyz".to_owned())),
+                    Token::Comment(Comment::Line(
+                        " xyz".to_owned(),
+                        LineCommentOpener::DoubleSlash
+                    )),
                     &Location::new_position(/*0,*/ 11, 1, 0),
                     6
                 ),
@@ -4550,6 +5151,300 @@ hello
         );
     }
 
+    #[test]
+    fn test_lex_max_number_digits() {
+        // the default limit
+        assert_eq!(
+            lex_from_str_without_location(&"0".repeat(DEFAULT_MAX_NUMBER_DIGITS)).unwrap(),
+            vec![Token::Number(NumberToken::I32(0))]
+        );
+
+        assert!(matches!(
+            lex_from_str_without_location(&"0".repeat(DEFAULT_MAX_NUMBER_DIGITS + 1)),
+            Err(AsonError::MessageWithLocation(_, _))
+        ));
+
+        let options = LexerOptions {
+            max_number_digits: 4,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            lex_from_str_with_options("1234 0x1234 0b1010 1.25", options.clone())
+                .unwrap()
+                .into_iter()
+                .map(|e| e.token)
+                .collect::<Vec<Token>>(),
+            vec![
+                Token::Number(NumberToken::I32(1234)),
+                Token::Number(NumberToken::I32(0x1234)),
+                Token::Number(NumberToken::I32(0b1010)),
+                Token::Number(NumberToken::F64(1.25)),
+            ]
+        );
+
+        // underscores are not counted
+        assert_eq!(
+            lex_from_str_with_options("1_2_3_4_i64", options.clone())
+                .unwrap()
+                .into_iter()
+                .map(|e| e.token)
+                .collect::<Vec<Token>>(),
+            vec![Token::Number(NumberToken::I64(1234))]
+        );
+
+        // err: exceeds the limit
+        // "7 12345"
+        //  0123456    // index
+        assert!(matches!(
+            lex_from_str_with_options("7 12345", options.clone()),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 2,
+                    line: 0,
+                    column: 2,
+                    length: 5
+                }
+            ))
+        ));
+
+        assert!(matches!(
+            lex_from_str_with_options("0x1_2345", options.clone()),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 8
+                }
+            ))
+        ));
+
+        assert!(matches!(
+            lex_from_str_with_options("0b10101", options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 7
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_lex_lenient_escapes() {
+        // err: unknown escape sequence by default
+        // "a\qb"
+        // 01234     // index
+        assert!(matches!(
+            lex_from_str_without_location(r#""a\qb""#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 2,
+                    line: 0,
+                    column: 2,
+                    length: 2
+                }
+            ))
+        ));
+
+        let options = LexerOptions {
+            lenient_escapes: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            lex_from_str_with_options(r#""a\qb" "C:\dir\n""#, options.clone())
+                .unwrap()
+                .into_iter()
+                .map(|e| e.token)
+                .collect::<Vec<Token>>(),
+            vec![Token::new_string("a\\qb"), Token::new_string("C:\\dir\n")]
+        );
+
+        // chars are not affected
+        assert!(matches!(
+            lex_from_str_with_options(r#"'\q'"#, options),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+    }
+
+    #[test]
+    fn test_lex_hash_line_comment() {
+        let options = LexerOptions {
+            comment_style: CommentStyle::Hash,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            lex_from_str_with_options(
+                r#"
+                7 #11
+                13 17# 19 23
+                // 29
+                "#,
+                options.clone()
+            )
+            .unwrap()
+            .into_iter()
+            .map(|e| e.token)
+            .collect::<Vec<Token>>(),
+            vec![
+                Token::NewLine,
+                Token::Number(NumberToken::I32(7)),
+                Token::Comment(Comment::Line("11".to_owned(), LineCommentOpener::Hash)),
+                Token::NewLine,
+                Token::Number(NumberToken::I32(13)),
+                Token::Number(NumberToken::I32(17)),
+                Token::Comment(Comment::Line(" 19 23".to_owned(), LineCommentOpener::Hash)),
+                Token::NewLine,
+                Token::Comment(Comment::Line(
+                    " 29".to_owned(),
+                    LineCommentOpener::DoubleSlash
+                )),
+                Token::NewLine,
+            ]
+        );
+
+        // location
+
+        assert_eq!(
+            lex_from_str_with_options("foo # bar", options).unwrap(),
+            vec![
+                TokenWithRange::from_position_and_length(
+                    Token::Identifier("foo".to_owned()),
+                    &Location::new_position(/*0,*/ 0, 0, 0),
+                    3
+                ),
+                TokenWithRange::from_position_and_length(
+                    Token::Comment(Comment::Line(" bar".to_owned(), LineCommentOpener::Hash)),
+                    &Location::new_position(/*0,*/ 4, 0, 4),
+                    5
+                ),
+            ]
+        );
+
+        // err: the hash sign is not accepted by default
+        assert!(matches!(
+            lex_from_str("foo # bar"),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 4,
+                    line: 0,
+                    column: 4,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_lex_forbid_interior_nulls() {
+        let options = LexerOptions {
+            forbid_interior_nulls: true,
+            ..Default::default()
+        };
+
+        // null chars are accepted by default
+        assert_eq!(
+            lex_from_str_without_location(r#""a\0b" '\0' '\u{0}'"#).unwrap(),
+            vec![
+                Token::String("a\0b".to_owned(), StringStyle::Normal),
+                Token::Char('\0'),
+                Token::Char('\0'),
+            ]
+        );
+
+        assert_eq!(
+            lex_from_str_with_options(r#""abc" 'a'"#, options.clone())
+                .unwrap()
+                .into_iter()
+                .map(|e| e.token)
+                .collect::<Vec<Token>>(),
+            vec![
+                Token::String("abc".to_owned(), StringStyle::Normal),
+                Token::Char('a')
+            ]
+        );
+
+        // err: escaped null char in string
+        assert!(matches!(
+            lex_from_str_with_options(r#""a\0b""#, options.clone()),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 2,
+                    line: 0,
+                    column: 2,
+                    length: 2
+                }
+            ))
+        ));
+
+        // err: unicode escaped null char in string
+        assert!(matches!(
+            lex_from_str_with_options(r#""a\u{0}""#, options.clone()),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 2,
+                    line: 0,
+                    column: 2,
+                    length: 5
+                }
+            ))
+        ));
+
+        // err: literal null char in raw string
+        assert!(matches!(
+            lex_from_str_with_options("r\"a\0\"", options.clone()),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 3,
+                    line: 0,
+                    column: 3,
+                    length: 1
+                }
+            ))
+        ));
+
+        // err: null char
+        assert!(matches!(
+            lex_from_str_with_options(r#"'\0'"#, options.clone()),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 1,
+                    line: 0,
+                    column: 1,
+                    length: 2
+                }
+            ))
+        ));
+
+        // err: unicode escaped null char
+        assert!(matches!(
+            lex_from_str_with_options(r#"'\u{0}'"#, options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 1,
+                    line: 0,
+                    column: 1,
+                    length: 5
+                }
+            ))
+        ));
+    }
+
     #[test]
     fn test_lex_block_comment() {
         assert_eq!(
@@ -4866,7 +5761,10 @@ hello
                 Token::new_string("foo"),
                 Token::Boolean(true),
                 Token::RightParen,
-                Token::Comment(Comment::Line(" line comment".to_owned())),
+                Token::Comment(Comment::Line(
+                    " line comment".to_owned(),
+                    LineCommentOpener::DoubleSlash
+                )),
                 Token::NewLine,
             ]
         );
@@ -16,21 +16,93 @@ mod peekableiter;
 mod printer;
 mod serde;
 mod token;
+mod tokens;
 
+#[cfg(feature = "testutil")]
+pub mod testutil;
+
+pub use parser::parse_all;
+pub use parser::parse_all_with_options;
 pub use parser::parse_from_reader;
+pub use parser::parse_from_reader_with_options;
 pub use parser::parse_from_str;
+pub use parser::parse_from_str_with_comments;
+pub use parser::parse_from_str_with_extension;
+pub use parser::parse_from_str_with_options;
+pub use parser::parse_from_str_with_ranges;
+pub use parser::parse_from_tokens;
+pub use parser::parse_from_tokens_with_options;
+pub use parser::parse_with_directives;
+pub use parser::parse_with_directives_with_options;
+pub use parser::try_from_str;
+pub use parser::DocumentSeparator;
+pub use parser::ExtensionHandler;
+pub use parser::ParseOptions;
+pub use printer::minify;
+pub use printer::print_comment_to_string;
 pub use printer::print_to_string;
+pub use printer::print_to_string_with_comments;
+pub use printer::print_to_string_with_config;
+pub use printer::print_to_string_with_source;
 pub use printer::print_to_writer;
+pub use printer::print_to_writer_with_comments;
+pub use printer::print_to_writer_with_config;
+pub use printer::print_to_writer_with_source;
+pub use printer::PrettyConfig;
 
 pub use serde::de::from_reader;
+pub use serde::de::from_reader_with_options;
 pub use serde::de::from_str;
+pub use serde::de::from_str_all;
+pub use serde::de::from_str_all_with_options;
+pub use serde::de::from_str_with_env;
+pub use serde::de::from_str_with_options;
+pub use serde::de::from_tokens;
+pub use serde::de::from_tokens_with_options;
+pub use serde::de::DeserializeOptions;
+pub use serde::format::Binary;
+pub use serde::format::Hex;
+pub use serde::format::Octal;
+pub use serde::format::Scientific;
+pub use serde::ser::escape_char;
+pub use serde::ser::escape_string;
 pub use serde::ser::to_string;
+pub use serde::ser::to_string_with_options;
 pub use serde::ser::to_writer;
+pub use serde::ser::to_writer_with_options;
+pub use serde::ser::FloatFormat;
+pub use serde::ser::HexDumpConfig;
+pub use serde::ser::SerializeOptions;
 pub use serde::serde_date::Date;
 
+pub use location::Location;
+
+pub use token::Comment;
+pub use token::CommentStyle;
+pub use token::Directive;
+pub use token::FloatType;
+pub use token::IntegerType;
+pub use token::LineCommentOpener;
+pub use token::NumberToken;
+pub use token::StringStyle;
+pub use token::Token;
+pub use token::TokenTag;
+pub use token::TokenWithRange;
+
+pub use tokens::check_line_width;
+pub use tokens::check_whitespace;
+pub use tokens::normalize_tokens;
+pub use tokens::tokenize_from_reader;
+pub use tokens::tokenize_from_str;
+pub use tokens::tokenize_tags;
+pub use tokens::Tokens;
+
 use std::fmt::{self, Display};
 
-use crate::location::Location;
+/// The version of the ASON grammar accepted by the default options,
+/// the optional modes (e.g. `ParseOptions` and `DeserializeOptions`)
+/// are extensions of it.
+pub const ASON_GRAMMAR_VERSION: &str = "1.0";
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum AsonError {
@@ -41,6 +113,11 @@ pub enum AsonError {
     // the last index of string, for example, the "char incomplete" error raised by a string `'a`,
     // which index is 2.
     MessageWithLocation(String, Location),
+
+    // the error raised by the underlying writer (or reader), e.g. a closed pipe.
+    // the `std::io::Error` is neither `Clone` nor `PartialEq`, so only
+    // its kind and message are kept.
+    Io(std::io::ErrorKind, String),
 }
 
 impl Display for AsonError {
@@ -60,12 +137,117 @@ impl Display for AsonError {
                 )?;
                 write!(f, "{}", detail)
             }
+            AsonError::Io(_, msg) => write!(f, "I/O error: {}", msg),
         }
     }
 }
 
 impl std::error::Error for AsonError {}
 
+impl From<std::io::Error> for AsonError {
+    fn from(e: std::io::Error) -> Self {
+        AsonError::Io(e.kind(), e.to_string())
+    }
+}
+
+impl AsonError {
+    /// Returns `true` if the document is truncated, e.g. an unclosed list `[1, 2`
+    /// or string `"abc`, i.e. the error may be resolved by appending more text.
+    /// It is useful for REPLs to prompt for continuation instead of reporting
+    /// an error.
+    ///
+    /// The document without any value (e.g. the empty or whitespace-only
+    /// text) is not considered incomplete.
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, AsonError::UnexpectedEndOfDocument(_))
+    }
+}
+
+/// The flattened form of `AsonError`, for tools that consume errors
+/// as structured data.
+///
+/// The `line` and `column` start from 1 (the same as the `Display` of
+/// `AsonError`), and they are `None` when the error has no location.
+#[cfg(feature = "json")]
+#[derive(Debug, PartialEq, Clone, ::serde::Serialize)]
+pub struct ErrorRecord {
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub length: Option<usize>,
+    pub kind: &'static str,
+}
+
+#[cfg(feature = "json")]
+impl AsonError {
+    pub fn to_record(&self) -> ErrorRecord {
+        match self {
+            AsonError::Message(msg) => ErrorRecord {
+                message: msg.to_owned(),
+                line: None,
+                column: None,
+                length: None,
+                kind: "message",
+            },
+            AsonError::UnexpectedEndOfDocument(detail) => ErrorRecord {
+                message: detail.to_owned(),
+                line: None,
+                column: None,
+                length: None,
+                kind: "unexpected_end_of_document",
+            },
+            AsonError::MessageWithLocation(detail, location) => ErrorRecord {
+                message: detail.to_owned(),
+                line: Some(location.line + 1),
+                column: Some(location.column + 1),
+                length: Some(location.length),
+                kind: "message_with_location",
+            },
+            AsonError::Io(_, msg) => ErrorRecord {
+                message: msg.to_owned(),
+                line: None,
+                column: None,
+                length: None,
+                kind: "io",
+            },
+        }
+    }
+
+    /// Returns the error as a JSON object with the fields
+    /// `message`, `line`, `column`, `length` and `kind`.
+    pub fn to_json(&self) -> String {
+        // serializing a struct of plain fields never fails.
+        serde_json::to_string(&self.to_record()).unwrap()
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{location::Location, AsonError};
+
+    #[test]
+    fn test_error_to_json() {
+        let e0 = AsonError::MessageWithLocation(
+            "Unexpected token.".to_owned(),
+            Location::new_range(17, 2, 5, 3),
+        );
+
+        assert_eq!(
+            e0.to_json(),
+            r#"{"message":"Unexpected token.","line":3,"column":6,"length":3,"kind":"message_with_location"}"#
+        );
+
+        let e1 = AsonError::UnexpectedEndOfDocument("Incomplete List.".to_owned());
+
+        assert_eq!(
+            e1.to_json(),
+            r#"{"message":"Incomplete List.","line":null,"column":null,"length":null,"kind":"unexpected_end_of_document"}"#
+        );
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use std::collections::HashMap;
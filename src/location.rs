@@ -5,6 +5,7 @@
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
 pub struct Location {
     // pub unit: usize,   // the index of source file
     pub index: usize,  // character index
@@ -71,13 +72,16 @@ impl Location {
     }
 
     /// Combine two ranges into a new range
+    ///
+    /// The `range_end` is expected to end after the start of `range_start`,
+    /// otherwise the length of the new range is 0.
     pub fn from_range_pair(range_start: &Location, range_end: &Location) -> Self {
         Self::new_range(
             // range_start.unit,
             range_start.index,
             range_start.line,
             range_start.column,
-            range_end.index - range_start.index + range_end.length,
+            (range_end.index + range_end.length).saturating_sub(range_start.index),
         )
     }
 
@@ -86,12 +90,13 @@ impl Location {
         Self::new_position(/* self.unit, */ self.index, self.line, self.column)
     }
 
-    // Convert Range to Position
-    // pub fn get_position_by_range_end(&self) -> Self {
-    //     let index = self.index + self.length;
-    //     let column = self.column + self.length;
-    //     Self::new_position(self.unit, index, self.line, column)
-    // }
+    /// Convert Range to Position, the position follows the last char
+    /// of the range, the range is assumed to be in a single line.
+    pub fn get_position_by_range_end(&self) -> Self {
+        let index = self.index + self.length;
+        let column = self.column + self.length;
+        Self::new_position(/* self.unit, */ index, self.line, column)
+    }
 
     pub fn move_position_forward(&self) -> Self {
         Self {
@@ -108,4 +113,86 @@ impl Location {
             ..*self
         }
     }
+
+    /// Returns the text of the source covered by this range.
+    ///
+    /// The `index` and `length` count chars rather than bytes,
+    /// the part of the range beyond the end of the source is ignored.
+    pub fn extract<'a>(&self, source: &'a str) -> &'a str {
+        let byte_offset = |char_index: usize| {
+            source
+                .char_indices()
+                .map(|(offset, _)| offset)
+                .nth(char_index)
+                .unwrap_or(source.len())
+        };
+
+        let start = byte_offset(self.index);
+        let end = byte_offset(self.index + self.length);
+        &source[start..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        location::Location, parser::parse_from_str, token::Token, tokens::normalize_tokens,
+    };
+
+    #[test]
+    fn test_from_range_pair() {
+        let first = Location::new_range(2, 0, 2, 3);
+        let second = Location::new_range(10, 1, 4, 5);
+
+        assert_eq!(
+            Location::from_range_pair(&first, &second),
+            Location::new_range(2, 0, 2, 13)
+        );
+        assert_eq!(
+            Location::from_range_pair(&first, &first),
+            Location::new_range(2, 0, 2, 3)
+        );
+
+        // the reversed ranges do not overflow
+        assert_eq!(
+            Location::from_range_pair(&second, &first),
+            Location::new_range(10, 1, 4, 0)
+        );
+        assert_eq!(
+            Location::from_range_pair(&second, &Location::new_range(8, 1, 2, 4)),
+            Location::new_range(10, 1, 4, 2)
+        );
+    }
+
+    #[test]
+    fn test_extract() {
+        let s = r#"{name: "文字", inner: {id: 11, tag: "🍒"}, tail: 'α'}"#;
+
+        assert_eq!(Location::new_range(1, 0, 1, 4).extract(s), "name");
+        assert_eq!(Location::new_range(7, 0, 7, 4).extract(s), r#""文字""#);
+        assert_eq!(Location::new_position(3, 0, 3).extract(s), "");
+        assert_eq!(Location::new_range(46, 0, 46, 10).extract(s), "'α'}");
+
+        // extract the nested object by the range of its braces
+        let tokens = normalize_tokens(s).unwrap();
+        let start = tokens
+            .iter()
+            .filter(|t| t.token == Token::LeftBrace)
+            .nth(1)
+            .unwrap();
+        let end = tokens
+            .iter()
+            .find(|t| t.token == Token::RightBrace)
+            .unwrap();
+
+        let range = Location::from_range_pair(&start.range, &end.range);
+        let text = range.extract(s);
+        assert_eq!(text, r#"{id: 11, tag: "🍒"}"#);
+        assert_eq!(
+            parse_from_str(text).unwrap(),
+            parse_from_str(r#"{id: 11, tag: "🍒"}"#).unwrap()
+        );
+    }
 }
@@ -9,17 +9,33 @@ use std::ops::Neg;
 use crate::{
     location::Location,
     peekableiter::PeekableIter,
-    token::{NumberToken, Token, TokenWithRange},
+    token::{Comment, NumberToken, Token, TokenWithRange},
     AsonError,
 };
 
 pub struct ClearTokenIter<'a> {
     upstream: &'a mut dyn Iterator<Item = Result<TokenWithRange, AsonError>>,
+
+    // the list which keeps the removed comments and their locations.
+    comments: Option<&'a mut Vec<(Comment, Location)>>,
 }
 
 impl<'a> ClearTokenIter<'a> {
     pub fn new(upstream: &'a mut dyn Iterator<Item = Result<TokenWithRange, AsonError>>) -> Self {
-        Self { upstream }
+        Self {
+            upstream,
+            comments: None,
+        }
+    }
+
+    pub fn with_comments(
+        upstream: &'a mut dyn Iterator<Item = Result<TokenWithRange, AsonError>>,
+        comments: &'a mut Vec<(Comment, Location)>,
+    ) -> Self {
+        Self {
+            upstream,
+            comments: Some(comments),
+        }
     }
 }
 
@@ -32,18 +48,22 @@ impl Iterator for ClearTokenIter<'_> {
 }
 
 // - remove all comments.
+// - keep the comments if it is required.
 fn clean(iter: &mut ClearTokenIter) -> Option<Result<TokenWithRange, AsonError>> {
     loop {
         match iter.upstream.next() {
             Some(result) => {
-                match &result {
+                match result {
                     Ok(TokenWithRange {
-                        token: Token::Comment(_),
-                        ..
+                        token: Token::Comment(comment),
+                        range,
                     }) => {
                         // consume comments
+                        if let Some(comments) = &mut iter.comments {
+                            comments.push((comment, range));
+                        }
                     }
-                    _ => {
+                    result => {
                         return Some(result);
                     }
                 }
@@ -55,13 +75,35 @@ fn clean(iter: &mut ClearTokenIter) -> Option<Result<TokenWithRange, AsonError>>
     }
 }
 
+// the max number of tokens that the normalizer and trimmer peek.
+pub const NORMALIZER_PEEK_TOKEN_MAX_COUNT: usize = 1;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct NormalizeOptions {
+    // report an error instead of removing the '+' in front of numbers.
+    pub forbid_redundant_plus: bool,
+}
+
 pub struct NormalizedTokenIter<'a> {
     upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>,
+    options: NormalizeOptions,
 }
 
 impl<'a> NormalizedTokenIter<'a> {
     pub fn new(upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>) -> Self {
-        Self { upstream }
+        Self::with_options(upstream, NormalizeOptions::default())
+    }
+
+    pub fn with_options(
+        upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>,
+        options: NormalizeOptions,
+    ) -> Self {
+        debug_assert!(
+            upstream.capacity() >= NORMALIZER_PEEK_TOKEN_MAX_COUNT,
+            "The capacity of the upstream of normalizer is too small."
+        );
+
+        Self { upstream, options }
     }
 }
 
@@ -85,7 +127,8 @@ impl Iterator for NormalizedTokenIter<'_> {
 //     + comma + comment(s) + comma => comma + comma
 //     + blank(s) + comment(s) + blank(s) => blank
 //
-// - remove the '+' tokens in front of numbers (includes `+Inf`).
+// - remove the '+' tokens in front of numbers (includes `+Inf`),
+//   or report an error if the option `forbid_redundant_plus` is set.
 // - apple the '-' tokens to numbers (includes `-Inf`).
 // - checks if the signed number is overflowed.
 //   note that the lexer does not check the valid range of a signed integer
@@ -167,6 +210,15 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                     }
                     Token::Plus => {
                         match iter.upstream.peek(0) {
+                            Some(Ok(TokenWithRange {
+                                token: Token::Number(_),
+                                ..
+                            })) if iter.options.forbid_redundant_plus => {
+                                Some(Err(AsonError::MessageWithLocation(
+                                    "Redundant plus sign.".to_owned(),
+                                    start_range,
+                                )))
+                            }
                             Some(Ok(TokenWithRange {
                                 token: Token::Number(num),
                                 range: current_range,
@@ -214,6 +266,13 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                             Location::from_range_pair(&start_range, current_range),
                                         )))
                                     }
+                                    NumberToken::I128(v) if *v > i128::MAX as u128 => {
+                                        // check signed number overflow
+                                        Some(Err(AsonError::MessageWithLocation(
+                                            format!("The i128 number {} is overflowed.", v),
+                                            Location::from_range_pair(&start_range, current_range),
+                                        )))
+                                    }
                                     _ => {
                                         // consumes the the plus sign (it's already done) and the
                                         // number token.
@@ -319,21 +378,25 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                         let combined_range =
                                             Location::from_range_pair(&start_range, current_range);
 
-                                        let parse_result =
-                                            format!("-{}", v).parse::<i8>().map_err(|_| {
+                                        // the magnitude can be at most the absolute value
+                                        // of `i8::MIN`, and the negative number is stored
+                                        // in the bit pattern of `u8`.
+                                        let negate_result = (*v <= i8::MIN.unsigned_abs())
+                                            .then(|| v.wrapping_neg())
+                                            .ok_or_else(|| {
                                                 AsonError::MessageWithLocation(
                                                     format!(
-                                                        "Can not convert \"{}\" to negative i8",
+                                                        "Can not convert \"{}\" to negative i8.",
                                                         v
                                                     ),
                                                     combined_range,
                                                 )
                                             });
 
-                                        match parse_result {
+                                        match negate_result {
                                             Ok(v) => {
                                                 let ret_val = Some(Ok(TokenWithRange::new(
-                                                    Token::Number(NumberToken::I8(v as u8)),
+                                                    Token::Number(NumberToken::I8(v)),
                                                     combined_range,
                                                 )));
 
@@ -349,8 +412,12 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                         let combined_range =
                                             Location::from_range_pair(&start_range, current_range);
 
-                                        let parse_result =
-                                            format!("-{}", v).parse::<i16>().map_err(|_| {
+                                        // the magnitude can be at most the absolute value
+                                        // of `i16::MIN`, and the negative number is stored
+                                        // in the bit pattern of `u16`.
+                                        let negate_result = (*v <= i16::MIN.unsigned_abs())
+                                            .then(|| v.wrapping_neg())
+                                            .ok_or_else(|| {
                                                 AsonError::MessageWithLocation(
                                                     format!(
                                                         "Can not convert \"{}\" to negative i16.",
@@ -360,10 +427,10 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                                 )
                                             });
 
-                                        match parse_result {
+                                        match negate_result {
                                             Ok(v) => {
                                                 let ret_val = Some(Ok(TokenWithRange::new(
-                                                    Token::Number(NumberToken::I16(v as u16)),
+                                                    Token::Number(NumberToken::I16(v)),
                                                     combined_range,
                                                 )));
 
@@ -379,8 +446,12 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                         let combined_range =
                                             Location::from_range_pair(&start_range, current_range);
 
-                                        let parse_result =
-                                            format!("-{}", v).parse::<i32>().map_err(|_| {
+                                        // the magnitude can be at most the absolute value
+                                        // of `i32::MIN`, and the negative number is stored
+                                        // in the bit pattern of `u32`.
+                                        let negate_result = (*v <= i32::MIN.unsigned_abs())
+                                            .then(|| v.wrapping_neg())
+                                            .ok_or_else(|| {
                                                 AsonError::MessageWithLocation(
                                                     format!(
                                                         "Can not convert \"{}\" to negative i32.",
@@ -390,10 +461,10 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                                 )
                                             });
 
-                                        match parse_result {
+                                        match negate_result {
                                             Ok(v) => {
                                                 let ret_val = Some(Ok(TokenWithRange::new(
-                                                    Token::Number(NumberToken::I32(v as u32)),
+                                                    Token::Number(NumberToken::I32(v)),
                                                     combined_range,
                                                 )));
 
@@ -409,8 +480,12 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                         let combined_range =
                                             Location::from_range_pair(&start_range, current_range);
 
-                                        let parse_result =
-                                            format!("-{}", v).parse::<i64>().map_err(|_| {
+                                        // the magnitude can be at most the absolute value
+                                        // of `i64::MIN`, and the negative number is stored
+                                        // in the bit pattern of `u64`.
+                                        let negate_result = (*v <= i64::MIN.unsigned_abs())
+                                            .then(|| v.wrapping_neg())
+                                            .ok_or_else(|| {
                                                 AsonError::MessageWithLocation(
                                                     format!(
                                                         "Can not convert \"{}\" to negative i64.",
@@ -420,10 +495,44 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                                 )
                                             });
 
-                                        match parse_result {
+                                        match negate_result {
                                             Ok(v) => {
                                                 let ret_val = Some(Ok(TokenWithRange::new(
-                                                    Token::Number(NumberToken::I64(v as u64)),
+                                                    Token::Number(NumberToken::I64(v)),
+                                                    combined_range,
+                                                )));
+
+                                                // consume the minus sign (already done) and the number literal token
+                                                iter.next();
+
+                                                ret_val
+                                            }
+                                            Err(e) => Some(Err(e)),
+                                        }
+                                    }
+                                    NumberToken::I128(v) => {
+                                        let combined_range =
+                                            Location::from_range_pair(&start_range, current_range);
+
+                                        // the magnitude can be at most the absolute value
+                                        // of `i128::MIN`, and the negative number is stored
+                                        // in the bit pattern of `u128`.
+                                        let negate_result = (*v <= i128::MIN.unsigned_abs())
+                                            .then(|| v.wrapping_neg())
+                                            .ok_or_else(|| {
+                                                AsonError::MessageWithLocation(
+                                                    format!(
+                                                        "Can not convert \"{}\" to negative i128.",
+                                                        v
+                                                    ),
+                                                    combined_range,
+                                                )
+                                            });
+
+                                        match negate_result {
+                                            Ok(v) => {
+                                                let ret_val = Some(Ok(TokenWithRange::new(
+                                                    Token::Number(NumberToken::I128(v)),
                                                     combined_range,
                                                 )));
 
@@ -438,7 +547,8 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                     NumberToken::U8(_)
                                     | NumberToken::U16(_)
                                     | NumberToken::U32(_)
-                                    | NumberToken::U64(_) => {
+                                    | NumberToken::U64(_)
+                                    | NumberToken::U128(_) => {
                                         Some(Err(AsonError::MessageWithLocation(
                                             "The minus sign cannot be applied to unsigned numbers."
                                                 .to_owned(),
@@ -494,6 +604,13 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                             start_range,
                         )))
                     }
+                    Token::Number(NumberToken::I128(v)) if *v > i128::MAX as u128 => {
+                        // check signed number overflow
+                        Some(Err(AsonError::MessageWithLocation(
+                            format!("The i128 number {} is overflowed.", v),
+                            start_range,
+                        )))
+                    }
                     _ => Some(result),
                 }
             }
@@ -509,6 +626,11 @@ pub struct TrimmedTokenIter<'a> {
 
 impl<'a> TrimmedTokenIter<'a> {
     pub fn new(upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>) -> Self {
+        debug_assert!(
+            upstream.capacity() >= NORMALIZER_PEEK_TOKEN_MAX_COUNT,
+            "The capacity of the upstream of trimmer is too small."
+        );
+
         // consume the first '\n of document
         if let Some(Ok(TokenWithRange {
             token: Token::NewLine,
@@ -565,18 +687,31 @@ mod tests {
         AsonError,
     };
 
-    use super::{ClearTokenIter, NormalizedTokenIter, TrimmedTokenIter};
+    use super::{
+        ClearTokenIter, NormalizeOptions, NormalizedTokenIter, TrimmedTokenIter,
+        NORMALIZER_PEEK_TOKEN_MAX_COUNT,
+    };
 
     fn lex_from_str(s: &str) -> Result<Vec<TokenWithRange>, AsonError> {
+        lex_from_str_with_options(s, NormalizeOptions::default())
+    }
+
+    fn lex_from_str_with_options(
+        s: &str,
+        options: NormalizeOptions,
+    ) -> Result<Vec<TokenWithRange>, AsonError> {
         let mut chars = s.chars();
         let mut char_position_iter = CharsWithPositionIter::new(&mut chars);
         let mut peekable_char_position_iter =
             PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
         let mut lexer = Lexer::new(&mut peekable_char_position_iter);
         let mut clear_iter = ClearTokenIter::new(&mut lexer);
-        let mut peekable_clear_iter = PeekableIter::new(&mut clear_iter, 1);
-        let mut normalized_iter = NormalizedTokenIter::new(&mut peekable_clear_iter);
-        let mut peekable_normalized_iter = PeekableIter::new(&mut normalized_iter, 1);
+        let mut peekable_clear_iter =
+            PeekableIter::new(&mut clear_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
+        let mut normalized_iter =
+            NormalizedTokenIter::with_options(&mut peekable_clear_iter, options);
+        let mut peekable_normalized_iter =
+            PeekableIter::new(&mut normalized_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
         let trimmed_iter = TrimmedTokenIter::new(&mut peekable_normalized_iter);
 
         // do not use `iter.collect::<Vec<_>>()` because the `TokenIter` throws
@@ -1217,6 +1352,49 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_normalize_negative_zero_integers() {
+        assert_eq!(
+            lex_from_str_without_location("-0").unwrap(),
+            vec![Token::Number(NumberToken::I32(0))]
+        );
+
+        assert_eq!(
+            lex_from_str_without_location("-0_i8").unwrap(),
+            vec![Token::Number(NumberToken::I8(0))]
+        );
+
+        assert_eq!(
+            lex_from_str_without_location("-0_i16").unwrap(),
+            vec![Token::Number(NumberToken::I16(0))]
+        );
+
+        assert_eq!(
+            lex_from_str_without_location("-0_i64").unwrap(),
+            vec![Token::Number(NumberToken::I64(0))]
+        );
+
+        assert_eq!(
+            lex_from_str_without_location("-0x0_i32").unwrap(),
+            vec![Token::Number(NumberToken::I32(0))]
+        );
+
+        assert_eq!(
+            lex_from_str_without_location("-0x00_i8").unwrap(),
+            vec![Token::Number(NumberToken::I8(0))]
+        );
+
+        assert_eq!(
+            lex_from_str_without_location("-0b0_i64").unwrap(),
+            vec![Token::Number(NumberToken::I64(0))]
+        );
+
+        assert_eq!(
+            lex_from_str_without_location("-0b0_i16").unwrap(),
+            vec![Token::Number(NumberToken::I16(0))]
+        );
+    }
+
     #[test]
     fn test_normalize_signed_integer_overflow_decimal() {
         assert!(matches!(
@@ -1290,6 +1468,141 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_normalize_negative_integer_boundaries() {
+        // the negation must produce the same results as
+        // parsing the text of the negative number.
+        fn check(magnitude: u64, suffix: &str, expected: Option<NumberToken>) {
+            let text = format!("-{}{}", magnitude, suffix);
+            match expected {
+                Some(number) => assert_eq!(
+                    lex_from_str_without_location(&text).unwrap(),
+                    vec![Token::Number(number)]
+                ),
+                None => assert!(lex_from_str_without_location(&text).is_err()),
+            }
+        }
+
+        for v in [0_u64, 1, 127, 128, 129, 255] {
+            let expected = format!("-{}", v).parse::<i8>().ok();
+            check(v, "_i8", expected.map(|v| NumberToken::I8(v as u8)));
+        }
+
+        for v in [0_u64, 1, 32767, 32768, 32769, 65535] {
+            let expected = format!("-{}", v).parse::<i16>().ok();
+            check(v, "_i16", expected.map(|v| NumberToken::I16(v as u16)));
+        }
+
+        for v in [0_u64, 1, 2147483647, 2147483648, 2147483649, 4294967295] {
+            let expected = format!("-{}", v).parse::<i32>().ok();
+            check(v, "_i32", expected.map(|v| NumberToken::I32(v as u32)));
+        }
+
+        for v in [
+            0_u64,
+            1,
+            9223372036854775807,
+            9223372036854775808,
+            9223372036854775809,
+            u64::MAX,
+        ] {
+            let expected = format!("-{}", v).parse::<i64>().ok();
+            check(v, "_i64", expected.map(|v| NumberToken::I64(v as u64)));
+        }
+
+        assert_eq!(
+            lex_from_str_without_location("-9223372036854775808_i64").unwrap(),
+            vec![Token::Number(NumberToken::I64(i64::MIN as u64))]
+        );
+
+        for v in [
+            0_u128,
+            1,
+            i128::MAX as u128,
+            i128::MIN.unsigned_abs(),
+            i128::MIN.unsigned_abs() + 1,
+            u128::MAX,
+        ] {
+            let text = format!("-{}_i128", v);
+            match format!("-{}", v).parse::<i128>() {
+                Ok(expected) => assert_eq!(
+                    lex_from_str_without_location(&text).unwrap(),
+                    vec![Token::Number(NumberToken::I128(expected as u128))]
+                ),
+                Err(_) => assert!(lex_from_str_without_location(&text).is_err()),
+            }
+        }
+
+        // err: positive overflow
+        assert!(
+            lex_from_str_without_location("170141183460469231731687303715884105728_i128").is_err()
+        );
+        assert!(
+            lex_from_str_without_location("+170141183460469231731687303715884105728_i128").is_err()
+        );
+
+        // err: unsigned
+        assert!(lex_from_str_without_location("-1_u128").is_err());
+    }
+
+    #[test]
+    fn test_normalize_forbid_redundant_plus() {
+        let options = NormalizeOptions {
+            forbid_redundant_plus: true,
+        };
+
+        assert_eq!(
+            lex_from_str_with_options("-5", options.clone()).unwrap(),
+            vec![TokenWithRange::from_position_and_length(
+                Token::Number(NumberToken::I32((-5_i32) as u32)),
+                &Location::new_position(0, 0, 0),
+                2
+            )]
+        );
+
+        assert_eq!(
+            lex_from_str_with_options("5", options.clone()).unwrap(),
+            vec![TokenWithRange::from_position_and_length(
+                Token::Number(NumberToken::I32(5)),
+                &Location::new_position(0, 0, 0),
+                1
+            )]
+        );
+
+        // err: redundant plus sign
+        assert!(matches!(
+            lex_from_str_with_options("+5", options.clone()),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 1
+                }
+            ))
+        ));
+
+        assert!(matches!(
+            lex_from_str_with_options("[11, +1.5]", options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 5,
+                    line: 0,
+                    column: 5,
+                    length: 1
+                }
+            ))
+        ));
+
+        // the plus sign is removed by default
+        assert_eq!(
+            lex_from_str_without_location("+5").unwrap(),
+            vec![Token::Number(NumberToken::I32(5))]
+        );
+    }
+
     #[test]
     fn test_normalize_plus_and_minus_floating_point_numbers() {
         // general
@@ -7,67 +7,660 @@
 use std::io::Read;
 
 use crate::{
-    ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant},
+    ast::{AsonNode, Comments, KeyValuePair, NameValuePair, NodeRange, Number, Variant},
     charstream::CharStream,
     charwithposition::CharsWithPositionIter,
-    lexer::{Lexer, LEXER_PEEK_CHAR_MAX_COUNT},
+    lexer::{Lexer, LexerOptions, DEFAULT_MAX_NUMBER_DIGITS, LEXER_PEEK_CHAR_MAX_COUNT},
     location::Location,
-    normalizer::{ClearTokenIter, NormalizedTokenIter, TrimmedTokenIter},
+    normalizer::{
+        ClearTokenIter, NormalizeOptions, NormalizedTokenIter, TrimmedTokenIter,
+        NORMALIZER_PEEK_TOKEN_MAX_COUNT,
+    },
     peekableiter::PeekableIter,
-    token::{NumberToken, Token, TokenWithRange},
+    token::{
+        Comment, CommentStyle, Directive, FloatType, IntegerType, NumberToken, Token,
+        TokenWithRange,
+    },
+    tokens::Tokens,
     AsonError,
 };
 
 pub const PARSER_PEEK_TOKEN_MAX_COUNT: usize = 3;
 
+// the text following the comment opener, i.e. the directive `//! ...` or `#! ...`.
+pub const DEFAULT_DIRECTIVE_PREFIX: &str = "!";
+
+/// The separator between the top-level documents of a multi-document
+/// text, see `parse_all_with_options` and `from_str_all_with_options`.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum DocumentSeparator {
+    /// The documents are separated by whitespaces or new-lines,
+    /// e.g. `{id: 11} {id: 13}`.
+    #[default]
+    Whitespace,
+
+    /// Each document starts on a new line (like NDJSON).
+    NewLine,
+
+    /// The documents are separated by commas, e.g. `{id: 11}, {id: 13}`,
+    /// a trailing comma is allowed.
+    Comma,
+
+    /// The documents are separated by the char, e.g. `{id: 11}; {id: 13}`
+    /// with `Custom(';')`, a trailing separator is allowed.
+    ///
+    /// The char must have no other meaning in ASON, i.e. an ASCII punctuation
+    /// other than the brackets, delimiters, signs and comment openers,
+    /// e.g. `;`, `|` and `~`.
+    Custom(char),
+}
+
+impl DocumentSeparator {
+    // the chars which have a meaning in ASON, e.g. `,` and `#`.
+    const RESERVED_CHARS: &str = ",:{}[]()+-@\"'#/_.";
+
+    // the custom separator char which is lexed as `Token::DocumentSeparator`.
+    pub(crate) fn custom_char(&self) -> Option<char> {
+        match self {
+            DocumentSeparator::Custom(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            DocumentSeparator::Whitespace => "whitespace".to_owned(),
+            DocumentSeparator::NewLine => "new-line".to_owned(),
+            DocumentSeparator::Comma => "comma".to_owned(),
+            DocumentSeparator::Custom(c) => format!("'{}'", c),
+        }
+    }
+}
+
+// checks that the custom separator has no other meaning in ASON,
+// otherwise the documents could never be separated by it.
+pub(crate) fn check_document_separator(separator: DocumentSeparator) -> Result<(), AsonError> {
+    match separator {
+        DocumentSeparator::Custom(c)
+            if !c.is_ascii_punctuation() || DocumentSeparator::RESERVED_CHARS.contains(c) =>
+        {
+            Err(AsonError::Message(format!(
+                "The char '{}' can not be the document separator.",
+                c
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Options for parsing.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ParseOptions {
+    /// Report an error when a plus sign precedes a number, e.g. `+5`,
+    /// instead of silently removing it.
+    pub forbid_redundant_plus: bool,
+
+    /// The style of line comments to accept in addition to `//`.
+    pub comment_style: CommentStyle,
+
+    /// Report an error when a char or string contains the null char.
+    pub forbid_interior_nulls: bool,
+
+    /// Abort with an error once the number of parsed nodes exceeds this count.
+    pub max_total_nodes: Option<usize>,
+
+    /// The type of the integer literals without type suffix.
+    pub default_integer_type: IntegerType,
+
+    /// The type of the floating-point literals without type suffix.
+    pub default_float_type: FloatType,
+
+    /// Join the adjacent strings which are separated only by whitespaces
+    /// or a new-line into one string, e.g. `"foo" "bar"` => `"foobar"`.
+    pub join_adjacent_strings: bool,
+
+    /// Report an error when a numeric literal has more digits than
+    /// this count, it is 512 when not specified.
+    pub max_number_digits: Option<usize>,
+
+    /// Skip the shebang line (e.g. `#!/usr/bin/env ason`) at
+    /// the very beginning of the document.
+    pub allow_shebang: bool,
+
+    /// Skip the document marker line (`---`) at the very beginning
+    /// of the document, e.g. the ASON text after the YAML-style front matter.
+    pub allow_document_marker: bool,
+
+    /// Require a comma between the elements of lists, tuples, objects
+    /// and maps, i.e. the elements can not be separated by new-lines only.
+    pub require_comma_separators: bool,
+
+    /// Keep the unknown escape sequences of strings verbatim
+    /// (e.g. `"\q"` as `\q`) instead of reporting an error.
+    pub lenient_escapes: bool,
+
+    /// Report an error when the key of an object is not an identifier,
+    /// e.g. the string key `"name"`, which is otherwise accepted for
+    /// the key which is not a valid identifier.
+    pub require_identifier_keys: bool,
+
+    /// Report an error when the document has more bytes than this count,
+    /// it is checked before lexing, so the oversized document is rejected
+    /// immediately. It applies to the string (`&str`) entry points only.
+    pub max_input_bytes: Option<usize>,
+
+    /// The separator between the top-level documents for `parse_all_with_options`.
+    pub document_separator: DocumentSeparator,
+
+    /// The prefix (following the `//` or `#`) of the line comments which are
+    /// collected by `parse_with_directives_with_options`, it is `!`
+    /// (i.e. `//! ...`) when not specified.
+    pub directive_prefix: Option<String>,
+}
+
 pub fn parse_from_str(s: &str) -> Result<AsonNode, AsonError> {
+    parse_from_str_with_options(s, &ParseOptions::default())
+}
+
+pub fn parse_from_str_with_options(s: &str, options: &ParseOptions) -> Result<AsonNode, AsonError> {
+    check_input_bytes(s, options.max_input_bytes)?;
+
     let mut chars = s.chars();
-    parse_from_char_stream(&mut chars)
+    parse_from_char_stream(&mut chars, options)
+}
+
+pub fn parse_from_reader<R: Read>(r: R) -> Result<AsonNode, AsonError> {
+    parse_from_reader_with_options(r, &ParseOptions::default())
 }
 
-pub fn parse_from_reader<R: Read>(mut r: R) -> Result<AsonNode, AsonError> {
+pub fn parse_from_reader_with_options<R: Read>(
+    mut r: R,
+    options: &ParseOptions,
+) -> Result<AsonNode, AsonError> {
     let mut char_stream = CharStream::new(&mut r);
-    parse_from_char_stream(&mut char_stream)
+    parse_from_char_stream(&mut char_stream, options)
+}
+
+/// The handler of the custom literal `@name(value)`.
+///
+/// The parser calls the handler with the `name` and the node enclosed
+/// in the parentheses, and the node returned by the handler takes
+/// the place of the literal, e.g. an `AsonNode::Extension`.
+pub type ExtensionHandler = dyn Fn(&str, AsonNode) -> Result<AsonNode, AsonError>;
+
+pub fn parse_from_str_with_extension(
+    s: &str,
+    options: &ParseOptions,
+    extension_handler: &ExtensionHandler,
+) -> Result<AsonNode, AsonError> {
+    check_input_bytes(s, options.max_input_bytes)?;
+
+    let mut chars = s.chars();
+    parse_from_char_stream_with_extension(&mut chars, options, Some(extension_handler))
 }
 
 pub fn parse_from_char_stream(
     char_stream: &mut dyn Iterator<Item = char>,
+    options: &ParseOptions,
+) -> Result<AsonNode, AsonError> {
+    parse_from_char_stream_with_extension(char_stream, options, None)
+}
+
+fn parse_from_char_stream_with_extension(
+    char_stream: &mut dyn Iterator<Item = char>,
+    options: &ParseOptions,
+    extension_handler: Option<&ExtensionHandler>,
 ) -> Result<AsonNode, AsonError> {
+    with_token_peekable_iter(char_stream, options, None, |upstream| {
+        parse_from_token_peekable_iter(upstream, options, extension_handler)
+    })
+}
+
+/// Parses the document and collects the directive comments,
+/// e.g. `//! include: "other.ason"`, for a post-processor to act on.
+pub fn parse_with_directives(s: &str) -> Result<(AsonNode, Vec<Directive>), AsonError> {
+    parse_with_directives_with_options(s, &ParseOptions::default())
+}
+
+pub fn parse_with_directives_with_options(
+    s: &str,
+    options: &ParseOptions,
+) -> Result<(AsonNode, Vec<Directive>), AsonError> {
+    check_input_bytes(s, options.max_input_bytes)?;
+
+    let mut chars = s.chars();
+    let mut comments = vec![];
+
+    let node = with_token_peekable_iter(&mut chars, options, Some(&mut comments), |upstream| {
+        parse_from_token_peekable_iter(upstream, options, None)
+    })?;
+
+    let directive_prefix = options
+        .directive_prefix
+        .as_deref()
+        .unwrap_or(DEFAULT_DIRECTIVE_PREFIX);
+
+    let directives = comments
+        .into_iter()
+        .filter_map(|(comment, location)| match comment {
+            Comment::Line(text, _) => text.strip_prefix(directive_prefix).map(|text| Directive {
+                text: text.trim().to_owned(),
+                location,
+            }),
+            Comment::Block(_) => None,
+        })
+        .collect();
+
+    Ok((node, directives))
+}
+
+/// Parses the document and keeps the comments, the comments are attached
+/// to the nearest node (or key-value pair), and they can be re-emitted by
+/// `print_to_string_with_comments`, so the commented documents (e.g. the
+/// hand-edited configuration files) survive reading and rewriting.
+///
+/// A comment is attached to:
+///
+/// - the element which ends on the same line before it, as a trailing comment.
+/// - otherwise the element which follows it, as a leading comment.
+/// - otherwise the list, object or map which encloses it, as a dangling comment.
+///
+/// The comments inside tuples are moved to the nearest element outside the tuple.
+pub fn parse_from_str_with_comments(
+    s: &str,
+    options: &ParseOptions,
+) -> Result<(AsonNode, Comments), AsonError> {
+    check_input_bytes(s, options.max_input_bytes)?;
+
+    let mut chars = s.chars();
+    let mut comments = vec![];
+
+    let (node, anchors) =
+        with_token_peekable_iter(&mut chars, options, Some(&mut comments), |upstream| {
+            let mut parser = Parser::new(upstream, options.clone(), None);
+            parser.anchors = Some(CommentAnchors::default());
+            let root = parser.parse_root()?;
+            Ok((root, parser.anchors.unwrap()))
+        })?;
+
+    Ok((node, attach_comments(comments, &anchors)))
+}
+
+/// Parses the document with error recovery, returns the best-effort tree
+/// and all the collected errors, e.g. for IDEs.
+///
+/// An invalid value of a list, tuple, object or map is reported and skipped
+/// (up to the next comma, new-line or the closing bracket), and the parsing
+/// continues with the next element, so the valid elements remain in the tree.
+/// The other errors (e.g. an invalid char, a missing bracket or the
+/// unexpected end of document) stop the parsing, and the tree is `None` then.
+///
+/// The errors are in the order of their locations.
+pub fn try_from_str(s: &str) -> (Option<AsonNode>, Vec<(Location, String)>) {
+    let options = ParseOptions::default();
+    let mut chars = s.chars();
+
+    let result = with_token_peekable_iter(&mut chars, &options, None, |upstream| {
+        let mut parser = Parser::new(upstream, options.clone(), None);
+        parser.errors = Some(vec![]);
+
+        let root = parser.parse_root();
+        let mut errors = parser.errors.take().unwrap();
+
+        let root = match root {
+            Ok(root) => Some(root),
+            Err(AsonError::MessageWithLocation(message, location)) => {
+                errors.push((location, message));
+                None
+            }
+            Err(e) => {
+                // the error without location, e.g. the unexpected end of document.
+                errors.push((parser.last_range.get_position_by_range_end(), e.to_string()));
+                None
+            }
+        };
+
+        Ok((root, errors))
+    });
+
+    // the closure returns no error.
+    result.unwrap()
+}
+
+/// Parses all top-level nodes of the document, e.g. a document
+/// consisting of values separated by new-lines (like NDJSON).
+pub fn parse_all(s: &str) -> Result<Vec<AsonNode>, AsonError> {
+    parse_all_with_options(s, &ParseOptions::default())
+}
+
+pub fn parse_all_with_options(s: &str, options: &ParseOptions) -> Result<Vec<AsonNode>, AsonError> {
+    check_input_bytes(s, options.max_input_bytes)?;
+    check_document_separator(options.document_separator)?;
+
+    let mut chars = s.chars();
+
+    with_token_peekable_iter(&mut chars, options, None, |upstream| {
+        parse_all_from_token_peekable_iter(upstream, options)
+    })
+}
+
+// checks the length of the document before any other work.
+pub(crate) fn check_input_bytes(s: &str, max_input_bytes: Option<usize>) -> Result<(), AsonError> {
+    match max_input_bytes {
+        Some(max_input_bytes) if s.len() > max_input_bytes => Err(AsonError::Message(format!(
+            "The document exceeds the maximum length of {} bytes.",
+            max_input_bytes
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Parses the document and keeps the location of each node, it is useful
+/// for mapping a value back to the source text, e.g. for showing the inline
+/// errors and hover information in editors.
+///
+/// The tree of `NodeRange` has the same shape as the node, e.g. the range of
+/// the node `root.get("tags")` is `ranges.children[1].range` if "tags" is the
+/// second key of the root object.
+pub fn parse_from_str_with_ranges(
+    s: &str,
+    options: &ParseOptions,
+) -> Result<(AsonNode, NodeRange), AsonError> {
+    check_input_bytes(s, options.max_input_bytes)?;
+
+    let mut chars = s.chars();
+
+    with_token_peekable_iter(&mut chars, options, None, |upstream| {
+        let mut parser = Parser::new(upstream, options.clone(), None);
+        parser.ranges = Some(vec![vec![]]);
+        let root = parser.parse_root()?;
+        Ok((root, parser.ranges.unwrap().remove(0).remove(0)))
+    })
+}
+
+// builds the token pipeline upon the char stream and
+// then passes the final token iterator to the function `f`.
+// the comments are collected into `comments` if it is present.
+fn with_token_peekable_iter<R, F>(
+    char_stream: &mut dyn Iterator<Item = char>,
+    options: &ParseOptions,
+    comments: Option<&mut Vec<(Comment, Location)>>,
+    f: F,
+) -> Result<R, AsonError>
+where
+    F: for<'a> FnOnce(
+        &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>,
+    ) -> Result<R, AsonError>,
+{
+    let lexer_options = LexerOptions {
+        comment_style: options.comment_style,
+        forbid_interior_nulls: options.forbid_interior_nulls,
+        default_integer_type: options.default_integer_type,
+        default_float_type: options.default_float_type,
+        max_number_digits: options
+            .max_number_digits
+            .unwrap_or(DEFAULT_MAX_NUMBER_DIGITS),
+        allow_shebang: options.allow_shebang,
+        allow_document_marker: options.allow_document_marker,
+        lenient_escapes: options.lenient_escapes,
+        document_separator: options.document_separator.custom_char(),
+    };
+
+    let normalize_options = NormalizeOptions {
+        forbid_redundant_plus: options.forbid_redundant_plus,
+    };
+
     let mut char_position_iter = CharsWithPositionIter::new(char_stream);
     let mut peekable_char_position_iter =
         PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
-    let mut lexer = Lexer::new(&mut peekable_char_position_iter);
-    let mut clear_iter = ClearTokenIter::new(&mut lexer);
-    let mut peekable_clear_iter = PeekableIter::new(&mut clear_iter, 1);
-    let mut normalized_iter = NormalizedTokenIter::new(&mut peekable_clear_iter);
-    let mut peekable_normalized_iter = PeekableIter::new(&mut normalized_iter, 1);
+    let mut lexer = Lexer::with_options(&mut peekable_char_position_iter, lexer_options);
+    let mut clear_iter = match comments {
+        Some(comments) => ClearTokenIter::with_comments(&mut lexer, comments),
+        None => ClearTokenIter::new(&mut lexer),
+    };
+    let mut peekable_clear_iter =
+        PeekableIter::new(&mut clear_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
+    let mut normalized_iter =
+        NormalizedTokenIter::with_options(&mut peekable_clear_iter, normalize_options);
+    let mut peekable_normalized_iter =
+        PeekableIter::new(&mut normalized_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
     let mut trimmed_iter = TrimmedTokenIter::new(&mut peekable_normalized_iter);
     let mut peekable_trimmed_iter =
         PeekableIter::new(&mut trimmed_iter, PARSER_PEEK_TOKEN_MAX_COUNT);
 
-    let mut parser = Parser::new(&mut peekable_trimmed_iter);
-    let root = parser.parse_node()?;
+    f(&mut peekable_trimmed_iter)
+}
+
+pub fn parse_from_tokens(tokens: &Tokens) -> Result<AsonNode, AsonError> {
+    parse_from_tokens_with_options(tokens, &ParseOptions::default())
+}
+
+/// Parses the tokens with the options, note that the options of the lexer
+/// (e.g. `comment_style` and `default_float_type`) take no effect since
+/// the tokens have been produced.
+pub fn parse_from_tokens_with_options(
+    tokens: &Tokens,
+    options: &ParseOptions,
+) -> Result<AsonNode, AsonError> {
+    let mut token_iter = tokens.iter();
+    let mut peekable_token_iter = PeekableIter::new(&mut token_iter, PARSER_PEEK_TOKEN_MAX_COUNT);
+
+    parse_from_token_peekable_iter(&mut peekable_token_iter, options, None)
+}
+
+fn parse_from_token_peekable_iter<'a>(
+    upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>,
+    options: &ParseOptions,
+    extension_handler: Option<&ExtensionHandler>,
+) -> Result<AsonNode, AsonError> {
+    let mut parser = Parser::new(upstream, options.clone(), extension_handler);
+    parser.parse_root()
+}
 
-    // check trailing token
-    match parser.next_token()? {
-        Some(_) => Err(AsonError::MessageWithLocation(
-            "Document has more than one node.".to_owned(),
-            parser.last_range.get_position_by_range_start(),
-        )),
-        None => Ok(root),
+fn parse_all_from_token_peekable_iter<'a>(
+    upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>,
+    options: &ParseOptions,
+) -> Result<Vec<AsonNode>, AsonError> {
+    let mut parser = Parser::new(upstream, options.clone(), None);
+    let mut nodes = vec![];
+
+    while parser.peek_token(0)?.is_some() {
+        nodes.push(parser.parse_node()?);
+        parser.consume_document_separator()?;
     }
+
+    Ok(nodes)
+}
+
+// the type of the node enclosed in brackets, i.e. `List` or `Map`.
+#[derive(PartialEq)]
+enum ListType {
+    Unknown,
+    List,
+    Map,
+}
+
+// the location of an element (i.e. the item of list, the key-value pair
+// of object or the name-value pair of map) for attaching the comments.
+struct ElementAnchor {
+    path: Vec<usize>,
+    // the index of the first char of the element
+    start: usize,
+    // the range of the last token of the element
+    end: Location,
+}
+
+// the location of the brackets of a list, object or map.
+struct ContainerAnchor {
+    path: Vec<usize>,
+    open: usize,
+    close: usize,
 }
 
-struct Parser<'a> {
+// the locations recorded by the parser for `parse_from_str_with_comments`.
+#[derive(Default)]
+struct CommentAnchors {
+    elements: Vec<ElementAnchor>,
+    containers: Vec<ContainerAnchor>,
+
+    // the path and the start index of the elements being parsed
+    path: Vec<usize>,
+    starts: Vec<usize>,
+}
+
+// the lifetime 'h is of the extension handler.
+struct Parser<'a, 'h> {
     upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>,
     last_range: Location,
+    options: ParseOptions,
+    total_nodes: usize,
+    extension_handler: Option<&'h ExtensionHandler>,
+
+    // it is present only if the comments are preserved.
+    anchors: Option<CommentAnchors>,
+
+    // the stack of the child nodes (with ranges) of the nodes being parsed,
+    // it is present only if the ranges are kept.
+    ranges: Option<Vec<Vec<NodeRange>>>,
+
+    // the errors of the skipped values, it is present only if parsing
+    // with error recovery, see `try_from_str`.
+    errors: Option<Vec<(Location, String)>>,
 }
 
-impl<'a> Parser<'a> {
-    fn new(upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>) -> Self {
+impl<'a, 'h> Parser<'a, 'h> {
+    fn new(
+        upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>,
+        options: ParseOptions,
+        extension_handler: Option<&'h ExtensionHandler>,
+    ) -> Self {
+        debug_assert!(
+            upstream.capacity() >= PARSER_PEEK_TOKEN_MAX_COUNT,
+            "The capacity of the upstream of parser is too small."
+        );
+
         Self {
             upstream,
             last_range: Location::new_range(0, 0, 0, 0),
+            options,
+            total_nodes: 0,
+            extension_handler,
+            anchors: None,
+            ranges: None,
+            errors: None,
+        }
+    }
+
+    // parses the value of an element of list, tuple, object or map.
+    //
+    // when parsing with error recovery, the error of the value is collected,
+    // and the remaining tokens of the value are skipped, then `None` is returned.
+    // the error which occurs in the lexer is not recovered since the lexer
+    // can not continue.
+    fn parse_element_value(&mut self, closing: &Token) -> Result<Option<AsonNode>, AsonError> {
+        match self.parse_node() {
+            Ok(node) => Ok(Some(node)),
+            Err(AsonError::MessageWithLocation(message, location))
+                if self.errors.is_some() && self.peek_token(0).is_ok() =>
+            {
+                self.errors.as_mut().unwrap().push((location, message));
+                self.skip_element(closing)?;
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    // skips the tokens until the separator (comma or new-line) or the
+    // closing bracket of the current container, the nested brackets are
+    // skipped as a whole, and the unpaired closing brackets are skipped as well.
+    fn skip_element(&mut self, closing: &Token) -> Result<(), AsonError> {
+        let mut depth: usize = 0;
+
+        while let Some(token) = self.peek_token(0)? {
+            match token {
+                Token::Comma | Token::NewLine if depth == 0 => break,
+                token if depth == 0 && token == closing => break,
+                Token::LeftBrace | Token::LeftBracket | Token::LeftParen => depth += 1,
+                Token::RightBrace | Token::RightBracket | Token::RightParen => {
+                    depth = depth.saturating_sub(1);
+                }
+                _ => {}
+            }
+            self.next_token()?;
+        }
+
+        Ok(())
+    }
+
+    // the element at `index` of the current list, object, map or tuple starts.
+    fn enter_element(&mut self, index: usize) -> Result<(), AsonError> {
+        let start = self.peek_range(0)?.map_or(0, |range| range.index);
+        if let Some(anchors) = &mut self.anchors {
+            anchors.path.push(index);
+            anchors.starts.push(start);
+        }
+        Ok(())
+    }
+
+    // the element ends, the values of tuples are not anchors because
+    // the tuple is printed in one line.
+    fn leave_element(&mut self, is_anchor: bool) {
+        if let Some(anchors) = &mut self.anchors {
+            let start = anchors.starts.pop().unwrap();
+            if is_anchor {
+                anchors.elements.push(ElementAnchor {
+                    path: anchors.path.clone(),
+                    start,
+                    end: self.last_range,
+                });
+            }
+            anchors.path.pop();
+        }
+    }
+
+    // the number of the anchors recorded so far, see `unnest_anchors`.
+    fn anchor_mark(&self) -> (usize, usize) {
+        self.anchors.as_ref().map_or((0, 0), |anchors| {
+            (anchors.elements.len(), anchors.containers.len())
+        })
+    }
+
+    // the first element of a list is parsed as the name of a map entry
+    // (i.e. at the sub-index `0`) before the type of the list is known,
+    // removes the sub-index from the anchors recorded since the mark
+    // if it turns out to be a list item.
+    fn unnest_anchors(&mut self, mark: (usize, usize)) {
+        if let Some(anchors) = &mut self.anchors {
+            let depth = anchors.path.len();
+            for element in &mut anchors.elements[mark.0..] {
+                element.path.remove(depth);
+            }
+            for container in &mut anchors.containers[mark.1..] {
+                container.path.remove(depth);
+            }
+        }
+    }
+
+    // the closing bracket of the current list, object or map is consumed.
+    fn record_container(&mut self, open: usize) {
+        if let Some(anchors) = &mut self.anchors {
+            anchors.containers.push(ContainerAnchor {
+                path: anchors.path.clone(),
+                open,
+                close: self.last_range.index,
+            });
+        }
+    }
+
+    fn increase_node_count(&mut self) -> Result<(), AsonError> {
+        self.total_nodes += 1;
+
+        match self.options.max_total_nodes {
+            Some(max_total_nodes) if self.total_nodes > max_total_nodes => Err(AsonError::Message(
+                "Document exceeds maximum node count.".to_owned(),
+            )),
+            _ => Ok(()),
         }
     }
 
@@ -135,8 +728,72 @@ impl<'a> Parser<'a> {
         }
     }
 
+    // consume the separator between top-level documents, it reports
+    // an error if the separator is missing and it is not the end of document.
+    fn consume_document_separator(&mut self) -> Result<(), AsonError> {
+        let separated = match self.options.document_separator {
+            DocumentSeparator::Whitespace => {
+                self.consume_new_line_if_exist()?;
+                true
+            }
+            DocumentSeparator::NewLine => self.consume_new_line_if_exist()?,
+            DocumentSeparator::Comma => self.consume_comma_if_exist()?,
+            DocumentSeparator::Custom(_) => {
+                // the separator may be on its own line
+                if self.expect_token(0, &Token::NewLine)?
+                    && self.expect_token(1, &Token::DocumentSeparator)?
+                {
+                    self.next_token()?; // consume '\n'
+                }
+
+                let separated = self.expect_token(0, &Token::DocumentSeparator)?;
+                if separated {
+                    self.next_token()?; // consume the separator
+                    self.consume_new_line_if_exist()?;
+                }
+                separated
+            }
+        };
+
+        match self.peek_range(0)? {
+            Some(range) if !separated => Err(AsonError::MessageWithLocation(
+                format!(
+                    "Expect a {} between documents.",
+                    self.options.document_separator.describe()
+                ),
+                range.get_position_by_range_start(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // consume ',' if it exists.
+    fn consume_comma_if_exist(&mut self) -> Result<bool, AsonError> {
+        match self.peek_token(0)? {
+            Some(Token::Comma) => {
+                self.next_token()?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     // consume '\n' or ',' if they exist.
     fn consume_new_line_or_comma_if_exist(&mut self) -> Result<bool, AsonError> {
+        if self.options.require_comma_separators
+            && self.expect_token(0, &Token::NewLine)?
+            && !matches!(
+                self.peek_token(1)?,
+                Some(Token::RightBracket | Token::RightParen | Token::RightBrace) | None
+            )
+        {
+            // the new-line is allowed only before the closing bracket
+            return Err(AsonError::MessageWithLocation(
+                "Expect a comma between elements.".to_owned(),
+                self.peek_range(0)?.unwrap().get_position_by_range_start(),
+            ));
+        }
+
         match self.peek_token(0)? {
             Some(Token::NewLine | Token::Comma) => {
                 self.next_token()?;
@@ -190,8 +847,58 @@ impl<'a> Parser<'a> {
     }
 }
 
-impl Parser<'_> {
+impl Parser<'_, '_> {
+    fn parse_root(&mut self) -> Result<AsonNode, AsonError> {
+        // no value at all, which is not an incomplete document, see `AsonError::is_incomplete`.
+        if self.peek_token(0)?.is_none() {
+            return Err(AsonError::Message("The document is empty.".to_owned()));
+        }
+
+        let start = self.peek_range(0)?.map_or(0, |range| range.index);
+        let root = self.parse_node()?;
+
+        if let Some(anchors) = &mut self.anchors {
+            anchors.elements.push(ElementAnchor {
+                path: vec![],
+                start,
+                end: self.last_range,
+            });
+        }
+
+        // check trailing token
+        match self.next_token()? {
+            Some(_) => Err(AsonError::MessageWithLocation(
+                "Document has more than one node.".to_owned(),
+                self.last_range.get_position_by_range_start(),
+            )),
+            None => Ok(root),
+        }
+    }
+
     fn parse_node(&mut self) -> Result<AsonNode, AsonError> {
+        if self.ranges.is_none() {
+            return self.parse_node_content();
+        }
+
+        let start = self.peek_range(0)?.copied();
+        self.ranges.as_mut().unwrap().push(vec![]);
+
+        let node = self.parse_node_content()?;
+
+        let ranges = self.ranges.as_mut().unwrap();
+        let children = ranges.pop().unwrap();
+        ranges.last_mut().unwrap().push(NodeRange {
+            // the start exists since the node is parsed successfully.
+            range: Location::from_range_pair(&start.unwrap(), &self.last_range),
+            children,
+        });
+
+        Ok(node)
+    }
+
+    fn parse_node_content(&mut self) -> Result<AsonNode, AsonError> {
+        self.increase_node_count()?;
+
         match self.peek_token(0)? {
             Some(current_token) => {
                 let node = match current_token {
@@ -210,10 +917,15 @@ impl Parser<'_> {
                         self.next_token()?;
                         v
                     }
-                    Token::String(s) => {
-                        let v = AsonNode::String(s.to_owned());
+                    Token::String(s, _) => {
+                        let mut s = s.to_owned();
                         self.next_token()?;
-                        v
+
+                        if self.options.join_adjacent_strings {
+                            self.join_adjacent_strings(&mut s)?;
+                        }
+
+                        AsonNode::String(s)
                     }
                     Token::Date(d) => {
                         let v = AsonNode::DateTime(*d);
@@ -256,6 +968,10 @@ impl Parser<'_> {
                         // tuple: (...)
                         self.parse_tuple()?
                     }
+                    Token::At if self.extension_handler.is_some() => {
+                        // extension: @name(...)
+                        self.parse_extension()?
+                    }
                     _ => {
                         return Err(AsonError::MessageWithLocation(
                             "Unexpected token.".to_owned(),
@@ -272,6 +988,78 @@ impl Parser<'_> {
         }
     }
 
+    fn join_adjacent_strings(&mut self, s: &mut String) -> Result<(), AsonError> {
+        // "foo" "bar"?  //
+        //       ^    ^__// to here
+        //       |_______// current token, UNVALIDATED
+
+        // "foo"  //
+        // "bar"? //
+        // ^    ^_// to here
+        // |______// current token, UNVALIDATED
+
+        loop {
+            let offset = if self.expect_token(0, &Token::NewLine)? {
+                1
+            } else {
+                0
+            };
+
+            // the string followed by a colon is the key of the next map item.
+            if self.expect_token(offset + 1, &Token::Colon)? {
+                break;
+            }
+
+            match self.peek_token(offset)? {
+                Some(Token::String(next, _)) => {
+                    s.push_str(next);
+
+                    if offset == 1 {
+                        self.next_token()?; // consume '\n'
+                    }
+                    self.next_token()?; // consume the string
+                }
+                _ => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_extension(&mut self) -> Result<AsonNode, AsonError> {
+        // @name(...)?  //
+        // ^         ^__// to here
+        // |____________// current token, validated
+
+        self.next_token()?; // consume '@'
+
+        let name = match self.next_token()? {
+            Some(Token::Identifier(name)) => name,
+            Some(_) => {
+                return Err(AsonError::MessageWithLocation(
+                    "Expect the name of extension.".to_owned(),
+                    self.last_range,
+                ))
+            }
+            None => {
+                return Err(AsonError::UnexpectedEndOfDocument(
+                    "Expect the name of extension.".to_owned(),
+                ))
+            }
+        };
+
+        self.consume_token(&Token::LeftParen, "left parenthese")?;
+        self.consume_new_line_if_exist()?;
+
+        let value = self.parse_node()?;
+
+        self.consume_new_line_if_exist()?;
+        self.consume_right_paren()?;
+
+        let handler = self.extension_handler.unwrap();
+        handler(&name, value)
+    }
+
     // includes tuple style and new-type style variant
     fn parse_tuple_variant(&mut self) -> Result<AsonNode, AsonError> {
         // type::member(...)?  //
@@ -325,8 +1113,10 @@ impl Parser<'_> {
             //
             //             is_first_element = false;
 
-            let value = self.parse_node()?;
-            items.push(value);
+            self.enter_element(items.len())?;
+            let value = self.parse_element_value(&Token::RightParen)?;
+            self.leave_element(false);
+            items.extend(value);
 
             let found_sep = self.consume_new_line_or_comma_if_exist()?;
             if !found_sep {
@@ -380,6 +1170,7 @@ impl Parser<'_> {
         // |-------// current token, validated
 
         self.next_token()?; // consume '{'
+        let open = self.last_range.index;
         self.consume_new_line_if_exist()?;
 
         let mut kvps: Vec<KeyValuePair> = vec![];
@@ -417,8 +1208,18 @@ impl Parser<'_> {
             //
             //             is_first_element = false;
 
+            self.enter_element(kvps.len())?;
+
+            // the key which is not a valid identifier is written as a string.
             let name = match self.next_token()? {
                 Some(Token::Identifier(n)) => n,
+                Some(Token::String(..)) if self.options.require_identifier_keys => {
+                    return Err(AsonError::MessageWithLocation(
+                        "Expect an identifier for the key of object.".to_owned(),
+                        self.last_range,
+                    ));
+                }
+                Some(Token::String(n, _)) => n,
                 Some(_) => {
                     return Err(AsonError::MessageWithLocation(
                         "Expect a key name for object.".to_owned(),
@@ -436,12 +1237,16 @@ impl Parser<'_> {
             self.consume_colon()?;
             self.consume_new_line_if_exist()?;
 
-            let value = self.parse_node()?;
-            let name_value_pair = KeyValuePair {
-                key: name,
-                value: Box::new(value),
-            };
-            kvps.push(name_value_pair);
+            let value = self.parse_element_value(&Token::RightBrace)?;
+            self.leave_element(true);
+
+            if let Some(value) = value {
+                let name_value_pair = KeyValuePair {
+                    key: name,
+                    value: Box::new(value),
+                };
+                kvps.push(name_value_pair);
+            }
 
             let found_sep = self.consume_new_line_or_comma_if_exist()?;
             if !found_sep {
@@ -451,6 +1256,7 @@ impl Parser<'_> {
 
         // self.next_token()?; // consume '}'
         self.consume_right_brace()?; // consume '}'
+        self.record_container(open);
 
         Ok(kvps)
     }
@@ -522,20 +1328,19 @@ impl Parser<'_> {
         // |-------// current token, validated
 
         self.next_token()?; // consume '['
+        let open = self.last_range.index;
         self.consume_new_line_if_exist()?;
 
         // let mut items: Vec<AsonNode> = vec![];
         let mut items: Vec<AsonNode> = vec![];
         let mut nvps: Vec<NameValuePair> = vec![];
 
-        #[derive(PartialEq)]
-        enum ListType {
-            Unknown,
-            List,
-            Map,
-        }
-
-        let mut list_type = ListType::Unknown;
+        // `[]` is an empty list, and `[:]` is an empty map.
+        let mut list_type = if self.consume_empty_map_colon_if_exist()? {
+            ListType::Map
+        } else {
+            ListType::Unknown
+        };
 
         // // to indicate it is parsing the first element of List, Tuple or Object
         // let mut is_first_element = true;
@@ -570,35 +1375,76 @@ impl Parser<'_> {
             //
             //             is_first_element = false;
 
-            let item = self.parse_node()?;
+            // the name and value of map entries take the sub-indices `0` and `1`,
+            // so the comments of their children do not collide.
+            self.enter_element(items.len() + nvps.len())?;
+            let mark = self.anchor_mark();
 
-            if list_type == ListType::Unknown {
-                if self
-                    .expect_token_ignore_newline(0, &Token::Colon)?
-                    .is_some()
-                {
-                    list_type = ListType::Map
-                } else {
-                    list_type = ListType::List
+            // it is `None` if the item or the name is skipped, see `parse_element_value`.
+            let item = if list_type == ListType::List {
+                self.parse_element_value(&Token::RightBracket)?
+            } else {
+                self.enter_element(0)?;
+                let name = self.parse_element_value(&Token::RightBracket)?;
+                self.leave_element(false);
+                name
+            };
+
+            if list_type == ListType::Unknown && item.is_some() {
+                list_type = self.resolve_list_type()?;
+                if list_type == ListType::List {
+                    self.unnest_anchors(mark);
                 }
             }
 
             if list_type == ListType::List {
-                items.push(item);
-            } else {
-                self.consume_new_line_if_exist()?;
+                items.extend(item);
+            } else if let Some(item) = item {
+                // the position follows the key
+                let key_end_position = self.last_range.get_position_by_range_end();
 
-                self.consume_colon()?;
                 self.consume_new_line_if_exist()?;
 
-                let value = self.parse_node()?;
-                let nvp = NameValuePair {
-                    name: Box::new(item),
-                    value: Box::new(value),
-                };
-                nvps.push(nvp);
+                if !self.expect_token(0, &Token::Colon)? {
+                    return Err(AsonError::MessageWithLocation(
+                        "Expect a colon after the key of map.".to_owned(),
+                        key_end_position,
+                    ));
+                }
+
+                self.next_token()?; // consume ':'
+                self.consume_new_line_if_exist()?;
+
+                match self.peek_token(0)? {
+                    Some(Token::RightBracket | Token::Comma) => {
+                        return Err(AsonError::MessageWithLocation(
+                            "Expect a value after the colon of map.".to_owned(),
+                            self.peek_range(0)?.unwrap().get_position_by_range_start(),
+                        ));
+                    }
+                    None => {
+                        return Err(AsonError::UnexpectedEndOfDocument(
+                            "Expect a value after the colon of map.".to_owned(),
+                        ));
+                    }
+                    _ => {}
+                }
+
+                self.enter_element(1)?;
+                let value = self.parse_element_value(&Token::RightBracket)?;
+                self.leave_element(false);
+
+                if let Some(value) = value {
+                    let nvp = NameValuePair {
+                        name: Box::new(item),
+                        value: Box::new(value),
+                    };
+                    nvps.push(nvp);
+                }
             }
 
+            self.leave_element(true);
+
             let found_sep = self.consume_new_line_or_comma_if_exist()?;
             if !found_sep {
                 break;
@@ -607,11 +1453,51 @@ impl Parser<'_> {
 
         // self.next_token()?; // consume ']'
         self.consume_right_bracket()?; // consume ']'
+        self.record_container(open);
 
-        if list_type == ListType::List {
+        if list_type == ListType::Map {
+            Ok(AsonNode::Map(nvps))
+        } else {
             Ok(AsonNode::List(items))
+        }
+    }
+
+    // consume the ':' of the empty map `[:]` if it exists.
+    fn consume_empty_map_colon_if_exist(&mut self) -> Result<bool, AsonError> {
+        // [:]?  //
+        //  ^^__// to here
+        //  |---// current token
+
+        if !self.expect_token(0, &Token::Colon)? {
+            return Ok(false);
+        }
+
+        self.next_token()?; // consume ':'
+        let colon_end_position = self.last_range.get_position_by_range_end();
+        self.consume_new_line_if_exist()?;
+
+        match self.peek_token(0)? {
+            Some(Token::RightBracket) | None => Ok(true),
+            Some(_) => Err(AsonError::MessageWithLocation(
+                "Expect a right bracket after the colon of empty map.".to_owned(),
+                colon_end_position,
+            )),
+        }
+    }
+
+    // the list is a map if the first element is followed by a colon.
+    fn resolve_list_type(&self) -> Result<ListType, AsonError> {
+        // [key: ...]  //
+        //  ^  ^_______// the colon of map
+        //  |----------// the first element, parsed
+
+        if self
+            .expect_token_ignore_newline(0, &Token::Colon)?
+            .is_some()
+        {
+            Ok(ListType::Map)
         } else {
-            Ok(AsonNode::Map(nvps))
+            Ok(ListType::List)
         }
     }
 
@@ -658,8 +1544,10 @@ impl Parser<'_> {
             //
             //             is_first_element = false;
 
-            let value = self.parse_node()?;
-            items.push(value);
+            self.enter_element(items.len())?;
+            let value = self.parse_element_value(&Token::RightParen)?;
+            self.leave_element(false);
+            items.extend(value);
 
             let found_sep = self.consume_new_line_or_comma_if_exist()?;
             if !found_sep {
@@ -681,6 +1569,117 @@ impl Parser<'_> {
     }
 }
 
+// attaches the comments to the recorded elements and containers,
+// see `parse_from_str_with_comments`.
+//
+// the comments are in the source order, so the containers which enclose
+// the current comment are tracked with a stack while walking through them,
+// and the elements are looked up in the lists sorted by the start and by the end.
+fn attach_comments(comments: Vec<(Comment, Location)>, anchors: &CommentAnchors) -> Comments {
+    let mut result = Comments::default();
+
+    // the sorting is stable, so of the elements which start (or end) at the
+    // same token, the inner ones come first since they are recorded first.
+    let mut elements_by_start: Vec<&ElementAnchor> = anchors.elements.iter().collect();
+    elements_by_start.sort_by_key(|element| element.start);
+
+    let mut elements_by_end: Vec<&ElementAnchor> = anchors.elements.iter().collect();
+    elements_by_end.sort_by_key(|element| element.end.index);
+
+    let mut containers: Vec<&ContainerAnchor> = anchors.containers.iter().collect();
+    containers.sort_by_key(|container| container.open);
+
+    let mut next_container_position = 0;
+
+    // the containers which enclose the comment, from the outermost to the innermost
+    let mut enclosing_containers: Vec<&ContainerAnchor> = vec![];
+
+    for (comment, location) in comments {
+        let index = location.index;
+
+        while let Some(container) = containers
+            .get(next_container_position)
+            .filter(|container| container.open < index)
+        {
+            while enclosing_containers
+                .last()
+                .is_some_and(|last| last.close < container.open)
+            {
+                enclosing_containers.pop();
+            }
+            enclosing_containers.push(container);
+            next_container_position += 1;
+        }
+
+        while enclosing_containers
+            .last()
+            .is_some_and(|last| last.close < index)
+        {
+            enclosing_containers.pop();
+        }
+
+        // the innermost list, object or map which encloses the comment
+        let container = enclosing_containers.last().copied();
+
+        let is_inside = |element: &&ElementAnchor| match container {
+            Some(container) => container.open < element.start && element.start < container.close,
+            None => true,
+        };
+
+        // the element which ends right before the comment on the same line,
+        // and no other element starts between them.
+        //
+        // the elements are nested, so the last element which ends before
+        // the comment is either inside the container or there is no
+        // such element inside the container.
+        let previous = elements_by_end
+            .partition_point(|element| element.end.index < index)
+            .checked_sub(1)
+            .map(|position| elements_by_end[position])
+            .filter(is_inside)
+            .filter(|previous| {
+                previous.end.line == location.line
+                    && elements_by_start
+                        .partition_point(|element| element.start <= previous.end.index)
+                        == elements_by_start.partition_point(|element| element.start < index)
+            });
+
+        if let Some(previous) = previous {
+            result
+                .nodes
+                .entry(previous.path.clone())
+                .or_default()
+                .trailing
+                .push(comment);
+            continue;
+        }
+
+        // the (outermost) element which follows the comment
+        let next = elements_by_start
+            .get(elements_by_start.partition_point(|element| element.start <= index))
+            .copied()
+            .filter(is_inside);
+
+        match (next, container) {
+            (Some(next), _) => result
+                .nodes
+                .entry(next.path.clone())
+                .or_default()
+                .leading
+                .push(comment),
+            (None, Some(container)) => result
+                .nodes
+                .entry(container.path.clone())
+                .or_default()
+                .dangling
+                .push(comment),
+            (None, None) => result.end.push(comment),
+        }
+    }
+
+    result
+}
+
 fn convert_number_token(token: &NumberToken) -> AsonNode {
     let number = match token {
         NumberToken::I8(v) => Number::I8(*v as i8),
@@ -691,6 +1690,8 @@ fn convert_number_token(token: &NumberToken) -> AsonNode {
         NumberToken::U32(v) => Number::U32(*v),
         NumberToken::I64(v) => Number::I64(*v as i64),
         NumberToken::U64(v) => Number::U64(*v),
+        NumberToken::I128(v) => Number::I128(*v as i128),
+        NumberToken::U128(v) => Number::U128(*v),
         NumberToken::F32(v) => Number::F32(*v),
         NumberToken::F64(v) => Number::F64(*v),
     };
@@ -700,17 +1701,29 @@ fn convert_number_token(token: &NumberToken) -> AsonNode {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use chrono::DateTime;
     use pretty_assertions::assert_eq;
 
     use crate::{
-        ast::{KeyValuePair, NameValuePair, Number, Variant},
+        ast::{Comments, KeyValuePair, NameValuePair, NodeComments, NodeRange, Number, Variant},
         location::Location,
-        parser::parse_from_str,
+        parser::{
+            parse_all, parse_all_with_options, parse_from_reader, parse_from_str,
+            parse_from_str_with_comments, parse_from_str_with_extension,
+            parse_from_str_with_options, parse_from_str_with_ranges,
+            parse_from_tokens_with_options, parse_with_directives,
+            parse_with_directives_with_options, try_from_str, DocumentSeparator, ParseOptions,
+        },
+        peekableiter::PeekableIter,
+        printer::print_to_string,
+        token::{Comment, CommentStyle, Directive, FloatType, IntegerType, LineCommentOpener},
+        tokens::tokenize_from_str,
         AsonError,
     };
 
-    use super::AsonNode;
+    use super::{AsonNode, Parser};
 
     // fn new_string_node(s: &str) -> AsonNode {
     //     AsonNode::String(s.to_owned())
@@ -805,6 +1818,12 @@ mod tests {
             expect_object1
         );
 
+        // quoted keys
+        assert_eq!(
+            parse_from_str(r#"{"id": 123, "name": "foo"}"#).unwrap(),
+            expect_object1
+        );
+
         assert_eq!(
             parse_from_str(
                 r#"
@@ -998,6 +2017,125 @@ mod tests {
             .unwrap(),
             expect_object1
         );
+
+        // err: missing value
+        assert!(matches!(
+            parse_from_str(r#"[1:]"#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 3,
+                    line: 0,
+                    column: 3,
+                    length: 0
+                }
+            ))
+        ));
+
+        assert!(matches!(
+            parse_from_str(r#"[1: 2, 3: ]"#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 10,
+                    line: 0,
+                    column: 10,
+                    length: 0
+                }
+            ))
+        ));
+
+        // err: missing colon
+        assert!(matches!(
+            parse_from_str(r#"[1: 2, 3 4]"#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 8,
+                    line: 0,
+                    column: 8,
+                    length: 0
+                }
+            ))
+        ));
+
+        assert!(matches!(
+            parse_from_str("[1: 2, \"foo\"]"),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 12,
+                    line: 0,
+                    column: 12,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_parse_map_with_string_keys() {
+        let expect_object1 = AsonNode::Map(vec![
+            NameValuePair {
+                name: Box::new(AsonNode::String("a b".to_owned())),
+                value: Box::new(AsonNode::Number(Number::I32(1))),
+            },
+            NameValuePair {
+                name: Box::new(AsonNode::String("c:d".to_owned())),
+                value: Box::new(AsonNode::Number(Number::I32(2))),
+            },
+        ]);
+
+        let node = parse_from_str(r#"["a b": 1, "c:d": 2]"#).unwrap();
+        assert_eq!(node, expect_object1);
+
+        // the colon inside the key does not separate the name and value
+        assert_eq!(
+            print_to_string(&node),
+            r#"[
+    "a b": 1
+    "c:d": 2
+]"#
+        );
+        assert_eq!(parse_from_str(&print_to_string(&node)).unwrap(), node);
+    }
+
+    #[test]
+    fn test_parse_empty_list_and_map() {
+        assert_eq!(parse_from_str(r#"[]"#).unwrap(), AsonNode::List(vec![]));
+        assert_eq!(parse_from_str(r#"[:]"#).unwrap(), AsonNode::Map(vec![]));
+        assert_eq!(
+            parse_from_str("[\n    :\n]").unwrap(),
+            AsonNode::Map(vec![])
+        );
+
+        assert_eq!(
+            parse_from_str(r#"[1:2]"#).unwrap(),
+            AsonNode::Map(vec![NameValuePair {
+                name: Box::new(AsonNode::Number(Number::I32(1))),
+                value: Box::new(AsonNode::Number(Number::I32(2))),
+            }])
+        );
+
+        // err: elements after the colon of empty map
+        assert!(matches!(
+            parse_from_str(r#"[: 1]"#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 2,
+                    line: 0,
+                    column: 2,
+                    length: 0
+                }
+            ))
+        ));
+
+        // err: incomplete
+        assert!(matches!(
+            parse_from_str(r#"[:"#),
+            Err(AsonError::UnexpectedEndOfDocument(_))
+        ));
     }
 
     #[test]
@@ -1094,6 +2232,47 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_list_with_minus_sign() {
+        let expect_list = AsonNode::List(vec![
+            AsonNode::Number(Number::I32(5)),
+            AsonNode::Number(Number::I32(-3)),
+        ]);
+
+        // the minus sign always belongs to the following number,
+        // there is no subtraction in ASON.
+        assert_eq!(parse_from_str(r#"[5, -3]"#).unwrap(), expect_list);
+        assert_eq!(parse_from_str("[5\n-3]").unwrap(), expect_list);
+        assert_eq!(parse_from_str("[5\n- 3]").unwrap(), expect_list);
+
+        // err: the space is not a separator
+        assert!(matches!(
+            parse_from_str(r#"[5 -3]"#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 3,
+                    line: 0,
+                    column: 3,
+                    length: 0
+                }
+            ))
+        ));
+
+        assert!(matches!(
+            parse_from_str(r#"[5 - 3]"#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 3,
+                    line: 0,
+                    column: 3,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
     #[test]
     fn test_parse_tuple() {
         let expect_tuple1 = AsonNode::Tuple(vec![
@@ -1456,6 +2635,1067 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_with_options() {
+        let options = ParseOptions {
+            forbid_redundant_plus: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_from_str_with_options("[-5, 5]", &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Number(Number::I32(-5)),
+                AsonNode::Number(Number::I32(5)),
+            ])
+        );
+
+        assert!(matches!(
+            parse_from_str_with_options("[-5, +5]", &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 5,
+                    line: 0,
+                    column: 5,
+                    length: 1
+                }
+            ))
+        ));
+
+        assert_eq!(
+            parse_from_str("+5").unwrap(),
+            AsonNode::Number(Number::I32(5))
+        );
+    }
+
+    #[test]
+    fn test_parse_document_marker() {
+        let options = ParseOptions {
+            allow_document_marker: true,
+            ..Default::default()
+        };
+
+        let expect_list = AsonNode::List(vec![
+            AsonNode::Number(Number::I32(11)),
+            AsonNode::Number(Number::I32(-13)),
+        ]);
+
+        assert_eq!(
+            parse_from_str_with_options("---\n[11, -13]", &options).unwrap(),
+            expect_list
+        );
+
+        assert_eq!(
+            parse_from_str_with_options("---\r\n[11, -13]", &options).unwrap(),
+            expect_list
+        );
+
+        // without the marker
+        assert_eq!(
+            parse_from_str_with_options("[11, -13]", &options).unwrap(),
+            expect_list
+        );
+
+        // err: the marker is not supported by default
+        assert!(matches!(
+            parse_from_str("---\n[11, -13]"),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+
+        // err: the marker must be at the very beginning of the document
+        assert!(matches!(
+            parse_from_str_with_options("\n---\n[11, -13]", &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    line: 1,
+                    column: 0,
+                    ..
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_parse_shebang() {
+        let options = ParseOptions {
+            allow_shebang: true,
+            ..Default::default()
+        };
+
+        let s0 = "#!/usr/bin/env ason\n[11, 13]";
+
+        assert_eq!(
+            parse_from_str_with_options(s0, &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Number(Number::I32(11)),
+                AsonNode::Number(Number::I32(13)),
+            ])
+        );
+
+        // err: the shebang is not supported by default
+        assert!(matches!(
+            parse_from_str(s0),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    ..
+                }
+            ))
+        ));
+
+        // err: the shebang must be at the very beginning of the document
+        assert!(matches!(
+            parse_from_str_with_options("\n#!/usr/bin/env ason\n[11, 13]", &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 1,
+                    line: 1,
+                    column: 0,
+                    ..
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_parse_require_comma_separators() {
+        let options = ParseOptions {
+            require_comma_separators: true,
+            ..Default::default()
+        };
+
+        let expect_list = AsonNode::List(vec![
+            AsonNode::Number(Number::I32(1)),
+            AsonNode::Number(Number::I32(2)),
+        ]);
+
+        assert_eq!(
+            parse_from_str_with_options("[1, 2]", &options).unwrap(),
+            expect_list
+        );
+
+        assert_eq!(
+            parse_from_str_with_options("[\n1,\n2,\n]", &options).unwrap(),
+            expect_list
+        );
+
+        assert_eq!(
+            parse_from_str_with_options("[\n1,\n2\n]", &options).unwrap(),
+            expect_list
+        );
+
+        // the default behavior is unchanged
+        assert_eq!(parse_from_str("[1\n2]").unwrap(), expect_list);
+
+        // err: elements separated by a new-line only
+        // "[1\n2]"
+        //  01 2    // index
+        assert!(matches!(
+            parse_from_str_with_options("[1\n2]", &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 2,
+                    line: 0,
+                    column: 2,
+                    length: 0
+                }
+            ))
+        ));
+
+        assert!(matches!(
+            parse_from_str_with_options("{\n    id: 1\n    name: \"foo\"\n}", &options),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+
+        assert!(matches!(
+            parse_from_str_with_options("(1\n2)", &options),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+    }
+
+    #[test]
+    fn test_parse_require_identifier_keys() {
+        let options = ParseOptions {
+            require_identifier_keys: true,
+            ..Default::default()
+        };
+
+        let expect_object = AsonNode::Object(vec![KeyValuePair::new(
+            "id",
+            AsonNode::Number(Number::I32(11)),
+        )]);
+
+        assert_eq!(
+            parse_from_str_with_options("{id: 11}", &options).unwrap(),
+            expect_object
+        );
+
+        // the quoted keys are accepted by default
+        assert_eq!(parse_from_str(r#"{"id": 11}"#).unwrap(), expect_object);
+
+        // err: the quoted key
+        // {"id": 11}
+        // 01234    // index
+        assert_eq!(
+            parse_from_str_with_options(r#"{"id": 11}"#, &options),
+            Err(AsonError::MessageWithLocation(
+                "Expect an identifier for the key of object.".to_owned(),
+                Location::new_range(1, 0, 1, 4)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_hash_comment() {
+        let options = ParseOptions {
+            comment_style: CommentStyle::Hash,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_from_str_with_options(
+                r#"# the first line
+                {
+                    id: 123 # the id
+                    // the name
+                    name: "foo"
+                }"#,
+                &options
+            )
+            .unwrap(),
+            AsonNode::Object(vec![
+                KeyValuePair::new("id", AsonNode::Number(Number::I32(123))),
+                KeyValuePair::new("name", AsonNode::String("foo".to_owned())),
+            ])
+        );
+
+        // err: the hash comment is not accepted by default
+        assert!(parse_from_str("# the first line\n123").is_err());
+    }
+
+    #[test]
+    fn test_parse_extension() {
+        let handler = |name: &str, value: AsonNode| match name {
+            "tag" | "ref" => Ok(AsonNode::Extension(name.to_owned(), Box::new(value))),
+            _ => Err(AsonError::Message(format!(
+                "Unknown extension \"{}\".",
+                name
+            ))),
+        };
+
+        let options = ParseOptions::default();
+
+        assert_eq!(
+            parse_from_str_with_extension(r#"@tag("foo")"#, &options, &handler).unwrap(),
+            AsonNode::Extension(
+                "tag".to_owned(),
+                Box::new(AsonNode::String("foo".to_owned()))
+            )
+        );
+
+        assert_eq!(
+            parse_from_str_with_extension(
+                r#"{
+                    id: 123
+                    parent: @ref({
+                        id: 11
+                    })
+                }"#,
+                &options,
+                &handler
+            )
+            .unwrap(),
+            AsonNode::Object(vec![
+                KeyValuePair::new("id", AsonNode::Number(Number::I32(123))),
+                KeyValuePair::new(
+                    "parent",
+                    AsonNode::Extension(
+                        "ref".to_owned(),
+                        Box::new(AsonNode::Object(vec![KeyValuePair::new(
+                            "id",
+                            AsonNode::Number(Number::I32(11))
+                        )]))
+                    )
+                ),
+            ])
+        );
+
+        // err: rejected by the handler
+        assert!(matches!(
+            parse_from_str_with_extension(r#"@foo(1)"#, &options, &handler),
+            Err(AsonError::Message(_))
+        ));
+
+        // err: missing the name
+        assert!(matches!(
+            parse_from_str_with_extension(r#"@(1)"#, &options, &handler),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 1,
+                    line: 0,
+                    column: 1,
+                    length: 1
+                }
+            ))
+        ));
+
+        // err: no extension handler
+        assert!(matches!(
+            parse_from_str(r#"@tag("foo")"#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_clone_and_equality() {
+        let text = r#"{
+            id: 123
+            name: "foo"
+            orders: [11, 13]
+            address: ("sz", Option::Some(d"2024-03-16T16:30:50+08:00"))
+            data: h"11 13"
+            shape: Shape::Rect{width: 1.5, height: 2.5}
+            tags: ["a": 'b']
+        }"#;
+
+        let node = parse_from_str(text).unwrap();
+
+        // two parses of the same source are equal
+        assert_eq!(node, parse_from_str(text).unwrap());
+
+        let cloned = node.clone();
+        assert_eq!(cloned, node);
+
+        // NaN follows IEEE semantics
+        let nan = parse_from_str("NaN").unwrap();
+        assert_ne!(nan.clone(), nan);
+
+        // errors are cloneable too
+        let err = parse_from_str("{id: }").unwrap_err();
+        assert_eq!(err.clone(), err);
+    }
+
+    #[test]
+    fn test_parse_max_input_bytes() {
+        let options = ParseOptions {
+            max_input_bytes: Some(16),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_from_str_with_options("[11, 13, 17]", &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Number(Number::I32(11)),
+                AsonNode::Number(Number::I32(13)),
+                AsonNode::Number(Number::I32(17)),
+            ])
+        );
+
+        // err: oversized, it is reported before the syntax error
+        // of the (unclosed) list.
+        let s = format!("[{}", "11, ".repeat(100_000));
+        assert_eq!(
+            parse_from_str_with_options(&s, &options),
+            Err(AsonError::Message(
+                "The document exceeds the maximum length of 16 bytes.".to_owned()
+            ))
+        );
+
+        // the length is counted in bytes rather than chars
+        assert!(parse_from_str_with_options(r#""文字文字文字""#, &options).is_err());
+
+        // unlimited by default
+        assert!(parse_from_str(&s).unwrap_err().is_incomplete());
+    }
+
+    #[test]
+    fn test_parse_from_reader_with_bom() {
+        let mut bytes = b"\xef\xbb\xbf".to_vec();
+        bytes.extend_from_slice(b"{id: 11, name: \"foo\"}");
+
+        assert_eq!(
+            parse_from_reader(std::io::Cursor::new(bytes)).unwrap(),
+            parse_from_str(r#"{id: 11, name: "foo"}"#).unwrap()
+        );
+
+        // the document without BOM
+        assert_eq!(
+            parse_from_reader(std::io::Cursor::new(b"[11, 13]")).unwrap(),
+            parse_from_str(r#"[11, 13]"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_error_is_incomplete() {
+        assert!(parse_from_str("[1, 2").unwrap_err().is_incomplete());
+        assert!(parse_from_str("{id: 1, name:").unwrap_err().is_incomplete());
+        assert!(parse_from_str("(1, \"abc").unwrap_err().is_incomplete());
+        assert!(parse_from_str("[1, /* comment")
+            .unwrap_err()
+            .is_incomplete());
+
+        // syntax errors
+        assert!(!parse_from_str("[1 2]").unwrap_err().is_incomplete());
+        assert!(!parse_from_str("{id: }").unwrap_err().is_incomplete());
+        assert!(!parse_from_str("[1, 2)").unwrap_err().is_incomplete());
+
+        // no token at all
+        for s in ["", "  \n  ", "// comment"] {
+            assert_eq!(
+                parse_from_str(s),
+                Err(AsonError::Message("The document is empty.".to_owned()))
+            );
+            assert!(!parse_from_str(s).unwrap_err().is_incomplete());
+        }
+    }
+
+    #[test]
+    fn test_try_from_str() {
+        let (root, errors) = try_from_str(
+            r#"{
+                id: 11
+                name: :
+                tags: ["a", @, "c"]
+                ok: true
+            }"#,
+        );
+
+        assert_eq!(
+            root,
+            Some(AsonNode::Object(vec![
+                KeyValuePair {
+                    key: "id".to_owned(),
+                    value: Box::new(AsonNode::Number(Number::I32(11))),
+                },
+                KeyValuePair {
+                    key: "tags".to_owned(),
+                    value: Box::new(AsonNode::List(vec![
+                        AsonNode::String("a".to_owned()),
+                        AsonNode::String("c".to_owned()),
+                    ])),
+                },
+                KeyValuePair {
+                    key: "ok".to_owned(),
+                    value: Box::new(AsonNode::Boolean(true)),
+                },
+            ]))
+        );
+
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    Location::new_range(47, 2, 22, 0),
+                    "Unexpected token.".to_owned()
+                ),
+                (
+                    Location::new_range(77, 3, 28, 0),
+                    "Unexpected token.".to_owned()
+                ),
+            ]
+        );
+
+        // the nested brackets of the invalid value are skipped as a whole
+        assert_eq!(
+            try_from_str("[1, : (2, 3), 4]"),
+            (
+                Some(AsonNode::List(vec![
+                    AsonNode::Number(Number::I32(1)),
+                    AsonNode::Number(Number::I32(4)),
+                ])),
+                vec![(
+                    Location::new_range(4, 0, 4, 0),
+                    "Unexpected token.".to_owned()
+                )]
+            )
+        );
+
+        // the valid document
+        assert_eq!(
+            try_from_str("[1, 2]"),
+            (
+                Some(AsonNode::List(vec![
+                    AsonNode::Number(Number::I32(1)),
+                    AsonNode::Number(Number::I32(2)),
+                ])),
+                vec![]
+            )
+        );
+
+        // the unrecoverable errors
+        assert_eq!(
+            try_from_str("[1, ?, 3]"),
+            (
+                None,
+                vec![(
+                    Location::new_range(4, 0, 4, 0),
+                    "Unexpected char '?'.".to_owned()
+                )]
+            )
+        );
+
+        let (root, errors) = try_from_str("[1, 2");
+        assert_eq!(root, None);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_default_integer_type() {
+        let options = ParseOptions {
+            default_integer_type: IntegerType::I64,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_from_str_with_options("3000000000", &options).unwrap(),
+            AsonNode::Number(Number::I64(3_000_000_000))
+        );
+
+        assert_eq!(
+            parse_from_str_with_options("[-3000000000, 0xff, 0b11, 7_i32]", &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Number(Number::I64(-3_000_000_000)),
+                AsonNode::Number(Number::I64(0xff)),
+                AsonNode::Number(Number::I64(0b11)),
+                AsonNode::Number(Number::I32(7)),
+            ])
+        );
+
+        // err: overflow i32 by default
+        assert!(matches!(
+            parse_from_str("3000000000"),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+
+        // err: overflow i64
+        assert!(matches!(
+            parse_from_str_with_options("9223372036854775808", &options),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+    }
+
+    #[test]
+    fn test_parse_default_float_type() {
+        let options = ParseOptions {
+            default_float_type: FloatType::F32,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_from_str_with_options("1.5", &options).unwrap(),
+            AsonNode::Number(Number::F32(1.5))
+        );
+
+        assert_eq!(
+            parse_from_str_with_options("[-2.5, 1e3, 0x1.8p1, Inf, -Inf, 7.5_f64, 11]", &options)
+                .unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Number(Number::F32(-2.5)),
+                AsonNode::Number(Number::F32(1000.0)),
+                AsonNode::Number(Number::F32(3.0)),
+                AsonNode::Number(Number::F32(f32::INFINITY)),
+                AsonNode::Number(Number::F32(f32::NEG_INFINITY)),
+                AsonNode::Number(Number::F64(7.5)),
+                AsonNode::Number(Number::I32(11)),
+            ])
+        );
+
+        assert!(matches!(
+            parse_from_str_with_options("NaN", &options).unwrap(),
+            AsonNode::Number(Number::F32(v)) if v.is_nan()
+        ));
+
+        // f64 by default
+        assert_eq!(
+            parse_from_str("1.5").unwrap(),
+            AsonNode::Number(Number::F64(1.5))
+        );
+
+        assert!(matches!(
+            parse_from_str("NaN").unwrap(),
+            AsonNode::Number(Number::F64(v)) if v.is_nan()
+        ));
+    }
+
+    #[test]
+    fn test_parse_all() {
+        assert_eq!(
+            parse_all("1\n2\n3").unwrap(),
+            vec![
+                AsonNode::Number(Number::I32(1)),
+                AsonNode::Number(Number::I32(2)),
+                AsonNode::Number(Number::I32(3)),
+            ]
+        );
+
+        assert_eq!(
+            parse_all(
+                r#"
+                {id: 11}
+                // comment
+                {id: 13} [17]
+
+                "#
+            )
+            .unwrap(),
+            vec![
+                AsonNode::Object(vec![KeyValuePair::new(
+                    "id",
+                    AsonNode::Number(Number::I32(11))
+                )]),
+                AsonNode::Object(vec![KeyValuePair::new(
+                    "id",
+                    AsonNode::Number(Number::I32(13))
+                )]),
+                AsonNode::List(vec![AsonNode::Number(Number::I32(17))]),
+            ]
+        );
+
+        assert_eq!(parse_all("").unwrap(), vec![]);
+
+        // err: incomplete node
+        assert!(matches!(
+            parse_all("1\n[2"),
+            Err(AsonError::UnexpectedEndOfDocument(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_all_with_document_separator() {
+        let expect_nodes = vec![
+            AsonNode::Number(Number::I32(11)),
+            AsonNode::List(vec![AsonNode::Number(Number::I32(13))]),
+            AsonNode::Number(Number::I32(17)),
+        ];
+
+        let options = ParseOptions {
+            document_separator: DocumentSeparator::NewLine,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_all_with_options("\n11\n[13]\n\n17\n", &options).unwrap(),
+            expect_nodes
+        );
+
+        // err: missing new-line
+        assert!(matches!(
+            parse_all_with_options("11\n[13] 17", &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 8,
+                    line: 1,
+                    column: 5,
+                    length: 0
+                }
+            ))
+        ));
+
+        let options = ParseOptions {
+            document_separator: DocumentSeparator::Comma,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_all_with_options("11, [13]\n,\n17", &options).unwrap(),
+            expect_nodes
+        );
+
+        // trailing comma
+        assert_eq!(
+            parse_all_with_options("11,[13],17,\n", &options).unwrap(),
+            expect_nodes
+        );
+
+        // err: missing comma
+        assert!(matches!(
+            parse_all_with_options("11, [13]\n17", &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 8,
+                    line: 0,
+                    column: 8,
+                    length: 0
+                }
+            ))
+        ));
+
+        let options = ParseOptions {
+            document_separator: DocumentSeparator::Custom(';'),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_all_with_options("11; [13]\n;\n17;\n", &options).unwrap(),
+            expect_nodes
+        );
+
+        // the separator is not recognized inside strings
+        assert_eq!(
+            parse_all_with_options(r#""a;b"; "c""#, &options).unwrap(),
+            vec![
+                AsonNode::String("a;b".to_owned()),
+                AsonNode::String("c".to_owned())
+            ]
+        );
+
+        // err: missing separator
+        assert_eq!(
+            parse_all_with_options("11; [13]\n17", &options),
+            Err(AsonError::MessageWithLocation(
+                "Expect a ';' between documents.".to_owned(),
+                Location {
+                    index: 8,
+                    line: 0,
+                    column: 8,
+                    length: 0
+                }
+            ))
+        );
+
+        // err: the char has other meaning
+        for c in [',', '#', 'a', ' '] {
+            assert_eq!(
+                parse_all_with_options(
+                    "11",
+                    &ParseOptions {
+                        document_separator: DocumentSeparator::Custom(c),
+                        ..Default::default()
+                    }
+                ),
+                Err(AsonError::Message(format!(
+                    "The char '{}' can not be the document separator.",
+                    c
+                )))
+            );
+        }
+
+        // err: the default separator does not accept commas
+        assert!(matches!(
+            parse_all("11, [13]"),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 2,
+                    line: 0,
+                    column: 2,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_parse_with_directives() {
+        let (node, directives) = parse_with_directives(
+            r#"//! include: "other.ason"
+{
+    id: 123 // regular comment
+    /* ! block comment */
+    //!strict
+}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            node,
+            AsonNode::Object(vec![KeyValuePair {
+                key: "id".to_owned(),
+                value: Box::new(AsonNode::Number(Number::I32(123))),
+            }])
+        );
+
+        assert_eq!(
+            directives,
+            vec![
+                Directive {
+                    text: "include: \"other.ason\"".to_owned(),
+                    location: Location::new_range(0, 0, 0, 25),
+                },
+                Directive {
+                    text: "strict".to_owned(),
+                    location: Location::new_range(89, 4, 4, 9),
+                },
+            ]
+        );
+
+        // custom prefix
+        let options = ParseOptions {
+            directive_prefix: Some("@".to_owned()),
+            ..Default::default()
+        };
+
+        let (_, directives) =
+            parse_with_directives_with_options("//@ pragma once\n//! include\n123", &options)
+                .unwrap();
+        assert_eq!(
+            directives,
+            vec![Directive {
+                text: "pragma once".to_owned(),
+                location: Location::new_range(0, 0, 0, 15),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_with_comments() {
+        let (node, comments) = parse_from_str_with_comments(
+            r#"// header
+{
+    id: 123 // the id
+    tags: [
+        // the first tag
+        "foo"
+        // no more tags
+    ]
+}
+// footer"#,
+            &ParseOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(node, parse_from_str(r#"{id: 123, tags: ["foo"]}"#).unwrap());
+
+        let line = |text: &str| Comment::Line(text.to_owned(), LineCommentOpener::DoubleSlash);
+        assert_eq!(
+            comments,
+            Comments {
+                nodes: HashMap::from([
+                    (
+                        vec![],
+                        NodeComments {
+                            leading: vec![line(" header")],
+                            ..Default::default()
+                        }
+                    ),
+                    (
+                        vec![0],
+                        NodeComments {
+                            trailing: vec![line(" the id")],
+                            ..Default::default()
+                        }
+                    ),
+                    (
+                        vec![1],
+                        NodeComments {
+                            dangling: vec![line(" no more tags")],
+                            ..Default::default()
+                        }
+                    ),
+                    (
+                        vec![1, 0],
+                        NodeComments {
+                            leading: vec![line(" the first tag")],
+                            ..Default::default()
+                        }
+                    ),
+                ]),
+                end: vec![line(" footer")],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_with_ranges() {
+        let text = r#"{
+    id: 123
+    tags: ["a", (1, 2)]
+}"#;
+
+        let (node, root) = parse_from_str_with_ranges(text, &ParseOptions::default()).unwrap();
+        assert_eq!(node, parse_from_str(text).unwrap());
+
+        // flatten the ranges in pre-order
+        fn flatten(node: &NodeRange, ranges: &mut Vec<Location>) {
+            ranges.push(node.range);
+            node.children
+                .iter()
+                .for_each(|child| flatten(child, ranges));
+        }
+
+        let mut ranges = vec![];
+        flatten(&root, &mut ranges);
+
+        assert_eq!(
+            ranges,
+            vec![
+                Location::new_range(0, 0, 0, 39),   // {...}
+                Location::new_range(10, 1, 8, 3),   // 123
+                Location::new_range(24, 2, 10, 13), // [...]
+                Location::new_range(25, 2, 11, 3),  // "a"
+                Location::new_range(30, 2, 16, 6),  // (1, 2)
+                Location::new_range(31, 2, 17, 1),  // 1
+                Location::new_range(34, 2, 20, 1),  // 2
+            ]
+        );
+
+        assert_eq!(root.range.extract(text), text);
+        assert_eq!(root.children[1].range.extract(text), r#"["a", (1, 2)]"#);
+        assert_eq!(root.children[1].children[1].range.extract(text), "(1, 2)");
+
+        // map and variant
+        let text = r#"[1: Option::Some('x')]"#;
+        let (_, root) = parse_from_str_with_ranges(text, &ParseOptions::default()).unwrap();
+
+        let mut ranges = vec![];
+        flatten(&root, &mut ranges);
+
+        assert_eq!(
+            ranges
+                .iter()
+                .map(|range| range.extract(text))
+                .collect::<Vec<_>>(),
+            vec![text, "1", "Option::Some('x')", "'x'"]
+        );
+    }
+
+    #[test]
+    fn test_parse_join_adjacent_strings() {
+        let options = ParseOptions {
+            join_adjacent_strings: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_from_str_with_options("\"foo\"\n\"bar\"", &options).unwrap(),
+            AsonNode::String("foobar".to_owned())
+        );
+
+        assert_eq!(
+            parse_from_str_with_options(
+                r#"[
+                    "foo" "bar"
+                    "baz"
+                    11
+                    "hello"
+                ]"#,
+                &options
+            )
+            .unwrap(),
+            AsonNode::List(vec![
+                AsonNode::String("foobarbaz".to_owned()),
+                AsonNode::Number(Number::I32(11)),
+                AsonNode::String("hello".to_owned()),
+            ])
+        );
+
+        // the key of map item is not joined
+        assert_eq!(
+            parse_from_str_with_options(
+                r#"[
+                    "foo": "hello" "world"
+                    "bar": "baz"
+                ]"#,
+                &options
+            )
+            .unwrap(),
+            AsonNode::Map(vec![
+                NameValuePair {
+                    name: Box::new(AsonNode::String("foo".to_owned())),
+                    value: Box::new(AsonNode::String("helloworld".to_owned())),
+                },
+                NameValuePair {
+                    name: Box::new(AsonNode::String("bar".to_owned())),
+                    value: Box::new(AsonNode::String("baz".to_owned())),
+                },
+            ])
+        );
+
+        // strings separated by comma are not joined
+        assert_eq!(
+            parse_from_str_with_options(r#"["foo", "bar"]"#, &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::String("foo".to_owned()),
+                AsonNode::String("bar".to_owned()),
+            ])
+        );
+
+        // two nodes by default
+        assert_eq!(
+            parse_from_str("[\"foo\"\n\"bar\"]").unwrap(),
+            AsonNode::List(vec![
+                AsonNode::String("foo".to_owned()),
+                AsonNode::String("bar".to_owned()),
+            ])
+        );
+
+        // err: more than one node at top level by default
+        assert!(matches!(
+            parse_from_str("\"foo\"\n\"bar\""),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 5,
+                    line: 0,
+                    column: 5,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_parse_max_total_nodes() {
+        let options = ParseOptions {
+            max_total_nodes: Some(100),
+            ..Default::default()
+        };
+
+        // 1 list + 3 numbers
+        assert_eq!(
+            parse_from_str_with_options("[11, 13, 17]", &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Number(Number::I32(11)),
+                AsonNode::Number(Number::I32(13)),
+                AsonNode::Number(Number::I32(17)),
+            ])
+        );
+
+        let s1 = format!("[{}]", "0,".repeat(1_000_000));
+
+        assert!(matches!(
+            parse_from_str_with_options(&s1, &options),
+            Err(AsonError::Message(m)) if m == "Document exceeds maximum node count."
+        ));
+
+        // the options are applied to the tokens as well
+        let tokens = tokenize_from_str(&s1).unwrap();
+        assert!(matches!(
+            parse_from_tokens_with_options(&tokens, &options),
+            Err(AsonError::Message(m)) if m == "Document exceeds maximum node count."
+        ));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "The capacity of the upstream of parser is too small.")]
+    fn test_parser_upstream_capacity() {
+        let tokens = tokenize_from_str("[11, 13]").unwrap();
+        let mut token_iter = tokens.iter();
+
+        // the parser peeks up to `PARSER_PEEK_TOKEN_MAX_COUNT` tokens
+        let mut peekable_token_iter = PeekableIter::new(&mut token_iter, 1);
+        let _ = Parser::new(&mut peekable_token_iter, ParseOptions::default(), None);
+    }
+
 //     #[test]
 //     fn test_parse() {
 //         let text = r#"{
@@ -6,6 +6,18 @@
 
 /// Unlike `std::iter::Peekable`, PeekableIter
 /// supports peek to specify offsets.
+///
+/// The capacity (i.e. `buffer_size`) must be larger than the max offset
+/// that the downstream peeks, otherwise `peek` would panic. The required
+/// capacities of each stage of the pipeline are:
+///
+/// - Lexer: `LEXER_PEEK_CHAR_MAX_COUNT` (3)
+/// - NormalizedTokenIter and TrimmedTokenIter: `NORMALIZER_PEEK_TOKEN_MAX_COUNT` (1)
+/// - Parser: `PARSER_PEEK_TOKEN_MAX_COUNT` (3)
+/// - Deserializer: `DESERIALIZER_PEEK_TOKEN_MAX_COUNT` (2)
+///
+/// The consumers check the capacity of their upstream when
+/// they are constructed (in debug mode).
 pub struct PeekableIter<'a, T>
 where
     T: PartialEq,
@@ -79,6 +91,11 @@ where
     T: PartialEq,
 {
     pub fn new(upstream: &'a mut dyn Iterator<Item = T>, buffer_size: usize) -> Self {
+        debug_assert!(
+            buffer_size > 0,
+            "The capacity of PeekableIter can not be 0."
+        );
+
         let mut buffer = RoundQueue::new(buffer_size);
 
         // pre-fill
@@ -98,6 +115,10 @@ where
         assert!(offset < self.buffer_size);
         self.buffer.peek(offset)
     }
+
+    pub fn capacity(&self) -> usize {
+        self.buffer_size
+    }
 }
 
 impl<T> Iterator for PeekableIter<'_, T>
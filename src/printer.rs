@@ -4,17 +4,100 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
-use std::io::Write;
+use std::{collections::HashMap, io::Write};
 
 use chrono::{DateTime, FixedOffset};
 
 use crate::{
-    ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant, VariantValue},
+    ast::{
+        AsonNode, Comments, KeyValuePair, NameValuePair, NodeComments, NodeRange, Number, Variant,
+        VariantValue,
+    },
+    lexer::is_valid_identifier,
+    parser::parse_from_str,
+    token::{Comment, CommentStyle},
     AsonError,
 };
 
 pub const DEFAULT_INDENT_CHARS: &str = "    ";
 
+/// The layout of the printed text.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrettyConfig {
+    /// The indentation of one level, it is repeated per depth level,
+    /// e.g. four spaces (the default) or a tab `"\t"`.
+    /// It should consist of whitespaces only, otherwise the
+    /// printed text can not be parsed.
+    pub indent: String,
+
+    /// Print the document in a single line with the minimal whitespaces,
+    /// e.g. `{id:123,tags:["foo","bar"]}`, the `indent` is ignored.
+    /// The new-lines of strings are escaped.
+    pub compact: bool,
+}
+
+impl Default for PrettyConfig {
+    fn default() -> Self {
+        Self {
+            indent: DEFAULT_INDENT_CHARS.to_owned(),
+            compact: false,
+        }
+    }
+}
+
+impl PrettyConfig {
+    fn leading_space(&self, indent_level: usize) -> String {
+        if self.compact {
+            String::new()
+        } else {
+            self.indent.repeat(indent_level)
+        }
+    }
+
+    // the separator between the elements of tuple
+    fn element_separator(&self) -> &'static str {
+        if self.compact {
+            ","
+        } else {
+            ", "
+        }
+    }
+
+    // the separator between the key and the value
+    fn key_value_separator(&self) -> &'static str {
+        if self.compact {
+            ":"
+        } else {
+            ": "
+        }
+    }
+}
+
+// the comments (and the source text of floating-point numbers) to re-emit
+// and the path of the current node, see `print_to_string_with_comments`
+// and `print_to_string_with_source`.
+struct PrintCursor<'c> {
+    comments: Option<&'c Comments>,
+    float_literals: HashMap<Vec<usize>, &'c str>,
+    path: Vec<usize>,
+}
+
+impl<'c> PrintCursor<'c> {
+    fn new(comments: Option<&'c Comments>) -> Self {
+        Self {
+            comments,
+            float_literals: HashMap::new(),
+            path: vec![],
+        }
+    }
+
+    // the comments of the current node
+    fn current(&self) -> Option<&'c NodeComments> {
+        self.comments
+            .and_then(|comments| comments.nodes.get(&self.path))
+    }
+}
+
 fn print_number(writer: &mut dyn Write, v: &Number) -> Result<(), std::io::Error> {
     match v {
         Number::I8(v) => {
@@ -42,6 +125,12 @@ fn print_number(writer: &mut dyn Write, v: &Number) -> Result<(), std::io::Error
         Number::U64(v) => {
             write!(writer, "{}_u64", v)
         }
+        Number::I128(v) => {
+            write!(writer, "{}_i128", v)
+        }
+        Number::U128(v) => {
+            write!(writer, "{}_u128", v)
+        }
         Number::F32(v) => {
             if v.is_nan() {
                 write!(writer, "NaN_f32")
@@ -108,7 +197,11 @@ fn print_char(writer: &mut dyn Write, v: &char) -> Result<(), std::io::Error> {
     write!(writer, "'{}'", s)
 }
 
-fn print_string(writer: &mut dyn Write, v: &str) -> Result<(), std::io::Error> {
+fn print_string(
+    writer: &mut dyn Write,
+    v: &str,
+    config: &PrettyConfig,
+) -> Result<(), std::io::Error> {
     write!(
         writer,
         "\"{}\"",
@@ -125,6 +218,10 @@ fn print_string(writer: &mut dyn Write, v: &str) -> Result<(), std::io::Error> {
                 // the end of a line, so it is best to escape the tab character.
                 '\t' => "\\t".to_owned(),
 
+                // the compact document consists of a single line.
+                '\n' if config.compact => "\\n".to_owned(),
+                '\r' if config.compact => "\\r".to_owned(),
+
                 _ => c.to_string(),
             })
             .collect::<Vec<String>>()
@@ -139,8 +236,9 @@ fn print_date(writer: &mut dyn Write, v: &DateTime<FixedOffset>) -> Result<(), s
 fn print_variant(
     writer: &mut dyn Write,
     v: &Variant,
-    indent_chars: &str,
+    config: &PrettyConfig,
     indent_level: usize,
+    cursor: &mut PrintCursor,
 ) -> Result<(), std::io::Error> {
     let (type_name, member_name, value) = (&v.type_name, &v.member_name, &v.value);
 
@@ -148,16 +246,19 @@ fn print_variant(
         VariantValue::Empty => write!(writer, "{}::{}", type_name, member_name),
         VariantValue::Value(v) => {
             write!(writer, "{}::{}(", type_name, member_name)?;
-            print_node(writer, v, indent_chars, indent_level)?;
+            // the single value takes the index `0`, the same as the tuple.
+            cursor.path.push(0);
+            print_node(writer, v, config, indent_level, cursor)?;
+            cursor.path.pop();
             write!(writer, ")")
         }
         VariantValue::Tuple(v) => {
             write!(writer, "{}::{}", type_name, member_name)?;
-            print_tuple(writer, v, indent_chars, indent_level)
+            print_tuple(writer, v, config, indent_level, cursor)
         }
         VariantValue::Object(kvps) => {
             write!(writer, "{}::{}", type_name, member_name)?;
-            print_object(writer, kvps, indent_chars, indent_level)
+            print_object(writer, kvps, config, indent_level, cursor)
         }
     }
 }
@@ -183,9 +284,13 @@ fn print_variant(
 fn print_hex_byte_data(
     writer: &mut dyn Write,
     data: &[u8],
-    indent_chars: &str,
+    config: &PrettyConfig,
 ) -> Result<(), std::io::Error> {
-    let line_sep = format!("\n{}", indent_chars);
+    let line_sep = if config.compact {
+        " ".to_owned()
+    } else {
+        format!("\n{}", config.indent)
+    };
     let content = data
         .chunks(8)
         .map(|chunk| {
@@ -201,7 +306,7 @@ fn print_hex_byte_data(
                     //
                     // Rust std format!()
                     // https://doc.rust-lang.org/std/fmt/
-                    if idx == 4 {
+                    if idx == 4 && !config.compact {
                         format!("  {:02x}", byte)
                     } else if idx == 0 {
                         format!("{:02x}", byte)
@@ -218,41 +323,119 @@ fn print_hex_byte_data(
     write!(writer, "h\"{}\"", content)
 }
 
+// prints the element of list, object or map on its own line,
+// along with the comments of the element.
+// the elements are separated by commas in the compact mode instead.
+fn print_element<F>(
+    writer: &mut dyn Write,
+    index: usize,
+    leading_space: &str,
+    config: &PrettyConfig,
+    cursor: &mut PrintCursor,
+    print_content: F,
+) -> Result<(), std::io::Error>
+where
+    F: FnOnce(&mut dyn Write, &mut PrintCursor) -> Result<(), std::io::Error>,
+{
+    cursor.path.push(index);
+
+    if config.compact {
+        if index > 0 {
+            write!(writer, ",")?;
+        }
+        print_content(writer, cursor)?;
+        cursor.path.pop();
+        return Ok(());
+    }
+    let node_comments = cursor.current();
+
+    if let Some(node_comments) = node_comments {
+        print_comment_lines(writer, &node_comments.leading, leading_space)?;
+    }
+
+    write!(writer, "{}", leading_space)?;
+    print_content(writer, cursor)?;
+
+    if let Some(node_comments) = node_comments {
+        print_trailing_comments(writer, &node_comments.trailing)?;
+    }
+
+    writeln!(writer)?;
+    cursor.path.pop();
+    Ok(())
+}
+
+// prints the opening bracket (or brace) of list, object or map,
+// the elements start from the next line unless in the compact mode.
+fn print_opening(
+    writer: &mut dyn Write,
+    opening: &str,
+    config: &PrettyConfig,
+) -> Result<(), std::io::Error> {
+    write!(writer, "{}", opening)?;
+    if !config.compact {
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+// prints the dangling comments of the current list, object or map.
+fn print_dangling_comments(
+    writer: &mut dyn Write,
+    leading_space: &str,
+    cursor: &PrintCursor,
+) -> Result<(), std::io::Error> {
+    match cursor.current() {
+        Some(node_comments) => print_comment_lines(writer, &node_comments.dangling, leading_space),
+        None => Ok(()),
+    }
+}
+
 fn print_list(
     writer: &mut dyn Write,
     v: &[AsonNode],
-    indent_chars: &str,
+    config: &PrettyConfig,
     indent_level: usize,
+    cursor: &mut PrintCursor,
 ) -> Result<(), std::io::Error> {
-    let leading_space = indent_chars.repeat(indent_level);
+    let leading_space = config.leading_space(indent_level);
     let sub_level = indent_level + 1;
-    let element_leading_space = indent_chars.repeat(sub_level);
-
-    writeln!(writer, "[")?;
-    for e in v {
-        write!(writer, "{}", element_leading_space)?;
-        print_node(writer, e, indent_chars, sub_level)?;
-        writeln!(writer)?;
+    let element_leading_space = config.leading_space(sub_level);
+
+    print_opening(writer, "[", config)?;
+    for (index, e) in v.iter().enumerate() {
+        print_element(
+            writer,
+            index,
+            &element_leading_space,
+            config,
+            cursor,
+            |writer, cursor| print_node(writer, e, config, sub_level, cursor),
+        )?;
     }
+    print_dangling_comments(writer, &element_leading_space, cursor)?;
     write!(writer, "{}]", leading_space)
 }
 
 fn print_tuple(
     writer: &mut dyn Write,
     v: &[AsonNode],
-    indent_chars: &str,
+    config: &PrettyConfig,
     indent_level: usize,
+    cursor: &mut PrintCursor,
 ) -> Result<(), std::io::Error> {
     write!(writer, "(")?;
     let mut is_first_element = true;
 
-    for e in v {
+    for (index, e) in v.iter().enumerate() {
         if is_first_element {
             is_first_element = false;
         } else {
-            write!(writer, ", ")?;
+            write!(writer, "{}", config.element_separator())?;
         }
-        print_node(writer, e, indent_chars, indent_level)?;
+        cursor.path.push(index);
+        print_node(writer, e, config, indent_level, cursor)?;
+        cursor.path.pop();
     }
     write!(writer, ")")
 }
@@ -260,84 +443,391 @@ fn print_tuple(
 fn print_object(
     writer: &mut dyn Write,
     v: &[KeyValuePair],
-    indent_chars: &str,
+    config: &PrettyConfig,
     indent_level: usize,
+    cursor: &mut PrintCursor,
 ) -> Result<(), std::io::Error> {
-    let leading_space = indent_chars.repeat(indent_level);
+    let leading_space = config.leading_space(indent_level);
     let sub_level = indent_level + 1;
-    let element_leading_space = indent_chars.repeat(sub_level);
-
-    writeln!(writer, "{{")?;
-    for e in v {
-        write!(writer, "{}{}: ", element_leading_space, e.key)?;
-        print_node(writer, &e.value, indent_chars, sub_level)?;
-        writeln!(writer)?;
+    let element_leading_space = config.leading_space(sub_level);
+
+    print_opening(writer, "{", config)?;
+    for (index, e) in v.iter().enumerate() {
+        print_element(
+            writer,
+            index,
+            &element_leading_space,
+            config,
+            cursor,
+            |writer, cursor| {
+                if is_valid_identifier(&e.key) {
+                    write!(writer, "{}", e.key)?;
+                } else {
+                    print_string(writer, &e.key, config)?;
+                }
+                write!(writer, "{}", config.key_value_separator())?;
+                print_node(writer, &e.value, config, sub_level, cursor)
+            },
+        )?;
     }
+    print_dangling_comments(writer, &element_leading_space, cursor)?;
     write!(writer, "{}}}", leading_space)
 }
 
 fn print_map(
     writer: &mut dyn Write,
     v: &[NameValuePair],
-    indent_chars: &str,
+    config: &PrettyConfig,
     indent_level: usize,
+    cursor: &mut PrintCursor,
 ) -> Result<(), std::io::Error> {
-    let leading_space = indent_chars.repeat(indent_level);
+    let leading_space = config.leading_space(indent_level);
     let sub_level = indent_level + 1;
-    let element_leading_space = indent_chars.repeat(sub_level);
-
-    writeln!(writer, "[")?;
-    for e in v {
-        write!(writer, "{}", element_leading_space)?;
-        print_node(writer, &e.name, indent_chars, sub_level)?;
-        write!(writer, ": ")?;
-        print_node(writer, &e.value, indent_chars, sub_level)?;
-        writeln!(writer)?;
+    let element_leading_space = config.leading_space(sub_level);
+
+    // the empty map is written as `[:]` to distinguish from the empty list.
+    if v.is_empty() {
+        write!(writer, "[:")?;
+        if cursor
+            .current()
+            .is_some_and(|node_comments| !node_comments.dangling.is_empty())
+        {
+            writeln!(writer)?;
+            print_dangling_comments(writer, &element_leading_space, cursor)?;
+            write!(writer, "{}", leading_space)?;
+        }
+        return write!(writer, "]");
+    }
+
+    print_opening(writer, "[", config)?;
+    for (index, e) in v.iter().enumerate() {
+        print_element(
+            writer,
+            index,
+            &element_leading_space,
+            config,
+            cursor,
+            |writer, cursor| {
+                // the name and value take the sub-indices `0` and `1`.
+                cursor.path.push(0);
+                print_node(writer, &e.name, config, sub_level, cursor)?;
+                cursor.path.pop();
+                write!(writer, "{}", config.key_value_separator())?;
+                cursor.path.push(1);
+                print_node(writer, &e.value, config, sub_level, cursor)?;
+                cursor.path.pop();
+                Ok(())
+            },
+        )?;
     }
+    print_dangling_comments(writer, &element_leading_space, cursor)?;
     write!(writer, "{}]", leading_space)
 }
 
 fn print_node(
     writer: &mut dyn Write,
     node: &AsonNode,
-    indent_chars: &str,
+    config: &PrettyConfig,
     indent_level: usize,
+    cursor: &mut PrintCursor,
 ) -> Result<(), std::io::Error> {
     match node {
-        AsonNode::Number(v) => print_number(writer, v),
+        AsonNode::Number(v) => match cursor.float_literals.get(&cursor.path) {
+            Some(text) => write!(writer, "{}", text),
+            None => print_number(writer, v),
+        },
         AsonNode::Boolean(v) => print_boolean(writer, v),
         AsonNode::Char(v) => print_char(writer, v),
-        AsonNode::String(v) => print_string(writer, v),
+        AsonNode::String(v) => print_string(writer, v, config),
         AsonNode::DateTime(v) => print_date(writer, v),
-        AsonNode::Variant(v) => print_variant(writer, v, indent_chars, indent_level),
-        AsonNode::HexByteData(v) => print_hex_byte_data(writer, v, indent_chars),
-        AsonNode::List(v) => print_list(writer, v, indent_chars, indent_level),
-        AsonNode::Tuple(v) => print_tuple(writer, v, indent_chars, indent_level),
-        AsonNode::Object(v) => print_object(writer, v, indent_chars, indent_level),
-        AsonNode::Map(v) => print_map(writer, v, indent_chars, indent_level),
+        AsonNode::Variant(v) => print_variant(writer, v, config, indent_level, cursor),
+        AsonNode::HexByteData(v) => print_hex_byte_data(writer, v, config),
+        AsonNode::List(v) => print_list(writer, v, config, indent_level, cursor),
+        AsonNode::Tuple(v) => print_tuple(writer, v, config, indent_level, cursor),
+        AsonNode::Object(v) => print_object(writer, v, config, indent_level, cursor),
+        AsonNode::Map(v) => print_map(writer, v, config, indent_level, cursor),
+        AsonNode::Extension(name, v) => {
+            write!(writer, "@{}(", name)?;
+            print_node(writer, v, config, indent_level, cursor)?;
+            write!(writer, ")")
+        }
     }
 }
 
+// prints the comment as it is in the source, i.e. `//...` or `/*...*/`.
+fn print_source_comment(writer: &mut dyn Write, comment: &Comment) -> Result<(), std::io::Error> {
+    match comment {
+        Comment::Line(text, opener) => write!(writer, "{}{}", opener.as_str(), text),
+        Comment::Block(text) => write!(writer, "/*{}*/", text),
+    }
+}
+
+// prints each comment on its own line.
+fn print_comment_lines(
+    writer: &mut dyn Write,
+    comments: &[Comment],
+    leading_space: &str,
+) -> Result<(), std::io::Error> {
+    for comment in comments {
+        write!(writer, "{}", leading_space)?;
+        print_source_comment(writer, comment)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+// prints the comments following the node on the same line.
+fn print_trailing_comments(
+    writer: &mut dyn Write,
+    comments: &[Comment],
+) -> Result<(), std::io::Error> {
+    for comment in comments {
+        write!(writer, " ")?;
+        print_source_comment(writer, comment)?;
+    }
+    Ok(())
+}
+
+fn print_comment(
+    writer: &mut dyn Write,
+    text: &str,
+    comment_style: CommentStyle,
+) -> Result<(), std::io::Error> {
+    let prefix = match comment_style {
+        CommentStyle::DoubleSlash => "//",
+        CommentStyle::Hash => "#",
+    };
+
+    for line in text.lines() {
+        if line.is_empty() {
+            writeln!(writer, "{}", prefix)?;
+        } else {
+            writeln!(writer, "{} {}", prefix, line)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the text as line comments in the specified style,
+/// each line of the text becomes a line comment.
+pub fn print_comment_to_string(text: &str, comment_style: CommentStyle) -> String {
+    let mut buf: Vec<u8> = vec![];
+    print_comment(&mut buf, text, comment_style).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
 pub fn print_to_writer(writer: &mut dyn Write, node: &AsonNode) -> Result<(), AsonError> {
-    match print_node(writer, node, DEFAULT_INDENT_CHARS, 0) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(AsonError::Message(e.to_string())),
+    print_to_writer_with_config(writer, node, &PrettyConfig::default())
+}
+
+pub fn print_to_writer_with_config(
+    writer: &mut dyn Write,
+    node: &AsonNode,
+    config: &PrettyConfig,
+) -> Result<(), AsonError> {
+    print_node(writer, node, config, 0, &mut PrintCursor::new(None))?;
+    Ok(())
+}
+
+/// Prints the node and re-emits the floating-point numbers as they are
+/// written in the source text, e.g. `1.50` instead of `1.5`, the ranges are
+/// obtained by `parse_from_str_with_ranges`.
+///
+/// The literal is re-emitted only if it still represents the number,
+/// i.e. the numbers which are changed after parsing are printed as usual.
+pub fn print_to_writer_with_source(
+    writer: &mut dyn Write,
+    node: &AsonNode,
+    ranges: &NodeRange,
+    source: &str,
+) -> Result<(), AsonError> {
+    // the byte offsets of chars, since the ranges count chars.
+    let byte_offsets = source
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(source.len()))
+        .collect::<Vec<usize>>();
+
+    let mut cursor = PrintCursor::new(None);
+    collect_float_literals(
+        node,
+        ranges,
+        source,
+        &byte_offsets,
+        &mut cursor.path,
+        &mut cursor.float_literals,
+    );
+
+    print_node(writer, node, &PrettyConfig::default(), 0, &mut cursor)?;
+    Ok(())
+}
+
+pub fn print_to_string_with_source(node: &AsonNode, ranges: &NodeRange, source: &str) -> String {
+    let mut buf: Vec<u8> = vec![];
+    print_to_writer_with_source(&mut buf, node, ranges, source).unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+// collects the source text of the floating-point numbers, the keys
+// are the paths of nodes, the same as the `PrintCursor`.
+fn collect_float_literals<'s>(
+    node: &AsonNode,
+    ranges: &NodeRange,
+    source: &'s str,
+    byte_offsets: &[usize],
+    path: &mut Vec<usize>,
+    literals: &mut HashMap<Vec<usize>, &'s str>,
+) {
+    let mut collect_child = |index: usize, node: &AsonNode, ranges: Option<&NodeRange>| {
+        if let Some(ranges) = ranges {
+            path.push(index);
+            collect_float_literals(node, ranges, source, byte_offsets, path, literals);
+            path.pop();
+        }
+    };
+
+    match node {
+        AsonNode::Number(number @ (Number::F32(_) | Number::F64(_))) => {
+            let range = &ranges.range;
+            let (Some(start), Some(end)) = (
+                byte_offsets.get(range.index),
+                byte_offsets.get(range.index + range.length),
+            ) else {
+                return;
+            };
+
+            let text = &source[*start..*end];
+            if matches!(parse_from_str(text), Ok(AsonNode::Number(n)) if &n == number) {
+                literals.insert(path.clone(), text);
+            }
+        }
+        AsonNode::List(items)
+        | AsonNode::Tuple(items)
+        | AsonNode::Variant(Variant {
+            value: VariantValue::Tuple(items),
+            ..
+        }) => {
+            for (index, item) in items.iter().enumerate() {
+                collect_child(index, item, ranges.children.get(index));
+            }
+        }
+        AsonNode::Object(kvps)
+        | AsonNode::Variant(Variant {
+            value: VariantValue::Object(kvps),
+            ..
+        }) => {
+            for (index, kvp) in kvps.iter().enumerate() {
+                collect_child(index, &kvp.value, ranges.children.get(index));
+            }
+        }
+        AsonNode::Map(nvps) => {
+            // the names and values are the children alternately,
+            // and they take the sub-indices `0` and `1`.
+            for (index, nvp) in nvps.iter().enumerate() {
+                path.push(index);
+                for (sub_index, child) in [&nvp.name, &nvp.value].into_iter().enumerate() {
+                    if let Some(child_ranges) = ranges.children.get(index * 2 + sub_index) {
+                        path.push(sub_index);
+                        collect_float_literals(
+                            child,
+                            child_ranges,
+                            source,
+                            byte_offsets,
+                            path,
+                            literals,
+                        );
+                        path.pop();
+                    }
+                }
+                path.pop();
+            }
+        }
+        AsonNode::Variant(Variant {
+            value: VariantValue::Value(v),
+            ..
+        }) => collect_child(0, v, ranges.children.first()),
+        AsonNode::Extension(_, v) => {
+            // the value of extension has the same path as the extension
+            if let Some(child_ranges) = ranges.children.first() {
+                collect_float_literals(v, child_ranges, source, byte_offsets, path, literals);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Prints the node along with the comments kept by `parse_from_str_with_comments`.
+pub fn print_to_writer_with_comments(
+    writer: &mut dyn Write,
+    node: &AsonNode,
+    comments: &Comments,
+) -> Result<(), AsonError> {
+    let mut cursor = PrintCursor::new(Some(comments));
+    let root_comments = cursor.current();
+
+    if let Some(root_comments) = root_comments {
+        print_comment_lines(writer, &root_comments.leading, "")?;
+    }
+
+    print_node(writer, node, &PrettyConfig::default(), 0, &mut cursor)?;
+
+    if let Some(root_comments) = root_comments {
+        print_trailing_comments(writer, &root_comments.trailing)?;
     }
+
+    for comment in &comments.end {
+        writeln!(writer)?;
+        print_source_comment(writer, comment)?;
+    }
+
+    Ok(())
+}
+
+pub fn print_to_string_with_comments(node: &AsonNode, comments: &Comments) -> String {
+    let mut buf: Vec<u8> = vec![];
+    print_to_writer_with_comments(&mut buf, node, comments).unwrap();
+    String::from_utf8(buf).unwrap()
 }
 
 pub fn print_to_string(node: &AsonNode) -> String {
+    print_to_string_with_config(node, &PrettyConfig::default())
+}
+
+pub fn print_to_string_with_config(node: &AsonNode, config: &PrettyConfig) -> String {
     let mut buf: Vec<u8> = vec![];
-    print_to_writer(&mut buf, node).unwrap();
+    print_to_writer_with_config(&mut buf, node, config).unwrap();
     String::from_utf8(buf).unwrap()
 }
 
+/// Re-prints the document in a single line with the minimal whitespaces,
+/// the comments are removed, see the `compact` option of `PrettyConfig`.
+pub fn minify(s: &str) -> Result<String, AsonError> {
+    let node = parse_from_str(s)?;
+    let config = PrettyConfig {
+        compact: true,
+        ..Default::default()
+    };
+    Ok(print_to_string_with_config(&node, &config))
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
 
-    use crate::parser::parse_from_str;
+    use crate::{
+        ast::{AsonNode, Comments, KeyValuePair, NodeComments, Number},
+        parser::{
+            parse_from_str, parse_from_str_with_comments, parse_from_str_with_options,
+            parse_from_str_with_ranges, ParseOptions,
+        },
+        token::{Comment, CommentStyle, LineCommentOpener},
+    };
 
-    use super::print_to_string;
+    use super::{
+        minify, print_comment_to_string, print_to_string, print_to_string_with_comments,
+        print_to_string_with_config, print_to_string_with_source, PrettyConfig,
+    };
 
     // fn new_string_node(s: &str) -> AsonNode {
     //     AsonNode::String(s.to_owned())
@@ -533,6 +1023,17 @@ mod tests {
 }"#
         );
 
+        // the keys which are not valid identifiers are quoted
+        assert_eq!(
+            format(r#"{"has space": 1, "true": 2, "a\"b": 3, "名字": 4}"#),
+            r#"{
+    "has space": 1
+    "true": 2
+    "a\"b": 3
+    名字: 4
+}"#
+        );
+
         assert_eq!(
             format(
                 r#"
@@ -565,6 +1066,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_print_with_tab_indent() {
+        let node = parse_from_str(
+            r#"{
+                id: 123
+                group: {
+                    name: "foo"
+                    orders: [11, 13]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let config = PrettyConfig {
+            indent: "\t".to_owned(),
+            ..Default::default()
+        };
+
+        let s = print_to_string_with_config(&node, &config);
+        assert_eq!(
+            s,
+            "{\n\tid: 123\n\tgroup: {\n\t\tname: \"foo\"\n\t\torders: [\n\t\t\t11\n\t\t\t13\n\t\t]\n\t}\n}"
+        );
+        assert_eq!(parse_from_str(&s).unwrap(), node);
+    }
+
+    #[test]
+    fn test_minify() {
+        let s0 = r#"
+            {
+                id:123
+                name:"hello"
+                orders: [
+                    (1, "foo", true)
+                    (2, "bar", false)
+                ]
+                group: {
+                    active: true
+                    permissions:[
+                        {number:11, title: "read"}
+                        {number:13, title: "write"}
+                    ]
+                }
+            }
+            "#;
+
+        let m0 = minify(s0).unwrap();
+        assert_eq!(
+            m0,
+            r#"{id:123,name:"hello",orders:[(1,"foo",true),(2,"bar",false)],group:{active:true,permissions:[{number:11,title:"read"},{number:13,title:"write"}]}}"#
+        );
+        assert!(m0.len() < s0.len());
+        assert_eq!(parse_from_str(&m0).unwrap(), parse_from_str(s0).unwrap());
+
+        // comments are removed, and the new-lines of strings are escaped
+        let s1 = r#"{
+            // line comment
+            text: "foo
+bar" /* block comment */
+            data: h"11 13 17 19 23 29"
+            scores: [
+                "a": -1.5
+                "b": Option::Some(0x10_u8)
+            ]
+            empty: [:]
+            key: Key::Pair("x", 7)
+        }"#;
+
+        let m1 = minify(s1).unwrap();
+        assert_eq!(
+            m1,
+            r#"{text:"foo\nbar",data:h"11 13 17 19 23 29",scores:["a":-1.5,"b":Option::Some(16_u8)],empty:[:],key:Key::Pair("x",7)}"#
+        );
+        assert_eq!(parse_from_str(&m1).unwrap(), parse_from_str(s1).unwrap());
+
+        assert!(minify("[11, 13").is_err());
+    }
+
     #[test]
     fn test_print_map() {
         assert_eq!(
@@ -578,6 +1157,8 @@ mod tests {
     456: "hello"
 ]"#
         );
+
+        assert_eq!(format(r#"[:]"#), r#"[:]"#);
     }
 
     #[test]
@@ -647,6 +1228,257 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_print_extension() {
+        let node = AsonNode::Extension(
+            "ref".to_owned(),
+            Box::new(AsonNode::Object(vec![KeyValuePair::new(
+                "id",
+                AsonNode::Number(Number::I32(11)),
+            )])),
+        );
+
+        assert_eq!(
+            print_to_string(&node),
+            r#"@ref({
+    id: 11
+})"#
+        );
+    }
+
+    #[test]
+    fn test_print_comment() {
+        assert_eq!(
+            print_comment_to_string("foo\n\nbar", CommentStyle::DoubleSlash),
+            "// foo\n//\n// bar\n"
+        );
+
+        assert_eq!(
+            print_comment_to_string("foo\n\nbar", CommentStyle::Hash),
+            "# foo\n#\n# bar\n"
+        );
+
+        // the comments can be parsed back
+        let options = ParseOptions {
+            comment_style: CommentStyle::Hash,
+            ..Default::default()
+        };
+
+        let s = format!(
+            "{}{}",
+            print_comment_to_string("the id", CommentStyle::Hash),
+            "123"
+        );
+        assert_eq!(
+            parse_from_str_with_options(&s, &options).unwrap(),
+            AsonNode::Number(Number::I32(123))
+        );
+    }
+
+    #[test]
+    fn test_print_with_comments() {
+        let text = r#"// the configuration of server
+{
+    // the name of server
+    name: "foo" // inline
+    ports: [
+        80 // http
+        /* the secure port */
+        443
+        // more ports later
+    ]
+    mode: Mode::Fast /* block */ // line
+    backup: Option::Some({
+        // the path
+        path: "/tmp"
+    })
+    limits: [
+        "cpu": 2 // cores
+    ]
+    // the end of object
+}
+// the end of document"#;
+
+        let (node, comments) =
+            parse_from_str_with_comments(text, &ParseOptions::default()).unwrap();
+        assert_eq!(print_to_string_with_comments(&node, &comments), text);
+
+        // the same as `print_to_string` without comments
+        assert_eq!(
+            print_to_string_with_comments(&node, &Comments::default()),
+            print_to_string(&node)
+        );
+
+        // reformat
+        let (node, comments) = parse_from_str_with_comments(
+            r#"[ // the first
+    11, 13 // the last
+    /* tail */ ]"#,
+            &ParseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            print_to_string_with_comments(&node, &comments),
+            r#"[
+    // the first
+    11
+    13 // the last
+    /* tail */
+]"#
+        );
+
+        // the names and values of map entries
+        let text = r#"[
+    [
+        1 // one
+    ]: [
+        2 // two
+    ]
+    "empty": [:
+        // nothing
+    ]
+]"#;
+
+        let (node, comments) =
+            parse_from_str_with_comments(text, &ParseOptions::default()).unwrap();
+        assert_eq!(print_to_string_with_comments(&node, &comments), text);
+
+        let (node, comments) =
+            parse_from_str_with_comments("[ // c\n :]", &ParseOptions::default()).unwrap();
+        assert_eq!(
+            print_to_string_with_comments(&node, &comments),
+            "[:\n    // c\n]"
+        );
+    }
+
+    #[test]
+    fn test_print_with_comment_openers() {
+        let options = ParseOptions {
+            comment_style: CommentStyle::Hash,
+            allow_shebang: true,
+            allow_document_marker: true,
+            ..Default::default()
+        };
+
+        let text = r#"#!/usr/bin/ason
+{
+    # hash
+    id: 1 // double slash
+    /* block */
+    name: "foo" # hash
+}"#;
+
+        let (node, comments) = parse_from_str_with_comments(text, &options).unwrap();
+        assert_eq!(print_to_string_with_comments(&node, &comments), text);
+
+        // the document marker is only recognized at the beginning of the document
+        let text = "--- front matter\n[\n    1 # one\n]";
+
+        let (node, comments) = parse_from_str_with_comments(text, &options).unwrap();
+        assert_eq!(print_to_string_with_comments(&node, &comments), text);
+    }
+
+    #[test]
+    fn test_print_with_leading_and_trailing_comments() {
+        let text = r#"{
+    // the leading comment of id
+    id: 1 // the id
+    /* the leading comment of name */
+    name: "foo" /* the name */ // line
+    tags: [
+        "a" // the first tag
+        // the leading comment of the second tag
+        "b"
+    ]
+}"#;
+
+        let (node, comments) =
+            parse_from_str_with_comments(text, &ParseOptions::default()).unwrap();
+
+        let line = |text: &str| Comment::Line(text.to_owned(), LineCommentOpener::DoubleSlash);
+        let block = |text: &str| Comment::Block(text.to_owned());
+
+        assert_eq!(
+            comments.nodes.get(&vec![0]),
+            Some(&NodeComments {
+                leading: vec![line(" the leading comment of id")],
+                trailing: vec![line(" the id")],
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            comments.nodes.get(&vec![1]),
+            Some(&NodeComments {
+                leading: vec![block(" the leading comment of name ")],
+                trailing: vec![block(" the name "), line(" line")],
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            comments.nodes.get(&vec![2, 0]),
+            Some(&NodeComments {
+                trailing: vec![line(" the first tag")],
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            comments.nodes.get(&vec![2, 1]),
+            Some(&NodeComments {
+                leading: vec![line(" the leading comment of the second tag")],
+                ..Default::default()
+            })
+        );
+
+        // the trailing comments are re-emitted on the same line
+        assert_eq!(print_to_string_with_comments(&node, &comments), text);
+    }
+
+    #[test]
+    fn test_print_with_source() {
+        let text = "1.50";
+        let (node, ranges) = parse_from_str_with_ranges(text, &ParseOptions::default()).unwrap();
+        assert_eq!(print_to_string_with_source(&node, &ranges, text), "1.50");
+
+        // the non-preserving path normalizes the number
+        assert_eq!(print_to_string(&node), "1.5");
+
+        let text = r#"{
+    a: [
+        1.50
+        2.0_f32
+        3
+    ]
+    b: [
+        1.10: Option::Some(2.50)
+    ]
+    c: 1.5e3
+}"#;
+        let (node, ranges) = parse_from_str_with_ranges(text, &ParseOptions::default()).unwrap();
+        assert_eq!(print_to_string_with_source(&node, &ranges, text), text);
+
+        // the changed numbers are printed as usual
+        let mut node = node;
+        let AsonNode::Object(kvps) = &mut node else {
+            unreachable!()
+        };
+        *kvps[2].value = AsonNode::Number(Number::F64(2.25));
+
+        assert_eq!(
+            print_to_string_with_source(&node, &ranges, text),
+            r#"{
+    a: [
+        1.50
+        2.0_f32
+        3
+    ]
+    b: [
+        1.10: Option::Some(2.50)
+    ]
+    c: 2.25
+}"#
+        );
+    }
+
     #[test]
     fn test_example_file_01() {
         let s = read_example_file_to_string("01-primitive.ason");
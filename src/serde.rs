@@ -5,6 +5,8 @@
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
 pub mod de;
+pub mod format;
+mod node;
 pub mod ser;
 pub mod serde_date;
 
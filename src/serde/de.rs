@@ -4,40 +4,247 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
-use std::io::Read;
+use std::{collections::HashMap, fmt::Display, io::Read, marker::PhantomData};
 
-use serde::de::{self, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess};
+use serde::de::{
+    self,
+    value::{MapDeserializer, StrDeserializer},
+    EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+};
 
 use crate::{
+    ast::AsonNode,
     charstream::CharStream,
     charwithposition::CharsWithPositionIter,
-    lexer::Lexer,
+    lexer::{Lexer, LexerOptions, DEFAULT_MAX_NUMBER_DIGITS, LEXER_PEEK_CHAR_MAX_COUNT},
     location::Location,
-    normalizer::{ClearTokenIter, NormalizedTokenIter, TrimmedTokenIter},
+    normalizer::{
+        ClearTokenIter, NormalizeOptions, NormalizedTokenIter, TrimmedTokenIter,
+        NORMALIZER_PEEK_TOKEN_MAX_COUNT,
+    },
+    parser::{check_document_separator, check_input_bytes, DocumentSeparator},
     peekableiter::PeekableIter,
-    token::{NumberToken, Token, TokenWithRange},
+    token::{CommentStyle, FloatType, NumberToken, StringStyle, Token, TokenWithRange},
+    tokens::Tokens,
     AsonError,
 };
 
-use super::Result;
+use super::{
+    node::{NodeDeserializer, DATE_TIME_KEY},
+    Result,
+};
+
+// the colon of map is peeked over the first key and a new-line, e.g. `["foo"\n: 11]`.
+pub const DESERIALIZER_PEEK_TOKEN_MAX_COUNT: usize = 3;
+
+/// Options for deserializing.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DeserializeOptions {
+    /// Accept values of compatible types, e.g. a `Char` for a `String`,
+    /// a one-character `String` or the UTF-8 bytes of one character
+    /// (e.g. `h"f0 9f 8d 92"`) for a `Char`, the integer `0` and `1`
+    /// for a `Boolean`, and numbers of other types for a number, e.g.
+    /// `17` for an `i8` (as long as the value is in range) and for a `f32`.
+    pub lenient: bool,
+
+    /// Report an error when a plus sign precedes a number, e.g. `+5`,
+    /// instead of silently removing it.
+    pub forbid_redundant_plus: bool,
+
+    /// The style of line comments to accept in addition to `//`.
+    pub comment_style: CommentStyle,
+
+    /// Report an error when a char or string contains the null char.
+    pub forbid_interior_nulls: bool,
+
+    /// Accept the bare member name of enum, e.g. `Red` for `Color::Red`.
+    pub allow_bare_variant: bool,
+
+    /// Abort with an error once the number of deserialized nodes
+    /// (the root, the elements of lists and tuples, the keys and values of
+    /// maps, the values of objects and variants, and the skipped values)
+    /// exceeds this count. The nodes are counted the same as `ParseOptions`.
+    pub max_total_nodes: Option<usize>,
+
+    /// Report an error when an object or map has more entries than this count.
+    pub max_map_entries: Option<usize>,
+
+    /// Report an error when the name of an object key, or the string key
+    /// of a map, has more characters than this length.
+    pub max_key_length: Option<usize>,
+
+    /// Trim the leading and trailing whitespaces of string values,
+    /// e.g. `" foo "` is deserialized as `"foo"`.
+    /// The keys of objects and maps are not affected.
+    ///
+    /// The auto-trimmed strings (`"""..."""`) are not affected
+    /// unless `trim_auto_trimmed_strings` is also set, since their
+    /// whitespaces may be intentional.
+    pub trim_strings: bool,
+
+    /// See `trim_strings`.
+    pub trim_auto_trimmed_strings: bool,
+
+    /// Report an error when a numeric literal has more digits than
+    /// this count, it is 512 when not specified.
+    pub max_number_digits: Option<usize>,
+
+    /// Skip the shebang line (e.g. `#!/usr/bin/env ason`) at
+    /// the very beginning of the document.
+    pub allow_shebang: bool,
+
+    /// Skip the document marker line (`---`) at the very beginning
+    /// of the document, e.g. the ASON text after the YAML-style front matter.
+    pub allow_document_marker: bool,
+
+    /// Accept a floating-point number without fractional part for
+    /// an integer, e.g. `5.0` for an `i32`, the number with fractional
+    /// part (e.g. `5.5`) is still rejected.
+    pub coerce_float_to_int: bool,
+
+    /// Keep the unknown escape sequences of strings verbatim
+    /// (e.g. `"\q"` as `\q`) instead of reporting an error.
+    pub lenient_escapes: bool,
+
+    /// Accept an integer as the index of the member of enum, e.g. `0` for
+    /// the first member, which is how C-like enums are often stored.
+    pub allow_variant_index: bool,
+
+    /// The type of the floating-point literals without type suffix, note that
+    /// the `f64` values then require the `_f64` suffix, unless `lenient` is set.
+    pub default_float_type: FloatType,
+
+    /// Report an error when the key of an object is not an identifier,
+    /// e.g. the string key `"name"` and the number key `123`, which are
+    /// otherwise accepted when deserializing an object into a map.
+    pub require_identifier_keys: bool,
+
+    /// Report an error when the document has more bytes than this count,
+    /// it is checked before lexing, so the oversized document is rejected
+    /// immediately. It applies to the string (`&str`) entry points only.
+    pub max_input_bytes: Option<usize>,
+
+    /// The separator between the top-level values for `from_str_all_with_options`.
+    pub document_separator: DocumentSeparator,
+
+    /// Replace the undefined variables with the empty string instead of
+    /// reporting an error, when interpolating with `from_str_with_env`.
+    pub allow_undefined_env: bool,
+}
+
+impl DeserializeOptions {
+    /// Returns the names of the options which differ from the default,
+    /// it is useful for logging and debugging the configuration.
+    pub fn describe(&self) -> Vec<&'static str> {
+        let flags = [
+            (self.lenient, "lenient"),
+            (self.forbid_redundant_plus, "forbid_redundant_plus"),
+            (
+                self.comment_style != CommentStyle::default(),
+                "comment_style",
+            ),
+            (self.forbid_interior_nulls, "forbid_interior_nulls"),
+            (self.allow_bare_variant, "allow_bare_variant"),
+            (self.max_total_nodes.is_some(), "max_total_nodes"),
+            (self.max_map_entries.is_some(), "max_map_entries"),
+            (self.max_key_length.is_some(), "max_key_length"),
+            (self.trim_strings, "trim_strings"),
+            (self.trim_auto_trimmed_strings, "trim_auto_trimmed_strings"),
+            (self.max_number_digits.is_some(), "max_number_digits"),
+            (self.allow_shebang, "allow_shebang"),
+            (self.allow_document_marker, "allow_document_marker"),
+            (self.coerce_float_to_int, "coerce_float_to_int"),
+            (self.lenient_escapes, "lenient_escapes"),
+            (self.allow_variant_index, "allow_variant_index"),
+            (
+                self.default_float_type != FloatType::default(),
+                "default_float_type",
+            ),
+            (self.require_identifier_keys, "require_identifier_keys"),
+            (self.max_input_bytes.is_some(), "max_input_bytes"),
+            (
+                self.document_separator != DocumentSeparator::default(),
+                "document_separator",
+            ),
+            (self.allow_undefined_env, "allow_undefined_env"),
+        ];
+
+        flags
+            .into_iter()
+            .filter_map(|(enabled, name)| enabled.then_some(name))
+            .collect()
+    }
+}
+
+/// Deserializes the document into a value of type `T`.
+///
+/// The strings without escape chars can be borrowed from the document,
+/// e.g. the `&str` fields and the `Cow<str>` fields with `#[serde(borrow)]`.
+pub fn from_str<'de, T>(s: &'de str) -> Result<T>
+where
+    T: de::Deserialize<'de>,
+{
+    from_str_with_options(s, &DeserializeOptions::default())
+}
 
-pub fn from_str<T>(s: &str) -> Result<T>
+pub fn from_str_with_options<'de, T>(s: &'de str, options: &DeserializeOptions) -> Result<T>
 where
-    T: de::DeserializeOwned,
+    T: de::Deserialize<'de>,
+{
+    check_input_bytes(s, options.max_input_bytes)?;
+
+    let mut chars = s.chars();
+
+    with_token_peekable_iter(&mut chars, options, |upstream| {
+        from_token_peekable_iter(upstream, Some(s), None, options)
+    })
+}
+
+/// Deserializes the document and substitutes the `${NAME}` in string values
+/// with the value of the variable `NAME` of the `env`, e.g. `"${HOME}/.config"`.
+///
+/// The `$${` is the escaped literal `${`, e.g. `"$${HOME}"` => `"${HOME}"`.
+/// An undefined variable is reported as an error,
+/// unless the `allow_undefined_env` option is set.
+///
+/// Only the string values are interpolated, the keys of objects and maps
+/// (e.g. `{"${NAME}": 1}` and `["${NAME}": 1]`) and the chars are kept as they are.
+pub fn from_str_with_env<'de, T>(
+    s: &'de str,
+    env: &HashMap<String, String>,
+    options: &DeserializeOptions,
+) -> Result<T>
+where
+    T: de::Deserialize<'de>,
 {
+    check_input_bytes(s, options.max_input_bytes)?;
+
     let mut chars = s.chars();
-    from_char_stream(&mut chars)
+
+    with_token_peekable_iter(&mut chars, options, |upstream| {
+        from_token_peekable_iter(upstream, Some(s), Some(env), options)
+    })
+}
+
+pub fn from_reader<T, R: Read>(r: R) -> Result<T>
+where
+    T: de::DeserializeOwned,
+{
+    from_reader_with_options(r, &DeserializeOptions::default())
 }
 
-pub fn from_reader<T, R: Read>(mut r: R) -> Result<T>
+pub fn from_reader_with_options<T, R: Read>(mut r: R, options: &DeserializeOptions) -> Result<T>
 where
     T: de::DeserializeOwned,
 {
     let mut char_stream = CharStream::new(&mut r);
-    from_char_stream(&mut char_stream)
+    from_char_stream(&mut char_stream, options)
 }
 
-pub fn from_char_stream<T>(char_stream: &mut dyn Iterator<Item = char>) -> Result<T>
+pub fn from_char_stream<T>(
+    char_stream: &mut dyn Iterator<Item = char>,
+    options: &DeserializeOptions,
+) -> Result<T>
 where
     T: de::DeserializeOwned,
 {
@@ -48,19 +255,122 @@ where
     // see:
     // https://serde.rs/lifetimes.html
 
+    with_token_peekable_iter(char_stream, options, |upstream| {
+        from_token_peekable_iter(upstream, None, None, options)
+    })
+}
+
+/// Deserializes all top-level values of the document, e.g. a document
+/// consisting of values separated by new-lines (like NDJSON).
+pub fn from_str_all<T>(s: &str) -> Result<Vec<T>>
+where
+    T: de::DeserializeOwned,
+{
+    from_str_all_with_options(s, &DeserializeOptions::default())
+}
+
+pub fn from_str_all_with_options<T>(s: &str, options: &DeserializeOptions) -> Result<Vec<T>>
+where
+    T: de::DeserializeOwned,
+{
+    check_input_bytes(s, options.max_input_bytes)?;
+    check_document_separator(options.document_separator)?;
+
+    let mut chars = s.chars();
+
+    with_token_peekable_iter(&mut chars, options, |upstream| {
+        all_from_token_peekable_iter(upstream, options)
+    })
+}
+
+// builds the token pipeline upon the char stream and
+// then passes the final token iterator to the function `f`.
+fn with_token_peekable_iter<R, F>(
+    char_stream: &mut dyn Iterator<Item = char>,
+    options: &DeserializeOptions,
+    f: F,
+) -> Result<R>
+where
+    F: for<'a> FnOnce(&'a mut PeekableIter<'a, Result<TokenWithRange>>) -> Result<R>,
+{
+    let lexer_options = LexerOptions {
+        comment_style: options.comment_style,
+        forbid_interior_nulls: options.forbid_interior_nulls,
+        max_number_digits: options
+            .max_number_digits
+            .unwrap_or(DEFAULT_MAX_NUMBER_DIGITS),
+        allow_shebang: options.allow_shebang,
+        allow_document_marker: options.allow_document_marker,
+        lenient_escapes: options.lenient_escapes,
+        default_float_type: options.default_float_type,
+        document_separator: options.document_separator.custom_char(),
+        ..Default::default()
+    };
+
+    let normalize_options = NormalizeOptions {
+        forbid_redundant_plus: options.forbid_redundant_plus,
+    };
+
     let mut char_position_iter = CharsWithPositionIter::new(char_stream);
-    let mut peekable_char_position_iter = PeekableIter::new(&mut char_position_iter, 3);
-    let mut lexer = Lexer::new(&mut peekable_char_position_iter);
+    let mut peekable_char_position_iter =
+        PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
+    let mut lexer = Lexer::with_options(&mut peekable_char_position_iter, lexer_options);
 
     let mut clear_iter = ClearTokenIter::new(&mut lexer);
-    let mut peekable_clear_iter = PeekableIter::new(&mut clear_iter, 1);
-    let mut normalized_iter = NormalizedTokenIter::new(&mut peekable_clear_iter);
-    let mut peekable_normalized_iter = PeekableIter::new(&mut normalized_iter, 1);
+    let mut peekable_clear_iter =
+        PeekableIter::new(&mut clear_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
+    let mut normalized_iter =
+        NormalizedTokenIter::with_options(&mut peekable_clear_iter, normalize_options);
+    let mut peekable_normalized_iter =
+        PeekableIter::new(&mut normalized_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
     let mut trimmed_iter = TrimmedTokenIter::new(&mut peekable_normalized_iter);
-    let mut peekable_trimmed_iter = PeekableIter::new(&mut trimmed_iter, 2);
+    let mut peekable_trimmed_iter =
+        PeekableIter::new(&mut trimmed_iter, DESERIALIZER_PEEK_TOKEN_MAX_COUNT);
+
+    f(&mut peekable_trimmed_iter)
+}
+
+pub fn from_tokens<T>(tokens: &Tokens) -> Result<T>
+where
+    T: de::DeserializeOwned,
+{
+    from_tokens_with_options(tokens, &DeserializeOptions::default())
+}
+
+pub fn from_tokens_with_options<T>(tokens: &Tokens, options: &DeserializeOptions) -> Result<T>
+where
+    T: de::DeserializeOwned,
+{
+    let mut token_iter = tokens.iter();
+    let mut peekable_token_iter =
+        PeekableIter::new(&mut token_iter, DESERIALIZER_PEEK_TOKEN_MAX_COUNT);
+
+    from_token_peekable_iter(&mut peekable_token_iter, None, None, options)
+}
 
-    let mut deserializer = Deserializer::from_token_peekable_iter(&mut peekable_trimmed_iter);
-    let value = T::deserialize(&mut deserializer)?;
+fn from_token_peekable_iter<'a, 'de, 'e, T>(
+    upstream: &'a mut PeekableIter<'a, Result<TokenWithRange>>,
+    source: Option<&'de str>,
+    env: Option<&'e HashMap<String, String>>,
+    options: &DeserializeOptions,
+) -> Result<T>
+where
+    T: de::Deserialize<'de>,
+{
+    let mut deserializer =
+        Deserializer::from_token_peekable_iter_with_options(upstream, options.clone())
+            .with_source(source)
+            .with_env(env);
+
+    // no value at all, which is not an incomplete document, see `AsonError::is_incomplete`.
+    if deserializer.peek_token(0)?.is_none() {
+        return Err(AsonError::Message("The document is empty.".to_owned()));
+    }
+
+    deserializer.increase_node_count()?;
+
+    let value =
+        T::deserialize(&mut deserializer).map_err(|e| deserializer.prepend_path_to_error(e))?;
 
     match deserializer.upstream.peek(0) {
         Some(Ok(TokenWithRange { range, .. })) => Err(AsonError::MessageWithLocation(
@@ -75,18 +385,273 @@ where
     }
 }
 
-pub struct Deserializer<'de> {
-    upstream: &'de mut PeekableIter<'de, Result<TokenWithRange>>,
+fn all_from_token_peekable_iter<'a, T>(
+    upstream: &'a mut PeekableIter<'a, Result<TokenWithRange>>,
+    options: &DeserializeOptions,
+) -> Result<Vec<T>>
+where
+    T: de::DeserializeOwned,
+{
+    let mut deserializer =
+        Deserializer::from_token_peekable_iter_with_options(upstream, options.clone());
+    let mut values = vec![];
+
+    while deserializer.peek_token(0)?.is_some() {
+        deserializer.increase_node_count()?;
+        values.push(
+            T::deserialize(&mut deserializer).map_err(|e| deserializer.prepend_path_to_error(e))?,
+        );
+        deserializer.consume_document_separator()?;
+    }
+
+    Ok(values)
+}
+
+pub struct Deserializer<'a, 'de, 'e> {
+    upstream: &'a mut PeekableIter<'a, Result<TokenWithRange>>,
     last_range: Location,
+    options: DeserializeOptions,
+    total_nodes: usize,
+
+    // the source text of the tokens, the strings without escape chars
+    // are borrowed from it instead of being copied.
+    source: Option<&'de str>,
+
+    // the (char index, byte index) pair of the last
+    // conversion from char index to byte index of the source text.
+    source_cursor: (usize, usize),
+
+    // the segments of the path of the value being deserialized,
+    // e.g. `outer`, `items`, `[3]` and `name`. the segments are kept
+    // when an error occurs, so the path of the failed value is reported.
+    path: Vec<PathSegment>,
+
+    // the variables for interpolating string values, see `from_str_with_env`,
+    // it has its own lifetime since no value borrows from it.
+    env: Option<&'e HashMap<String, String>>,
+
+    // it is set while deserializing the key of object and map,
+    // the keys are neither trimmed by the option `trim_strings`
+    // nor interpolated with the env.
+    is_map_key: bool,
 }
 
-impl<'de> Deserializer<'de> {
+impl<'a, 'de, 'e> Deserializer<'a, 'de, 'e> {
     pub fn from_token_peekable_iter(
-        upstream: &'de mut PeekableIter<'de, Result<TokenWithRange>>,
+        upstream: &'a mut PeekableIter<'a, Result<TokenWithRange>>,
+    ) -> Self {
+        Self::from_token_peekable_iter_with_options(upstream, DeserializeOptions::default())
+    }
+
+    pub fn from_token_peekable_iter_with_options(
+        upstream: &'a mut PeekableIter<'a, Result<TokenWithRange>>,
+        options: DeserializeOptions,
     ) -> Self {
+        debug_assert!(
+            upstream.capacity() >= DESERIALIZER_PEEK_TOKEN_MAX_COUNT,
+            "The capacity of the upstream of deserializer is too small."
+        );
+
         Self {
             upstream,
             last_range: Location::new_range(0, 0, 0, 0),
+            options,
+            total_nodes: 0,
+            source: None,
+            source_cursor: (0, 0),
+            path: vec![],
+            env: None,
+            is_map_key: false,
+        }
+    }
+
+    fn with_source(mut self, source: Option<&'de str>) -> Self {
+        self.source = source;
+        self
+    }
+
+    fn with_env(mut self, env: Option<&'e HashMap<String, String>>) -> Self {
+        self.env = env;
+        self
+    }
+
+    // converts the char index to the byte index of the source text.
+    //
+    // the ranges of tokens are increasing, so the conversion
+    // continues from the last position instead of the beginning.
+    fn source_byte_index(&mut self, source: &str, char_index: usize) -> usize {
+        if char_index < self.source_cursor.0 {
+            self.source_cursor = (0, 0);
+        }
+
+        let (cursor_char_index, cursor_byte_index) = self.source_cursor;
+        let byte_index = source[cursor_byte_index..]
+            .char_indices()
+            .nth(char_index - cursor_char_index)
+            .map_or(source.len(), |(offset, _)| cursor_byte_index + offset);
+
+        self.source_cursor = (char_index, byte_index);
+        byte_index
+    }
+
+    // returns the slice of source text between the delimiters of the last
+    // token if it is identical to the string value, it is `None` if the string
+    // contains escape chars or it is not taken from the source verbatim
+    // (e.g. the auto-trimmed strings and the joined adjacent strings),
+    // or the source text is unavailable.
+    fn borrow_from_source(&mut self, s: &str, style: StringStyle) -> Option<&'de str> {
+        let source = self.source?;
+        let start = self.source_byte_index(source, self.last_range.index);
+        let end = self.source_byte_index(source, self.last_range.index + self.last_range.length);
+        let token_text = &source[start..end];
+
+        // the lengths of the opening and closing delimiters
+        let (open, close) = match style {
+            StringStyle::Normal => (1, 1),                              // "..."
+            StringStyle::Raw if token_text.starts_with("r#") => (3, 2), // r#"..."#
+            StringStyle::Raw => (2, 1),                                 // r"..."
+            StringStyle::AutoTrimmed => return None,
+        };
+
+        let content = token_text.get(open..token_text.len().checked_sub(close)?)?;
+        (content == s).then_some(content)
+    }
+
+    // whether the string value of the last token should be trimmed,
+    // see the option `trim_strings`.
+    fn should_trim_string(&self, style: StringStyle) -> bool {
+        self.options.trim_strings
+            && !self.is_map_key
+            && (style != StringStyle::AutoTrimmed || self.options.trim_auto_trimmed_strings)
+    }
+
+    // substitutes the `${NAME}` of the string value with the variable
+    // if the env is provided, and `$${` with the literal `${`.
+    fn interpolate_env(&self, s: String) -> Result<String> {
+        let Some(env) = self.env.filter(|_| !self.is_map_key) else {
+            return Ok(s);
+        };
+
+        if !s.contains("${") {
+            return Ok(s);
+        }
+
+        let mut result = String::with_capacity(s.len());
+        let mut rest = s.as_str();
+
+        while let Some(offset) = rest.find('$') {
+            result.push_str(&rest[..offset]);
+            rest = &rest[offset..];
+
+            if let Some(remain) = rest.strip_prefix("$${") {
+                result.push_str("${");
+                rest = remain;
+            } else if let Some(remain) = rest.strip_prefix("${") {
+                let Some(end) = remain.find('}') else {
+                    return Err(AsonError::MessageWithLocation(
+                        "Missing the closing brace of the variable.".to_owned(),
+                        self.last_range,
+                    ));
+                };
+
+                let name = &remain[..end];
+                match env.get(name) {
+                    Some(value) => result.push_str(value),
+                    None if self.options.allow_undefined_env => {}
+                    None => {
+                        return Err(AsonError::MessageWithLocation(
+                            format!("Undefined variable \"{}\".", name),
+                            self.last_range,
+                        ));
+                    }
+                }
+                rest = &remain[end + 1..];
+            } else {
+                result.push('$');
+                rest = &rest[1..];
+            }
+        }
+
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    // deserializes the value with the segment appended to the path.
+    fn deserialize_with_path<T>(&mut self, segment: PathSegment, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        let depth = self.path.len();
+        self.path.push(segment);
+        let value = seed.deserialize(&mut *self)?;
+        self.path.truncate(depth);
+        Ok(value)
+    }
+
+    // deserializes the buffered node with the segment appended to the path.
+    fn deserialize_node_with_path<T>(
+        &mut self,
+        segment: PathSegment,
+        node: &AsonNode,
+        seed: T,
+    ) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        let depth = self.path.len();
+        self.path.push(segment);
+        let value = seed.deserialize(NodeDeserializer::new(node))?;
+        self.path.truncate(depth);
+        Ok(value)
+    }
+
+    // e.g. `outer.items[3].name: Expect a "i32".`
+    fn prepend_path_to_error(&self, error: AsonError) -> AsonError {
+        if self.path.is_empty() {
+            return error;
+        }
+
+        let mut path = String::new();
+        for segment in &self.path {
+            if !path.is_empty() && matches!(segment, PathSegment::Field(_)) {
+                path.push('.');
+            }
+            path.push_str(&segment.to_string());
+        }
+
+        match error {
+            AsonError::Message(message) => AsonError::Message(format!("{}: {}", path, message)),
+            AsonError::UnexpectedEndOfDocument(message) => {
+                AsonError::UnexpectedEndOfDocument(format!("{}: {}", path, message))
+            }
+            AsonError::MessageWithLocation(message, location) => {
+                AsonError::MessageWithLocation(format!("{}: {}", path, message), location)
+            }
+            AsonError::Io(kind, message) => AsonError::Io(kind, format!("{}: {}", path, message)),
+        }
+    }
+
+    // the path segment of the value of map or object, e.g. `name`, `["foo"]` and `[11]`.
+    fn key_path_segment(&self, entry_index: usize) -> Result<PathSegment> {
+        let segment = match self.peek_token(0)? {
+            Some(Token::Identifier(key)) => PathSegment::Field(key.to_owned()),
+            Some(Token::String(key, _)) => PathSegment::String(key.to_owned()),
+            Some(Token::Char(key)) => PathSegment::Char(*key),
+            Some(Token::Number(key)) => PathSegment::Number(key.clone()),
+            _ => PathSegment::Index(entry_index),
+        };
+
+        Ok(segment)
+    }
+
+    fn increase_node_count(&mut self) -> Result<()> {
+        self.total_nodes += 1;
+
+        match self.options.max_total_nodes {
+            Some(max_total_nodes) if self.total_nodes > max_total_nodes => Err(AsonError::Message(
+                "Document exceeds maximum node count.".to_owned(),
+            )),
+            _ => Ok(()),
         }
     }
 
@@ -117,6 +682,89 @@ impl<'de> Deserializer<'de> {
         }
     }
 
+    // integers of other types can be coerced in the lenient mode, and
+    // floating-point numbers can be coerced with `coerce_float_to_int`.
+    fn can_coerce_integer(&self, number: &NumberToken) -> bool {
+        self.options.lenient
+            || (self.options.coerce_float_to_int
+                && matches!(number, NumberToken::F32(_) | NumberToken::F64(_)))
+    }
+
+    // converts the number of any type to the target integer type.
+    fn coerce_integer<T>(&self, number: &NumberToken, type_name: &str) -> Result<T>
+    where
+        T: TryFrom<i128>,
+    {
+        let v = match number {
+            NumberToken::I8(v) => *v as i8 as i128,
+            NumberToken::U8(v) => *v as i128,
+            NumberToken::I16(v) => *v as i16 as i128,
+            NumberToken::U16(v) => *v as i128,
+            NumberToken::I32(v) => *v as i32 as i128,
+            NumberToken::U32(v) => *v as i128,
+            NumberToken::I64(v) => *v as i64 as i128,
+            NumberToken::U64(v) => *v as i128,
+            NumberToken::I128(v) => *v as i128,
+            NumberToken::U128(v) => i128::try_from(*v).map_err(|_| {
+                AsonError::MessageWithLocation(
+                    format!("The number is out of the range of \"{}\".", type_name),
+                    self.last_range,
+                )
+            })?,
+            NumberToken::F32(_) | NumberToken::F64(_) if self.options.coerce_float_to_int => {
+                let f = coerce_float(number);
+                if f.fract() != 0.0 || !f.is_finite() {
+                    return Err(AsonError::MessageWithLocation(
+                        format!(
+                            "The number has a fractional part, can not be converted to \"{}\".",
+                            type_name
+                        ),
+                        self.last_range,
+                    ));
+                }
+                f as i128
+            }
+            NumberToken::F32(_) | NumberToken::F64(_) => {
+                return Err(AsonError::MessageWithLocation(
+                    format!("Expect an \"{}\" value.", type_name),
+                    self.last_range.get_position_by_range_start(),
+                ));
+            }
+        };
+
+        T::try_from(v).map_err(|_| {
+            AsonError::MessageWithLocation(
+                format!("The number is out of the range of \"{}\".", type_name),
+                self.last_range,
+            )
+        })
+    }
+
+    // checks the number of entries (including the next one) and
+    // the length of the key of the next entry of object or map.
+    fn check_map_entry(&self, entry_count: usize) -> Result<()> {
+        if matches!(self.options.max_map_entries, Some(max_map_entries) if entry_count > max_map_entries)
+        {
+            return Err(AsonError::MessageWithLocation(
+                "Exceeds maximum entry count.".to_owned(),
+                self.peek_range(0)?.unwrap().get_position_by_range_start(),
+            ));
+        }
+
+        if let (Some(max_key_length), Some(Token::Identifier(key) | Token::String(key, _))) =
+            (self.options.max_key_length, self.peek_token(0)?)
+        {
+            if key.chars().count() > max_key_length {
+                return Err(AsonError::MessageWithLocation(
+                    "Key exceeds maximum length.".to_owned(),
+                    *self.peek_range(0)?.unwrap(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     fn expect_token(&self, offset: usize, expected_token: &Token) -> Result<bool> {
         Ok(matches!(
             self.peek_token(offset)?,
@@ -134,6 +782,51 @@ impl<'de> Deserializer<'de> {
         }
     }
 
+    // consume the separator between top-level values, it reports
+    // an error if the separator is missing and it is not the end of document.
+    fn consume_document_separator(&mut self) -> Result<()> {
+        let separated = match self.options.document_separator {
+            DocumentSeparator::Whitespace => {
+                self.consume_new_line_if_exist()?;
+                true
+            }
+            DocumentSeparator::NewLine => self.consume_new_line_if_exist()?,
+            DocumentSeparator::Comma => {
+                let separated = self.expect_token(0, &Token::Comma)?;
+                if separated {
+                    self.next_token()?;
+                }
+                separated
+            }
+            DocumentSeparator::Custom(_) => {
+                // the separator may be on its own line
+                if self.expect_token(0, &Token::NewLine)?
+                    && self.expect_token(1, &Token::DocumentSeparator)?
+                {
+                    self.next_token()?; // consume '\n'
+                }
+
+                let separated = self.expect_token(0, &Token::DocumentSeparator)?;
+                if separated {
+                    self.next_token()?; // consume the separator
+                    self.consume_new_line_if_exist()?;
+                }
+                separated
+            }
+        };
+
+        match self.peek_range(0)? {
+            Some(range) if !separated => Err(AsonError::MessageWithLocation(
+                format!(
+                    "Expect a {} between documents.",
+                    self.options.document_separator.describe()
+                ),
+                range.get_position_by_range_start(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
     // consume '\n' or ',' if they exist.
     fn consume_new_line_or_comma_if_exist(&mut self) -> Result<bool> {
         match self.peek_token(0)? {
@@ -164,6 +857,108 @@ impl<'de> Deserializer<'de> {
         }
     }
 
+    // consume the ':' of the empty map `[:]` if it exists.
+    fn consume_empty_map_colon_if_exist(&mut self) -> Result<()> {
+        if !self.expect_token(0, &Token::Colon)? {
+            return Ok(());
+        }
+
+        self.next_token()?; // consume ':'
+        let colon_end_position = self.last_range.get_position_by_range_end();
+        self.consume_new_line_if_exist()?;
+
+        match self.peek_token(0)? {
+            Some(Token::RightBracket) | None => Ok(()),
+            Some(_) => Err(AsonError::MessageWithLocation(
+                "Expect a right bracket after the colon of empty map.".to_owned(),
+                colon_end_position,
+            )),
+        }
+    }
+
+    // whether the next value consists of more than one token,
+    // i.e. list, tuple, object, map and variant with value.
+    fn is_composite_value(&self) -> Result<bool> {
+        let is_composite = match self.peek_token(0)? {
+            Some(Token::LeftParen | Token::LeftBracket | Token::LeftBrace) => true,
+            Some(Token::Variant(..)) => {
+                self.expect_token(1, &Token::LeftParen)?
+                    || self.expect_token(1, &Token::LeftBrace)?
+            }
+            _ => false,
+        };
+
+        Ok(is_composite)
+    }
+
+    // consume the tokens of a value, the brackets (and parentheses and braces)
+    // must be paired. the nested values are counted as the parser does.
+    fn skip_value(&mut self) -> Result<()> {
+        let mut closings: Vec<Token> = vec![];
+
+        // the value itself has been counted by the caller.
+        let mut is_first_value = true;
+        let mut is_variant_payload = false;
+
+        loop {
+            let token = self.next_token()?;
+
+            let starts_value = match &token {
+                Some(Token::LeftParen | Token::LeftBracket | Token::LeftBrace) => {
+                    !is_variant_payload
+                }
+                Some(
+                    Token::Boolean(_)
+                    | Token::Variant(..)
+                    | Token::Number(_)
+                    | Token::Char(_)
+                    | Token::String(..)
+                    | Token::Date(_)
+                    | Token::HexByteData(_),
+                ) => true,
+                _ => false,
+            };
+
+            is_variant_payload = false;
+
+            if starts_value && !std::mem::take(&mut is_first_value) {
+                self.increase_node_count()?;
+            }
+
+            match token {
+                Some(Token::LeftParen) => closings.push(Token::RightParen),
+                Some(Token::LeftBracket) => closings.push(Token::RightBracket),
+                Some(Token::LeftBrace) => closings.push(Token::RightBrace),
+                Some(token @ (Token::RightParen | Token::RightBracket | Token::RightBrace)) => {
+                    if closings.pop() != Some(token) {
+                        return Err(AsonError::MessageWithLocation(
+                            "Unexpected closing bracket.".to_owned(),
+                            self.last_range,
+                        ));
+                    }
+                }
+                Some(Token::Variant(..))
+                    if self.expect_token(0, &Token::LeftParen)?
+                        || self.expect_token(0, &Token::LeftBrace)? =>
+                {
+                    // the value of the member follows
+                    is_variant_payload = true;
+                    continue;
+                }
+                Some(_) => {}
+                None => {
+                    return Err(AsonError::UnexpectedEndOfDocument(
+                        "Incomplete value.".to_owned(),
+                    ))
+                }
+            }
+
+            if closings.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+
     // consume ')'
     fn consume_right_paren(&mut self) -> Result<()> {
         self.consume_token(&Token::RightParen, "close parenthese \")\"")
@@ -183,43 +978,201 @@ impl<'de> Deserializer<'de> {
     fn consume_colon(&mut self) -> Result<()> {
         self.consume_token(&Token::Colon, "colon sign")
     }
-}
-
-impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
-    type Error = AsonError;
 
-    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    // the variant token (or the bare member name) has been consumed.
+    fn visit_variant_member<V>(&mut self, member_name: String, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        Err(AsonError::MessageWithLocation(
-            "Unexpected value.".to_owned(),
-            self.last_range.get_position_by_range_start(),
-        ))
+        if self.expect_token(0, &Token::LeftParen)? {
+            // variant with single value or multiple values
+            let v = visitor.visit_enum(VariantAccessor::new(self, &member_name))?;
+            Ok(v)
+        } else if self.expect_token(0, &Token::LeftBrace)? {
+            // variant with struct value
+            let v = visitor.visit_enum(VariantAccessor::new(self, &member_name))?;
+            Ok(v)
+        } else {
+            // variant without value
+            visitor.visit_enum(member_name.into_deserializer())
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'_, 'de, '_> {
+    type Error = AsonError;
+
+    // ASON is a text format, so the types which have a compact form (e.g. `IpAddr`)
+    // are read in their readable form.
+    fn is_human_readable(&self) -> bool {
+        true
     }
 
-    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
+        // The type of value is determined by the document, it is used by
+        // self-describing targets, e.g. the remaining entries of an object
+        // which are collected by a `#[serde(flatten)]` field.
+        //
+        // Since serde has no date type, a date `d"..."` is surfaced as a
+        // single-entry map `{"$ason::DateTime": "<RFC 3339 string>"}`, which
+        // can be distinguished from an ordinary string, e.g. it becomes
+        // `AsonNode::DateTime`.
+        if matches!(self.peek_token(0)?, Some(Token::Variant(type_name, _)) if type_name == "Option")
+        {
+            return self.deserialize_option(visitor);
+        }
+
         match self.next_token()? {
+            Some(Token::Number(number)) => match number {
+                NumberToken::I8(v) => visitor.visit_i8(v as i8),
+                NumberToken::U8(v) => visitor.visit_u8(v),
+                NumberToken::I16(v) => visitor.visit_i16(v as i16),
+                NumberToken::U16(v) => visitor.visit_u16(v),
+                NumberToken::I32(v) => visitor.visit_i32(v as i32),
+                NumberToken::U32(v) => visitor.visit_u32(v),
+                NumberToken::I64(v) => visitor.visit_i64(v as i64),
+                NumberToken::U64(v) => visitor.visit_u64(v),
+                NumberToken::I128(v) => visitor.visit_i128(v as i128),
+                NumberToken::U128(v) => visitor.visit_u128(v),
+                NumberToken::F32(v) => visitor.visit_f32(v),
+                NumberToken::F64(v) => visitor.visit_f64(v),
+            },
             Some(Token::Boolean(v)) => visitor.visit_bool(v),
+            Some(Token::Char(c)) => visitor.visit_char(c),
+            Some(Token::String(s, style)) => {
+                let s = self.interpolate_env(s)?;
+                if self.should_trim_string(style) {
+                    visitor.visit_string(s.trim().to_owned())
+                } else {
+                    visitor.visit_string(s)
+                }
+            }
+            Some(Token::Date(d)) => visitor.visit_map(MapDeserializer::new(std::iter::once((
+                DATE_TIME_KEY,
+                d.to_rfc3339(),
+            )))),
+            Some(Token::HexByteData(d)) => visitor.visit_byte_buf(d),
+            Some(Token::Identifier(id)) => {
+                // the key of object
+                visitor.visit_string(id)
+            }
+            Some(Token::Variant(_, member_name)) => self.visit_variant_member(member_name, visitor),
+            Some(Token::LeftBracket) => {
+                // a map if it is the empty map `[:]` or the first element is followed
+                // by a colon (a new-line is allowed before the colon, the same as
+                // the parser), e.g. `["foo": 11]`, otherwise a list.
+                //
+                // the end of a composite element can not be peeked, e.g. the key
+                // `(1, 2)` of `[(1, 2): "a"]`, so it is buffered as a node first.
+                self.consume_new_line_if_exist()?;
+
+                let first_element = if self.is_composite_value()? {
+                    self.increase_node_count()?;
+                    Some(
+                        self.deserialize_with_path(PathSegment::Index(0), PhantomData::<AsonNode>)?,
+                    )
+                } else {
+                    None
+                };
+
+                // the offset of the token following the first element
+                let offset = if first_element.is_some() { 0 } else { 1 };
+                let is_empty_map =
+                    first_element.is_none() && self.expect_token(0, &Token::Colon)?;
+                let is_map = is_empty_map
+                    || self.expect_token(offset, &Token::Colon)?
+                    || (self.expect_token(offset, &Token::NewLine)?
+                        && self.expect_token(offset + 1, &Token::Colon)?);
+
+                let value = if is_map {
+                    if is_empty_map {
+                        self.consume_empty_map_colon_if_exist()?;
+                    }
+                    let mut accessor = MapAccessor::new(self);
+                    accessor.buffered_key = first_element;
+                    visitor.visit_map(accessor)?
+                } else {
+                    let mut accessor = ArrayAccessor::new(self);
+                    accessor.buffered_element = first_element;
+                    visitor.visit_seq(accessor)?
+                };
+                self.consume_right_bracket()?; // consume ']'
+
+                Ok(value)
+            }
+            Some(Token::LeftParen) => {
+                let value = visitor.visit_seq(TupleAccessor::new(self))?;
+                self.consume_new_line_or_comma_if_exist()?;
+                self.consume_right_paren()?; // consume ')'
+
+                Ok(value)
+            }
+            Some(Token::LeftBrace) => {
+                let value = visitor.visit_map(ObjectAccessor::new(self))?;
+                self.consume_right_brace()?; // consume '}'
+
+                Ok(value)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"Boolean\" value.".to_owned(),
+                "Unexpected value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
             )),
             None => Err(AsonError::UnexpectedEndOfDocument(
-                "Expect a \"Boolean\" value.".to_owned(),
+                "Expect a value.".to_owned(),
             )),
         }
     }
 
-    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value>
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
         match self.next_token()? {
-            Some(Token::Number(NumberToken::I8(v))) => visitor.visit_i8(v as i8),
+            Some(Token::Boolean(v)) => visitor.visit_bool(v),
+            Some(Token::Number(number)) if self.options.lenient => match number {
+                NumberToken::I8(0)
+                | NumberToken::U8(0)
+                | NumberToken::I16(0)
+                | NumberToken::U16(0)
+                | NumberToken::I32(0)
+                | NumberToken::U32(0)
+                | NumberToken::I64(0)
+                | NumberToken::U64(0) => visitor.visit_bool(false),
+                NumberToken::I8(1)
+                | NumberToken::U8(1)
+                | NumberToken::I16(1)
+                | NumberToken::U16(1)
+                | NumberToken::I32(1)
+                | NumberToken::U32(1)
+                | NumberToken::I64(1)
+                | NumberToken::U64(1) => visitor.visit_bool(true),
+                _ => Err(AsonError::MessageWithLocation(
+                    "Only the integer 0 and 1 can be converted to \"Boolean\".".to_owned(),
+                    self.last_range,
+                )),
+            },
+            Some(_) => Err(AsonError::MessageWithLocation(
+                "Expect a \"Boolean\" value.".to_owned(),
+                self.last_range.get_position_by_range_start(),
+            )),
+            None => Err(AsonError::UnexpectedEndOfDocument(
+                "Expect a \"Boolean\" value.".to_owned(),
+            )),
+        }
+    }
+
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.next_token()? {
+            Some(Token::Number(NumberToken::I8(v))) => visitor.visit_i8(v as i8),
+            Some(Token::Number(number)) if self.can_coerce_integer(&number) => {
+                visitor.visit_i8(self.coerce_integer(&number, "i8")?)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect an \"i8\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -236,6 +1189,9 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::I16(v))) => visitor.visit_i16(v as i16),
+            Some(Token::Number(number)) if self.can_coerce_integer(&number) => {
+                visitor.visit_i16(self.coerce_integer(&number, "i16")?)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect an \"i16\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -252,6 +1208,9 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::I32(v))) => visitor.visit_i32(v as i32),
+            Some(Token::Number(number)) if self.can_coerce_integer(&number) => {
+                visitor.visit_i32(self.coerce_integer(&number, "i32")?)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect an \"i32\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -268,6 +1227,9 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::I64(v))) => visitor.visit_i64(v as i64),
+            Some(Token::Number(number)) if self.can_coerce_integer(&number) => {
+                visitor.visit_i64(self.coerce_integer(&number, "i64")?)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect an \"i64\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -284,6 +1246,9 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::U8(v))) => visitor.visit_u8(v),
+            Some(Token::Number(number)) if self.can_coerce_integer(&number) => {
+                visitor.visit_u8(self.coerce_integer(&number, "u8")?)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect an \"u8\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -300,6 +1265,9 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::U16(v))) => visitor.visit_u16(v),
+            Some(Token::Number(number)) if self.can_coerce_integer(&number) => {
+                visitor.visit_u16(self.coerce_integer(&number, "u16")?)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect an \"u16\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -316,6 +1284,9 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::U32(v))) => visitor.visit_u32(v),
+            Some(Token::Number(number)) if self.can_coerce_integer(&number) => {
+                visitor.visit_u32(self.coerce_integer(&number, "u32")?)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect an \"u32\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -332,6 +1303,9 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::U64(v))) => visitor.visit_u64(v),
+            Some(Token::Number(number)) if self.can_coerce_integer(&number) => {
+                visitor.visit_u64(self.coerce_integer(&number, "u64")?)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect an \"u64\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -342,12 +1316,53 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         }
     }
 
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.next_token()? {
+            Some(Token::Number(NumberToken::I128(v))) => visitor.visit_i128(v as i128),
+            Some(Token::Number(number)) if self.can_coerce_integer(&number) => {
+                visitor.visit_i128(self.coerce_integer(&number, "i128")?)
+            }
+            Some(_) => Err(AsonError::MessageWithLocation(
+                "Expect an \"i128\" value.".to_owned(),
+                self.last_range.get_position_by_range_start(),
+            )),
+            None => Err(AsonError::UnexpectedEndOfDocument(
+                "Expect an \"i128\" value.".to_owned(),
+            )),
+        }
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.next_token()? {
+            Some(Token::Number(NumberToken::U128(v))) => visitor.visit_u128(v),
+            Some(Token::Number(number)) if self.can_coerce_integer(&number) => {
+                visitor.visit_u128(self.coerce_integer(&number, "u128")?)
+            }
+            Some(_) => Err(AsonError::MessageWithLocation(
+                "Expect an \"u128\" value.".to_owned(),
+                self.last_range.get_position_by_range_start(),
+            )),
+            None => Err(AsonError::UnexpectedEndOfDocument(
+                "Expect an \"u128\" value.".to_owned(),
+            )),
+        }
+    }
+
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::F32(v))) => visitor.visit_f32(v),
+            Some(Token::Number(number)) if self.options.lenient => {
+                visitor.visit_f32(coerce_float(&number) as f32)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect a \"f32\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -364,6 +1379,9 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::F64(v))) => visitor.visit_f64(v),
+            Some(Token::Number(number)) if self.options.lenient => {
+                visitor.visit_f64(coerce_float(&number))
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect a \"f64\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -380,6 +1398,21 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Char(c)) => visitor.visit_char(c),
+            Some(Token::String(s, _)) if self.options.lenient && s.chars().count() == 1 => {
+                visitor.visit_char(s.chars().next().unwrap())
+            }
+            Some(Token::HexByteData(bytes)) if self.options.lenient => {
+                // the bytes must be valid UTF-8 and decode to exactly one char.
+                let mut chars = std::str::from_utf8(&bytes).unwrap_or_default().chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => visitor.visit_char(c),
+                    _ => Err(AsonError::MessageWithLocation(
+                        "Only the UTF-8 bytes of exactly one character can be converted to \"Char\"."
+                            .to_owned(),
+                        self.last_range,
+                    )),
+                }
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect a \"Char\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -395,7 +1428,19 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         V: de::Visitor<'de>,
     {
         match self.next_token()? {
-            Some(Token::String(s)) => visitor.visit_str(&s),
+            Some(Token::String(s, style)) => {
+                let s = self.interpolate_env(s)?;
+                let trim = self.should_trim_string(style);
+                match self.borrow_from_source(&s, style) {
+                    Some(borrowed) if trim => visitor.visit_borrowed_str(borrowed.trim()),
+                    Some(borrowed) => visitor.visit_borrowed_str(borrowed),
+                    None if trim => visitor.visit_str(s.trim()),
+                    None => visitor.visit_str(&s),
+                }
+            }
+            Some(Token::Char(c)) if self.options.lenient => {
+                visitor.visit_str(c.encode_utf8(&mut [0_u8; 4]))
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect a \"String\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -411,7 +1456,15 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         V: de::Visitor<'de>,
     {
         match self.next_token()? {
-            Some(Token::String(s)) => visitor.visit_string(s),
+            Some(Token::String(s, style)) => {
+                let s = self.interpolate_env(s)?;
+                if self.should_trim_string(style) {
+                    visitor.visit_string(s.trim().to_owned())
+                } else {
+                    visitor.visit_string(s)
+                }
+            }
+            Some(Token::Char(c)) if self.options.lenient => visitor.visit_string(c.to_string()),
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect a \"String\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -427,7 +1480,29 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         V: de::Visitor<'de>,
     {
         match self.next_token()? {
-            Some(Token::HexByteData(d)) => visitor.visit_bytes(&d),
+            Some(Token::HexByteData(d)) => {
+                // the hex byte data is decoded from the source text, so it
+                // can not be borrowed, e.g. by `&[u8]` and `&serde_bytes::Bytes`.
+                //
+                // a visitor which asks for bytes but rejects the transient bytes
+                // as an invalid type (the default of `visit_bytes`) accepts
+                // the borrowed bytes only, the other errors are passed through.
+                let invalid_type_error =
+                    <AsonError as de::Error>::invalid_type(de::Unexpected::Bytes(&d), &visitor);
+
+                visitor.visit_bytes(&d).map_err(|e| {
+                    if e == invalid_type_error {
+                        AsonError::MessageWithLocation(
+                            "Can not borrow the \"Bytes\" value, use an owned type such as \
+                            `Vec<u8>` or `serde_bytes::ByteBuf` instead."
+                                .to_owned(),
+                            self.last_range.get_position_by_range_start(),
+                        )
+                    } else {
+                        e
+                    }
+                })
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect a \"Bytes\" value.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -465,6 +1540,7 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
                         visitor.visit_none()
                     } else if member_name == "Some" && self.expect_token(0, &Token::LeftParen)? {
                         self.next_token()?; // consume '('
+                        self.increase_node_count()?;
                         let v = visitor.visit_some(&mut *self);
                         self.consume_right_paren()?;
                         v
@@ -597,11 +1673,22 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
 
         match self.next_token()? {
             Some(Token::LeftBracket) => {
+                self.consume_new_line_if_exist()?;
+                self.consume_empty_map_colon_if_exist()?; // `[:]` is an empty map
+
                 let value = visitor.visit_map(MapAccessor::new(self))?;
                 self.consume_right_bracket()?; // consume ']'
 
                 Ok(value)
             }
+            Some(Token::LeftBrace) => {
+                // an object is accepted as a map with string keys, which is
+                // also the form of a struct that contains `#[serde(flatten)]` fields.
+                let value = visitor.visit_map(ObjectAccessor::new(self))?;
+                self.consume_right_brace()?; // consume '}'
+
+                Ok(value)
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect a \"Map\".".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -645,7 +1732,7 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     fn deserialize_enum<V>(
         self,
         name: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
@@ -655,18 +1742,7 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         match self.next_token()? {
             Some(Token::Variant(type_name, member_name)) => {
                 if type_name == name {
-                    if self.expect_token(0, &Token::LeftParen)? {
-                        // variant with single value or multiple values
-                        let v = visitor.visit_enum(VariantAccessor::new(self, &member_name))?;
-                        Ok(v)
-                    } else if self.expect_token(0, &Token::LeftBrace)? {
-                        // variant with struct value
-                        let v = visitor.visit_enum(VariantAccessor::new(self, &member_name))?;
-                        Ok(v)
-                    } else {
-                        // variant without value
-                        visitor.visit_enum(member_name.into_deserializer())
-                    }
+                    self.visit_variant_member(member_name, visitor)
                 } else {
                     Err(AsonError::MessageWithLocation(
                         format!("Expect the type \"{}\" of variant.", name,),
@@ -674,6 +1750,40 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
                     ))
                 }
             }
+            Some(Token::Identifier(member_name)) if self.options.allow_bare_variant => {
+                // bare member name, e.g. `Red` instead of `Color::Red`
+                if variants.contains(&member_name.as_str()) {
+                    self.visit_variant_member(member_name, visitor)
+                } else {
+                    Err(AsonError::MessageWithLocation(
+                        format!("Expect a member of variant \"{}\".", name),
+                        self.last_range,
+                    ))
+                }
+            }
+            Some(Token::Number(number)) if self.options.allow_variant_index => {
+                // the index of member, e.g. `0` instead of `Color::Red`
+                let Ok(index) = self.coerce_integer::<usize>(&number, "usize") else {
+                    return Err(AsonError::MessageWithLocation(
+                        format!(
+                            "The index of the member of variant \"{}\" must be \
+                            a non-negative integer.",
+                            name
+                        ),
+                        self.last_range,
+                    ));
+                };
+
+                match variants.get(index) {
+                    Some(member_name) => {
+                        self.visit_variant_member((*member_name).to_owned(), visitor)
+                    }
+                    None => Err(AsonError::MessageWithLocation(
+                        format!("The index is out of the members of variant \"{}\".", name),
+                        self.last_range,
+                    )),
+                }
+            }
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect a \"Variant\".".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -689,8 +1799,9 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         V: de::Visitor<'de>,
     {
         // An identifier in Serde is the type that identifies a field of a struct.
+        // the field name which is not a valid identifier is written as a string.
         match self.next_token()? {
-            Some(Token::Identifier(id)) => visitor.visit_string(id),
+            Some(Token::Identifier(id) | Token::String(id, _)) => visitor.visit_string(id),
             Some(_) => Err(AsonError::MessageWithLocation(
                 "Expect an identifier for object.".to_owned(),
                 self.last_range.get_position_by_range_start(),
@@ -701,35 +1812,112 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         }
     }
 
-    fn deserialize_ignored_any<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        unreachable!()
+        // the value of an unknown field is skipped without being converted,
+        // because serde reads any variant as a newtype variant when ignoring it,
+        // which does not fit the tuple-style members, e.g. `Color::Rgb(1, 2, 3)`.
+        self.skip_value()?;
+        visitor.visit_unit()
+    }
+}
+
+// converts the number of any type to floating-point number,
+// it is used in the lenient mode.
+fn coerce_float(number: &NumberToken) -> f64 {
+    match number {
+        NumberToken::I8(v) => *v as i8 as f64,
+        NumberToken::U8(v) => *v as f64,
+        NumberToken::I16(v) => *v as i16 as f64,
+        NumberToken::U16(v) => *v as f64,
+        NumberToken::I32(v) => *v as i32 as f64,
+        NumberToken::U32(v) => *v as f64,
+        NumberToken::I64(v) => *v as i64 as f64,
+        NumberToken::U64(v) => *v as f64,
+        NumberToken::I128(v) => *v as i128 as f64,
+        NumberToken::U128(v) => *v as f64,
+        NumberToken::F32(v) => *v as f64,
+        NumberToken::F64(v) => *v,
+    }
+}
+
+// the segment of the path of the value being deserialized, it is
+// formatted only when an error occurs, see `prepend_path_to_error`.
+enum PathSegment {
+    // the element of list and tuple, and the entry of map with
+    // composite key, e.g. `[3]`.
+    Index(usize),
+    // the value of object, e.g. `name`.
+    Field(String),
+    // the value of map, e.g. `["foo"]`, `['a']` and `[11]`.
+    String(String),
+    Char(char),
+    Number(NumberToken),
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+            PathSegment::Field(name) => write!(f, "{}", name),
+            PathSegment::String(key) => write!(f, "[{:?}]", key),
+            PathSegment::Char(key) => write!(f, "[{:?}]", key),
+            PathSegment::Number(key) => match key {
+                NumberToken::I8(v) => write!(f, "[{}]", *v as i8),
+                NumberToken::U8(v) => write!(f, "[{}]", v),
+                NumberToken::I16(v) => write!(f, "[{}]", *v as i16),
+                NumberToken::U16(v) => write!(f, "[{}]", v),
+                NumberToken::I32(v) => write!(f, "[{}]", *v as i32),
+                NumberToken::U32(v) => write!(f, "[{}]", v),
+                NumberToken::I64(v) => write!(f, "[{}]", *v as i64),
+                NumberToken::U64(v) => write!(f, "[{}]", v),
+                NumberToken::I128(v) => write!(f, "[{}]", *v as i128),
+                NumberToken::U128(v) => write!(f, "[{}]", v),
+                NumberToken::F32(v) => write!(f, "[{}]", v),
+                NumberToken::F64(v) => write!(f, "[{}]", v),
+            },
+        }
     }
 }
 
-struct ArrayAccessor<'a, 'de: 'a> {
-    de: &'a mut Deserializer<'de>,
+struct ArrayAccessor<'a, 'b, 'de, 'e> {
+    de: &'a mut Deserializer<'b, 'de, 'e>,
     is_first_element: bool,
+    element_index: usize,
+
+    // the first element which has been consumed (and counted), see `deserialize_any`.
+    buffered_element: Option<AsonNode>,
 }
 
-impl<'a, 'de> ArrayAccessor<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>) -> Self {
+impl<'a, 'b, 'de, 'e> ArrayAccessor<'a, 'b, 'de, 'e> {
+    fn new(de: &'a mut Deserializer<'b, 'de, 'e>) -> Self {
         Self {
             de,
             is_first_element: true,
+            element_index: 0,
+            buffered_element: None,
         }
     }
 }
 
-impl<'de> SeqAccess<'de> for ArrayAccessor<'_, 'de> {
+impl<'de> SeqAccess<'de> for ArrayAccessor<'_, '_, 'de, '_> {
     type Error = AsonError;
 
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
     where
         T: de::DeserializeSeed<'de>,
     {
+        if let Some(node) = self.buffered_element.take() {
+            self.is_first_element = false;
+            self.element_index += 1;
+            return self
+                .de
+                .deserialize_node_with_path(PathSegment::Index(0), &node, seed)
+                .map(Some);
+        }
+
         let exists_separator = if self.is_first_element {
             self.de.consume_new_line_if_exist()?
         } else {
@@ -758,26 +1946,31 @@ impl<'de> SeqAccess<'de> for ArrayAccessor<'_, 'de> {
         }
 
         self.is_first_element = false;
+        self.de.increase_node_count()?;
 
-        seed.deserialize(&mut *self.de).map(Some)
+        let segment = PathSegment::Index(self.element_index);
+        self.element_index += 1;
+        self.de.deserialize_with_path(segment, seed).map(Some)
     }
 }
 
-struct TupleAccessor<'a, 'de: 'a> {
-    de: &'a mut Deserializer<'de>,
+struct TupleAccessor<'a, 'b, 'de, 'e> {
+    de: &'a mut Deserializer<'b, 'de, 'e>,
     is_first_element: bool,
+    element_index: usize,
 }
 
-impl<'a, 'de> TupleAccessor<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>) -> Self {
+impl<'a, 'b, 'de, 'e> TupleAccessor<'a, 'b, 'de, 'e> {
+    fn new(de: &'a mut Deserializer<'b, 'de, 'e>) -> Self {
         Self {
             de,
             is_first_element: true,
+            element_index: 0,
         }
     }
 }
 
-impl<'de> SeqAccess<'de> for TupleAccessor<'_, 'de> {
+impl<'de> SeqAccess<'de> for TupleAccessor<'_, '_, 'de, '_> {
     type Error = AsonError;
 
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
@@ -790,9 +1983,12 @@ impl<'de> SeqAccess<'de> for TupleAccessor<'_, 'de> {
             self.de.consume_new_line_or_comma_if_exist()?
         };
 
-        // the deserializer knows the number of members of the
-        // target tuple, so it doesn't need to check the
-        // ending marker ')'.
+        // the deserializer usually knows the number of members of the
+        // target tuple, the ending marker ')' is checked for the
+        // self-describing targets (see `deserialize_any`).
+        if self.de.expect_token(0, &Token::RightParen)? {
+            return Ok(None);
+        }
 
         if self.de.peek_token(0)?.is_none() {
             return Err(AsonError::UnexpectedEndOfDocument(
@@ -811,32 +2007,52 @@ impl<'de> SeqAccess<'de> for TupleAccessor<'_, 'de> {
         }
 
         self.is_first_element = false;
+        self.de.increase_node_count()?;
 
-        seed.deserialize(&mut *self.de).map(Some)
+        let segment = PathSegment::Index(self.element_index);
+        self.element_index += 1;
+        self.de.deserialize_with_path(segment, seed).map(Some)
     }
 }
 
-struct MapAccessor<'a, 'de: 'a> {
-    de: &'a mut Deserializer<'de>,
+struct MapAccessor<'a, 'b, 'de, 'e> {
+    de: &'a mut Deserializer<'b, 'de, 'e>,
     is_first_element: bool,
+    entry_count: usize,
+
+    // the path segment of the value of the current entry.
+    value_path_segment: Option<PathSegment>,
+
+    // the first key which has been consumed (and counted), see `deserialize_any`.
+    buffered_key: Option<AsonNode>,
 }
 
-impl<'a, 'de> MapAccessor<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>) -> Self {
+impl<'a, 'b, 'de, 'e> MapAccessor<'a, 'b, 'de, 'e> {
+    fn new(de: &'a mut Deserializer<'b, 'de, 'e>) -> Self {
         Self {
             de,
             is_first_element: true,
+            entry_count: 0,
+            value_path_segment: None,
+            buffered_key: None,
         }
     }
 }
 
-impl<'de> MapAccess<'de> for MapAccessor<'_, 'de> {
+impl<'de> MapAccess<'de> for MapAccessor<'_, '_, 'de, '_> {
     type Error = AsonError;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
     where
         K: de::DeserializeSeed<'de>,
     {
+        if let Some(node) = self.buffered_key.take() {
+            self.is_first_element = false;
+            self.entry_count += 1;
+            self.value_path_segment = Some(PathSegment::Index(0));
+            return seed.deserialize(NodeDeserializer::new(&node)).map(Some);
+        }
+
         let exists_separator = if self.is_first_element {
             self.de.consume_new_line_if_exist()?
         } else {
@@ -864,9 +2080,16 @@ impl<'de> MapAccess<'de> for MapAccessor<'_, 'de> {
         }
 
         self.is_first_element = false;
+        self.entry_count += 1;
+        self.de.check_map_entry(self.entry_count)?;
+        self.de.increase_node_count()?;
+        self.value_path_segment = Some(self.de.key_path_segment(self.entry_count - 1)?);
 
         // Deserialize a field key.
-        seed.deserialize(&mut *self.de).map(Some)
+        self.de.is_map_key = true;
+        let key = seed.deserialize(&mut *self.de);
+        self.de.is_map_key = false;
+        key.map(Some)
 
         // the function 'deserialize_identifier' is called here, and then
         // the key name will be obtained.
@@ -880,27 +2103,37 @@ impl<'de> MapAccess<'de> for MapAccessor<'_, 'de> {
 
         self.de.consume_colon()?;
         self.de.consume_new_line_if_exist()?;
+        self.de.increase_node_count()?;
 
         // Deserialize a field value.
-        seed.deserialize(&mut *self.de)
+        match self.value_path_segment.take() {
+            Some(segment) => self.de.deserialize_with_path(segment, seed),
+            None => seed.deserialize(&mut *self.de),
+        }
     }
 }
 
-struct ObjectAccessor<'a, 'de: 'a> {
-    de: &'a mut Deserializer<'de>,
+struct ObjectAccessor<'a, 'b, 'de, 'e> {
+    de: &'a mut Deserializer<'b, 'de, 'e>,
     is_first_element: bool,
+    entry_count: usize,
+
+    // the path segment of the value of the current entry.
+    value_path_segment: Option<PathSegment>,
 }
 
-impl<'a, 'de> ObjectAccessor<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>) -> Self {
+impl<'a, 'b, 'de, 'e> ObjectAccessor<'a, 'b, 'de, 'e> {
+    fn new(de: &'a mut Deserializer<'b, 'de, 'e>) -> Self {
         Self {
             de,
             is_first_element: true,
+            entry_count: 0,
+            value_path_segment: None,
         }
     }
 }
 
-impl<'de> MapAccess<'de> for ObjectAccessor<'_, 'de> {
+impl<'de> MapAccess<'de> for ObjectAccessor<'_, '_, 'de, '_> {
     type Error = AsonError;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
@@ -935,13 +2168,38 @@ impl<'de> MapAccess<'de> for ObjectAccessor<'_, 'de> {
             ));
         }
 
+        if self.de.options.require_identifier_keys
+            && !matches!(self.de.peek_token(0)?, Some(Token::Identifier(_)))
+        {
+            return Err(AsonError::MessageWithLocation(
+                "Expect an identifier for the key of object.".to_owned(),
+                *self.de.peek_range(0)?.unwrap(),
+            ));
+        }
+
         self.is_first_element = false;
+        self.entry_count += 1;
+        self.de.check_map_entry(self.entry_count)?;
+        self.value_path_segment = Some(self.de.key_path_segment(self.entry_count - 1)?);
 
         // Deserialize a field key.
-        seed.deserialize(&mut *self.de).map(Some)
-
-        // the function 'deserialize_identifier' is called here, and then
-        // the key name will be obtained.
+        match self.de.peek_token(0)? {
+            Some(Token::Identifier(key)) => {
+                // the identifier key is deserialized as a string, so that it
+                // can be the key of map (e.g. `HashMap<String, T>`) as well as
+                // the name of field.
+                let key = key.to_owned();
+                self.de.next_token()?;
+                seed.deserialize(IntoDeserializer::<AsonError>::into_deserializer(key))
+                    .map(Some)
+            }
+            _ => {
+                self.de.is_map_key = true;
+                let key = seed.deserialize(&mut *self.de);
+                self.de.is_map_key = false;
+                key.map(Some)
+            }
+        }
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
@@ -951,19 +2209,23 @@ impl<'de> MapAccess<'de> for ObjectAccessor<'_, 'de> {
         self.de.consume_new_line_if_exist()?;
         self.de.consume_colon()?;
         self.de.consume_new_line_if_exist()?;
+        self.de.increase_node_count()?;
 
         // Deserialize a field value.
-        seed.deserialize(&mut *self.de)
+        match self.value_path_segment.take() {
+            Some(segment) => self.de.deserialize_with_path(segment, seed),
+            None => seed.deserialize(&mut *self.de),
+        }
     }
 }
 
-struct VariantAccessor<'a, 'de: 'a> {
-    de: &'a mut Deserializer<'de>,
+struct VariantAccessor<'a, 'b, 'de, 'e> {
+    de: &'a mut Deserializer<'b, 'de, 'e>,
     variant_member_name: &'a str,
 }
 
-impl<'a, 'de> VariantAccessor<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>, variant_member_name: &'a str) -> Self {
+impl<'a, 'b, 'de, 'e> VariantAccessor<'a, 'b, 'de, 'e> {
+    fn new(de: &'a mut Deserializer<'b, 'de, 'e>, variant_member_name: &'a str) -> Self {
         Self {
             de,
             variant_member_name,
@@ -976,7 +2238,7 @@ impl<'a, 'de> VariantAccessor<'a, 'de> {
 //
 // Note that all enum deserialization methods in Serde refer exclusively to the
 // "externally tagged" enum representation.
-impl<'de> EnumAccess<'de> for VariantAccessor<'_, 'de> {
+impl<'de> EnumAccess<'de> for VariantAccessor<'_, '_, 'de, '_> {
     type Error = AsonError;
     type Variant = Self;
 
@@ -984,20 +2246,25 @@ impl<'de> EnumAccess<'de> for VariantAccessor<'_, 'de> {
     where
         V: de::DeserializeSeed<'de>,
     {
-        let value = seed.deserialize(self.variant_member_name.into_deserializer())?;
+        let deserializer: StrDeserializer<AsonError> = self.variant_member_name.into_deserializer();
+        let value = seed.deserialize(deserializer)?;
         Ok((value, self))
     }
 }
 
 // `VariantAccess` is provided to the `Visitor` to give it the ability to see
 // the content of the single variant that it decided to deserialize.
-impl<'de> VariantAccess<'de> for VariantAccessor<'_, 'de> {
+impl<'de> VariantAccess<'de> for VariantAccessor<'_, '_, 'de, '_> {
     type Error = AsonError;
 
     // If the `Visitor` expected this variant to be a unit variant, the input
-    // should have been the plain string case handled in `deserialize_enum`.
+    // should have been the plain string case handled in `deserialize_enum`,
+    // i.e. the member is followed by a value unexpectedly, e.g. `Color::Red(1)`.
     fn unit_variant(self) -> Result<()> {
-        unreachable!()
+        Err(AsonError::Message(format!(
+            "The member \"{}\" of variant does not take a value.",
+            self.variant_member_name
+        )))
     }
 
     // Newtype variants are represented in ASON as `(value)` so
@@ -1008,6 +2275,7 @@ impl<'de> VariantAccess<'de> for VariantAccessor<'_, 'de> {
     {
         self.de.next_token()?; // consume '('
         self.de.consume_new_line_if_exist()?;
+        self.de.increase_node_count()?;
 
         let v = seed.deserialize(&mut *self.de);
         self.de.consume_new_line_if_exist()?;
@@ -1033,12 +2301,24 @@ impl<'de> VariantAccess<'de> for VariantAccessor<'_, 'de> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
-    use crate::{location::Location, serde::de::from_str, AsonError};
+    use std::{borrow::Cow, collections::HashMap};
+
+    use crate::{
+        ast::{AsonNode, KeyValuePair, Number},
+        location::Location,
+        parse_from_str, parse_from_str_with_options, parse_from_tokens,
+        parser::{DocumentSeparator, ParseOptions},
+        serde::de::{
+            from_reader_with_options, from_str, from_str_all, from_str_all_with_options,
+            from_str_with_env, from_str_with_options, from_tokens, from_tokens_with_options,
+            DeserializeOptions,
+        },
+        token::FloatType,
+        tokenize_from_str, AsonError,
+    };
 
     use pretty_assertions::assert_eq;
-    use serde::Deserialize;
+    use serde::{de, Deserialize};
     use serde_bytes::ByteBuf;
 
     #[test]
@@ -1171,6 +2451,68 @@ mod tests {
             from_str::<ByteBuf>(r#"h"61 62 63""#).unwrap(),
             ByteBuf::from(b"abc")
         );
+
+        // err: borrowed bytes
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Packet<'a> {
+            #[serde(borrow)]
+            data: &'a serde_bytes::Bytes,
+        }
+
+        assert_eq!(
+            from_str::<Packet>(r#"{data: h"61 62"}"#).unwrap_err(),
+            AsonError::MessageWithLocation(
+                "data: Can not borrow the \"Bytes\" value, use an owned type such as \
+                `Vec<u8>` or `serde_bytes::ByteBuf` instead."
+                    .to_owned(),
+                Location::new_position(7, 0, 7)
+            )
+        );
+
+        assert!(matches!(
+            from_str::<&[u8]>(r#"h"61 62""#),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+
+        // the other errors of the visitor are passed through
+        #[derive(Debug)]
+        struct Digest;
+
+        impl<'de> Deserialize<'de> for Digest {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct DigestVisitor;
+
+                impl de::Visitor<'_> for DigestVisitor {
+                    type Value = Digest;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        formatter.write_str("4 bytes")
+                    }
+
+                    fn visit_bytes<E>(self, v: &[u8]) -> Result<Digest, E>
+                    where
+                        E: de::Error,
+                    {
+                        match v.len() {
+                            4 => Ok(Digest),
+                            n => Err(E::invalid_length(n, &self)),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_bytes(DigestVisitor)
+            }
+        }
+
+        assert!(from_str::<Digest>(r#"h"01 02 03 04""#).is_ok());
+        assert_eq!(
+            from_str::<Digest>(r#"h"01 02""#).unwrap_err(),
+            AsonError::Message("invalid length 2, expected 4 bytes".to_owned())
+        );
     }
 
     #[test]
@@ -1182,6 +2524,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_empty_document() {
+        for s in ["", "  \n  ", "// comment"] {
+            assert_eq!(
+                from_str::<i32>(s),
+                Err(AsonError::Message("The document is empty.".to_owned()))
+            );
+        }
+
+        // err: incomplete
+        assert!(from_str::<Vec<i32>>("[1, 2").unwrap_err().is_incomplete());
+    }
+
     #[test]
     fn test_list() {
         assert_eq!(
@@ -1394,6 +2749,108 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_tuple_with_mixed_elements() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum Color {
+            Red,
+            Green,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object {
+            id: i32,
+        }
+
+        type Record = (i32, Vec<i32>, Color, Object);
+
+        let expect: Record = (11, vec![1, 2], Color::Red, Object { id: 1 });
+
+        assert_eq!(
+            from_str::<Record>(r#"(11, [1,2], Color::Red, {id: 1})"#).unwrap(),
+            expect
+        );
+
+        assert_eq!(
+            from_str::<Record>(r#"(11, [1,2,], Color::Red, {id: 1,},)"#).unwrap(),
+            expect
+        );
+
+        assert_eq!(
+            from_str::<Record>(
+                r#"(
+    11
+    [
+        1
+        2
+    ]
+    Color::Red
+    {
+        id: 1
+    }
+)"#
+            )
+            .unwrap(),
+            expect
+        );
+
+        // the last element is a list
+        assert_eq!(
+            from_str::<(i32, String, Vec<i32>)>(r#"(11, "foo", [13, 17])"#).unwrap(),
+            (11, "foo".to_owned(), vec![13, 17])
+        );
+
+        assert_eq!(
+            from_str::<(i32, String, Vec<i32>)>(r#"(11, "foo", [])"#).unwrap(),
+            (11, "foo".to_owned(), vec![])
+        );
+
+        // a list of tuples with collection elements
+        assert_eq!(
+            from_str::<Vec<(Color, Vec<i32>)>>(r#"[(Color::Red, [1]), (Color::Green, [2, 3])]"#)
+                .unwrap(),
+            vec![(Color::Red, vec![1]), (Color::Green, vec![2, 3])]
+        );
+
+        // err: missing a separator between the list and the variant
+        assert!(matches!(
+            from_str::<Record>(r#"(11, [1,2] Color::Red, {id: 1})"#),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+    }
+
+    #[test]
+    fn test_object_with_unknown_fields() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object {
+            a: i32,
+        }
+
+        assert_eq!(from_str::<Object>("{a: 1, b: 2}").unwrap(), Object { a: 1 });
+
+        // the values of unknown fields are skipped whatever their types
+        assert_eq!(
+            from_str::<Object>(
+                r#"{
+    b: [(1, "foo"), (2, "bar")]
+    a: 11
+    c: {d: Option::Some(Color::Rgb(1, 2, 3)), e: [:]}
+}"#
+            )
+            .unwrap(),
+            Object { a: 11 }
+        );
+
+        // fields are denied when the struct is marked with `deny_unknown_fields`
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(deny_unknown_fields)]
+        struct Strict {
+            a: i32,
+        }
+
+        assert!(from_str::<Strict>("{a: 1, b: 2}").is_err());
+    }
+
     #[test]
     fn test_object() {
         #[derive(Deserialize, Debug, PartialEq)]
@@ -1545,6 +3002,66 @@ mod tests {
         assert_eq!(m1.get(&223).unwrap(), &Option::Some("hello".to_owned()));
         assert_eq!(m1.get(&227).unwrap(), &Option::None);
         assert_eq!(m1.get(&229).unwrap(), &Option::Some("world".to_owned()));
+
+        // empty map
+        assert!(from_str::<HashMap<i32, String>>(r#"[]"#)
+            .unwrap()
+            .is_empty());
+        assert!(from_str::<HashMap<i32, String>>(r#"[:]"#)
+            .unwrap()
+            .is_empty());
+        assert!(from_str::<HashMap<i32, String>>("[\n:\n]")
+            .unwrap()
+            .is_empty());
+        assert!(from_str::<HashMap<i32, String>>(r#"[: 1]"#).is_err());
+    }
+
+    #[test]
+    fn test_map_preserves_key_order() {
+        use indexmap::IndexMap;
+
+        let s0 = r#"
+        [
+            "foo": 11
+            "bar": 13
+            "baz": 17
+        ]
+        "#;
+
+        let m0: IndexMap<String, i32> = from_str(s0).unwrap();
+        assert_eq!(
+            m0.iter()
+                .map(|(k, v)| (k.as_str(), *v))
+                .collect::<Vec<(&str, i32)>>(),
+            vec![("foo", 11), ("bar", 13), ("baz", 17)]
+        );
+
+        // the order is preserved when serializing it back
+        assert_eq!(
+            crate::to_string(&m0).unwrap(),
+            r#"[
+    "foo": 11
+    "bar": 13
+    "baz": 17
+]"#
+        );
+
+        // object
+        let s1 = r#"
+        {
+            foo: 11
+            bar: 13
+            baz: 17
+        }
+        "#;
+
+        let m1: IndexMap<String, i32> = from_str(s1).unwrap();
+        assert_eq!(
+            m1.iter()
+                .map(|(k, v)| (k.as_str(), *v))
+                .collect::<Vec<(&str, i32)>>(),
+            vec![("foo", 11), ("bar", 13), ("baz", 17)]
+        );
     }
 
     #[test]
@@ -1559,6 +3076,12 @@ mod tests {
         assert_eq!(from_str::<Color>(r#"Color::Red"#).unwrap(), Color::Red);
         assert_eq!(from_str::<Color>(r#"Color::Green"#).unwrap(), Color::Green);
         assert_eq!(from_str::<Color>(r#"Color::Blue"#).unwrap(), Color::Blue);
+
+        // the unit member followed by a value
+        assert!(matches!(
+            from_str::<Color>(r#"Color::Red(11)"#),
+            Err(AsonError::Message(_))
+        ));
     }
 
     #[test]
@@ -1933,4 +3456,1434 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_from_tokens() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object {
+            id: i32,
+            orders: Vec<i32>,
+        }
+
+        let tokens = tokenize_from_str(
+            r#"{
+    id: 123
+    orders: [11, 13]
+}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            from_tokens::<Object>(&tokens).unwrap(),
+            Object {
+                id: 123,
+                orders: vec![11, 13]
+            }
+        );
+
+        // deserialize the same tokens again
+        assert_eq!(
+            from_tokens::<Object>(&tokens).unwrap(),
+            Object {
+                id: 123,
+                orders: vec![11, 13]
+            }
+        );
+
+        assert_eq!(
+            parse_from_tokens(&tokens).unwrap(),
+            AsonNode::Object(vec![
+                KeyValuePair::new("id", AsonNode::Number(Number::I32(123))),
+                KeyValuePair::new(
+                    "orders",
+                    AsonNode::List(vec![
+                        AsonNode::Number(Number::I32(11)),
+                        AsonNode::Number(Number::I32(13))
+                    ])
+                )
+            ])
+        );
+    }
+
+    #[test]
+    fn test_lenient_char_as_string() {
+        let options = DeserializeOptions {
+            lenient: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_str_with_options::<String>(r#"'a'"#, &options).unwrap(),
+            "a".to_owned()
+        );
+
+        // multi-byte char
+        assert_eq!(
+            from_str_with_options::<String>(r#"'🍒'"#, &options).unwrap(),
+            "🍒".to_owned()
+        );
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object {
+            name: String,
+        }
+
+        assert_eq!(
+            from_str_with_options::<Object>(r#"{name: 'a'}"#, &options).unwrap(),
+            Object {
+                name: "a".to_owned()
+            }
+        );
+
+        // err: strict mode
+        assert!(matches!(
+            from_str::<String>(r#"'a'"#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_lenient_byte_data_as_char() {
+        let options = DeserializeOptions {
+            lenient: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_str_with_options::<char>(r#"h"f0 9f 8d 92""#, &options).unwrap(),
+            '🍒'
+        );
+        assert_eq!(
+            from_str_with_options::<char>(r#"h"61""#, &options).unwrap(),
+            'a'
+        );
+
+        // err: more than one char
+        assert!(matches!(
+            from_str_with_options::<char>(r#"h"61 62""#, &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 8
+                }
+            ))
+        ));
+
+        // err: empty and invalid UTF-8
+        assert!(from_str_with_options::<char>("h\"\"", &options).is_err());
+        assert!(from_str_with_options::<char>(r#"h"f0 9f 8d""#, &options).is_err());
+
+        // err: strict mode
+        assert!(matches!(
+            from_str::<char>(r#"h"61""#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_lenient_integer_as_bool() {
+        let options = DeserializeOptions {
+            lenient: true,
+            ..Default::default()
+        };
+
+        assert!(from_str_with_options::<bool>("1", &options).unwrap());
+        assert!(!from_str_with_options::<bool>("0", &options).unwrap());
+        assert!(from_str_with_options::<bool>("1_u8", &options).unwrap());
+        assert!(from_str_with_options::<bool>("true", &options).unwrap());
+
+        // err: integer other than 0 and 1
+        assert!(matches!(
+            from_str_with_options::<bool>("2", &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 1
+                }
+            ))
+        ));
+
+        // err: strict mode
+        assert!(matches!(
+            from_str::<bool>("1"),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_lenient_number_coercion() {
+        let options = DeserializeOptions {
+            lenient: true,
+            ..Default::default()
+        };
+
+        assert_eq!(from_str_with_options::<i8>("-17", &options).unwrap(), -17);
+        assert_eq!(from_str_with_options::<u64>("17_u8", &options).unwrap(), 17);
+        assert_eq!(from_str_with_options::<f32>("17", &options).unwrap(), 17.0);
+        assert_eq!(from_str_with_options::<f32>("1.5", &options).unwrap(), 1.5);
+        assert_eq!(
+            from_str_with_options::<f64>("1.5_f32", &options).unwrap(),
+            1.5
+        );
+
+        // err: out of range
+        assert!(matches!(
+            from_str_with_options::<u8>("-1", &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 2
+                }
+            ))
+        ));
+
+        // err: float to integer
+        assert!(matches!(
+            from_str_with_options::<i32>("1.5", &options),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+    }
+
+    #[test]
+    fn test_coerce_float_to_int() {
+        let options = DeserializeOptions {
+            coerce_float_to_int: true,
+            ..Default::default()
+        };
+
+        assert_eq!(from_str_with_options::<i32>("5.0", &options).unwrap(), 5);
+        assert_eq!(from_str_with_options::<i8>("-5.0", &options).unwrap(), -5);
+        assert_eq!(from_str_with_options::<u64>("1e3", &options).unwrap(), 1000);
+        assert_eq!(
+            from_str_with_options::<u16>("7.0_f32", &options).unwrap(),
+            7
+        );
+
+        // err: fractional part
+        assert_eq!(
+            from_str_with_options::<i32>("5.5", &options),
+            Err(AsonError::MessageWithLocation(
+                "The number has a fractional part, can not be converted to \"i32\".".to_owned(),
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 3
+                }
+            ))
+        );
+
+        // err: out of range
+        assert!(matches!(
+            from_str_with_options::<u8>("-1.0", &options),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+
+        // err: integers of other types are still rejected
+        assert!(matches!(
+            from_str_with_options::<i32>("5_u8", &options),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+
+        // the default rejects any float for integer
+        assert!(matches!(
+            from_str::<i32>("5.0"),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+    }
+
+    #[test]
+    fn test_max_input_bytes() {
+        let options = DeserializeOptions {
+            max_input_bytes: Some(16),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_str_with_options::<Vec<i32>>("[11, 13, 17]", &options).unwrap(),
+            vec![11, 13, 17]
+        );
+
+        // err: oversized
+        let s = format!("[{}]", "11, ".repeat(100_000));
+        assert_eq!(
+            from_str_with_options::<Vec<i32>>(&s, &options),
+            Err(AsonError::Message(
+                "The document exceeds the maximum length of 16 bytes.".to_owned()
+            ))
+        );
+
+        // unlimited by default
+        assert_eq!(from_str::<Vec<i32>>(&s).unwrap().len(), 100_000);
+    }
+
+    #[test]
+    fn test_error_path() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Item {
+            id: i32,
+            name: String,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Inner {
+            items: Vec<Item>,
+            tags: HashMap<String, (i32, bool)>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Outer {
+            inner: Inner,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Root {
+            outer: Outer,
+        }
+
+        let s0 = r#"{
+            outer: {
+                inner: {
+                    items: [
+                        {id: 1, name: "foo"}
+                        {id: 2, name: "bar"}
+                        {id: 3, name: "baz"}
+                        {id: 4, name: 5}
+                    ]
+                    tags: [:]
+                }
+            }
+        }"#;
+
+        assert_eq!(
+            from_str::<Root>(s0).unwrap_err(),
+            AsonError::MessageWithLocation(
+                "outer.inner.items[3].name: Expect a \"String\" value.".to_owned(),
+                Location::new_position(250, 7, 38)
+            )
+        );
+
+        let s1 = r#"{
+            outer: {
+                inner: {
+                    items: []
+                    tags: ["a": (1, true), "b": (2, 3)]
+                }
+            }
+        }"#;
+
+        assert!(matches!(
+            from_str::<Root>(s1),
+            Err(AsonError::MessageWithLocation(message, _))
+                if message.starts_with(r#"outer.inner.tags["b"][1]: "#)
+        ));
+
+        // the numeric keys are reported without losing precision
+        assert!(matches!(
+            from_str::<HashMap<u64, i32>>(r#"[9007199254740993_u64: true]"#),
+            Err(AsonError::MessageWithLocation(message, _))
+                if message.starts_with("[9007199254740993]: ")
+        ));
+
+        assert!(matches!(
+            from_str::<HashMap<i32, i32>>(r#"[-11: true]"#),
+            Err(AsonError::MessageWithLocation(message, _))
+                if message.starts_with("[-11]: ")
+        ));
+
+        // err: missing field
+        assert!(matches!(
+            from_str::<Root>(r#"{outer: {inner: {items: [{id: 1}], tags: [:]}}}"#),
+            Err(AsonError::Message(message))
+                if message == "outer.inner.items[0]: missing field `name`"
+        ));
+    }
+
+    #[test]
+    fn test_require_identifier_keys() {
+        let options = DeserializeOptions {
+            require_identifier_keys: true,
+            ..Default::default()
+        };
+
+        // the quoted and numeric keys are accepted by default
+        assert_eq!(
+            from_str::<HashMap<String, i32>>(r#"{"a": 11}"#).unwrap(),
+            HashMap::from([("a".to_owned(), 11)])
+        );
+        assert_eq!(
+            from_str::<HashMap<i32, i32>>(r#"{13: 17}"#).unwrap(),
+            HashMap::from([(13, 17)])
+        );
+
+        assert_eq!(
+            from_str_with_options::<HashMap<String, i32>>(r#"{a: 11, b: 13}"#, &options).unwrap(),
+            HashMap::from([("a".to_owned(), 11), ("b".to_owned(), 13)])
+        );
+
+        // err: quoted key
+        // "{a: 11, "b": 13}"
+        //  0123456789        // index
+        assert_eq!(
+            from_str_with_options::<HashMap<String, i32>>(r#"{a: 11, "b": 13}"#, &options),
+            Err(AsonError::MessageWithLocation(
+                "Expect an identifier for the key of object.".to_owned(),
+                Location {
+                    index: 8,
+                    line: 0,
+                    column: 8,
+                    length: 3
+                }
+            ))
+        );
+
+        // err: numeric key
+        assert!(matches!(
+            from_str_with_options::<HashMap<i32, i32>>(r#"{13: 17}"#, &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 1,
+                    line: 0,
+                    column: 1,
+                    length: 2
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_default_float_type() {
+        let options = DeserializeOptions {
+            default_float_type: FloatType::F32,
+            ..Default::default()
+        };
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Point {
+            x: f32,
+            y: f32,
+            z: f64,
+        }
+
+        assert_eq!(
+            from_str_with_options::<Point>(r#"{x: 1.5, y: -Inf, z: 2.5_f64}"#, &options).unwrap(),
+            Point {
+                x: 1.5,
+                y: f32::NEG_INFINITY,
+                z: 2.5
+            }
+        );
+
+        // err: the suffix-less float is f64 by default
+        assert!(matches!(
+            from_str::<f32>(r#"1.5"#),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+
+        // err: the suffix-less float is not f64 under the option
+        assert!(matches!(
+            from_str_with_options::<f64>(r#"1.5"#, &options),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+    }
+
+    #[test]
+    fn test_describe_options() {
+        assert!(DeserializeOptions::default().describe().is_empty());
+
+        let options = DeserializeOptions {
+            lenient: true,
+            max_total_nodes: Some(100),
+            allow_shebang: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            options.describe(),
+            vec!["lenient", "max_total_nodes", "allow_shebang"]
+        );
+    }
+
+    #[test]
+    fn test_max_total_nodes() {
+        let options = DeserializeOptions {
+            max_total_nodes: Some(100),
+            ..Default::default()
+        };
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object {
+            id: i32,
+            tags: Vec<String>,
+        }
+
+        assert_eq!(
+            from_str_with_options::<Object>(r#"{id: 11, tags: ["foo", "bar"]}"#, &options).unwrap(),
+            Object {
+                id: 11,
+                tags: vec!["foo".to_owned(), "bar".to_owned()]
+            }
+        );
+
+        let s1 = format!("[{}]", "0,".repeat(1_000_000));
+
+        assert!(matches!(
+            from_str_with_options::<Vec<i32>>(&s1, &options),
+            Err(AsonError::Message(m)) if m == "Document exceeds maximum node count."
+        ));
+
+        // the nodes are counted the same as the parser, i.e. the keys of map,
+        // the values of variant and the skipped values are counted.
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Item {
+            id: i32,
+        }
+
+        fn check<T: de::DeserializeOwned>(s: &str, total_nodes: usize) {
+            let parse_options = |max_total_nodes| ParseOptions {
+                max_total_nodes: Some(max_total_nodes),
+                ..Default::default()
+            };
+            let options = |max_total_nodes| DeserializeOptions {
+                max_total_nodes: Some(max_total_nodes),
+                ..Default::default()
+            };
+
+            assert!(parse_from_str_with_options(s, &parse_options(total_nodes)).is_ok());
+            assert!(parse_from_str_with_options(s, &parse_options(total_nodes - 1)).is_err());
+            assert!(from_str_with_options::<T>(s, &options(total_nodes)).is_ok());
+            assert!(from_str_with_options::<T>(s, &options(total_nodes - 1)).is_err());
+        }
+
+        check::<HashMap<i32, Option<i32>>>(r#"[11: Option::Some(13), 17: Option::None]"#, 6);
+        check::<Item>(r#"{id: 11, extra: [13, Option::Some((17, 19))]}"#, 8);
+    }
+
+    #[test]
+    fn test_max_map_entries_and_key_length() {
+        let options = DeserializeOptions {
+            max_map_entries: Some(2),
+            max_key_length: Some(5),
+            ..Default::default()
+        };
+
+        let m0: HashMap<String, i32> =
+            from_str_with_options(r#"["foo": 11, "bar": 13]"#, &options).unwrap();
+        assert_eq!(m0.len(), 2);
+
+        // err: the 3rd entry of map
+        assert!(matches!(
+            from_str_with_options::<HashMap<String, i32>>(
+                r#"["foo": 11, "bar": 13, "baz": 17]"#,
+                &options
+            ),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 23,
+                    line: 0,
+                    column: 23,
+                    length: 0
+                }
+            ))
+        ));
+
+        // err: the key of map is too long
+        assert!(matches!(
+            from_str_with_options::<HashMap<String, i32>>(r#"["foo": 11, "foobar": 13]"#, &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 12,
+                    line: 0,
+                    column: 12,
+                    length: 8
+                }
+            ))
+        ));
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Point {
+            x: i32,
+            y: i32,
+            z: i32,
+        }
+
+        // err: the 3rd entry of object
+        assert!(matches!(
+            from_str_with_options::<Point>(r#"{x: 11, y: 13, z: 17}"#, &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 15,
+                    line: 0,
+                    column: 15,
+                    length: 0
+                }
+            ))
+        ));
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object {
+            id: i32,
+            longname: i32,
+        }
+
+        // err: the key of object is too long
+        assert!(matches!(
+            from_str_with_options::<Object>(r#"{id: 11, longname: 13}"#, &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 9,
+                    line: 0,
+                    column: 9,
+                    length: 8
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_flatten_struct() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Inner {
+            b: i32,
+            c: Option<String>,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Outer {
+            a: i32,
+            #[serde(flatten)]
+            inner: Inner,
+        }
+
+        assert_eq!(
+            from_str::<Outer>("{a: 1, b: 2}").unwrap(),
+            Outer {
+                a: 1,
+                inner: Inner { b: 2, c: None }
+            }
+        );
+
+        let s0 = r#"{
+            b: 22
+            c: Option::Some("foo")
+            a: 11
+        }"#;
+
+        let expected = Outer {
+            a: 11,
+            inner: Inner {
+                b: 22,
+                c: Some("foo".to_owned()),
+            },
+        };
+
+        assert_eq!(from_str::<Outer>(s0).unwrap(), expected);
+        assert_eq!(
+            parse_from_str(s0)
+                .unwrap()
+                .deserialize_into::<Outer>()
+                .unwrap(),
+            expected
+        );
+
+        // the flattened struct and map can be used together,
+        // the map collects the keys which are not claimed by the others.
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Document {
+            a: i32,
+            #[serde(flatten)]
+            inner: Inner,
+            #[serde(flatten)]
+            rest: HashMap<String, i32>,
+        }
+
+        assert_eq!(
+            from_str::<Document>("{a: 1, b: 2, d: 3, e: 4}").unwrap(),
+            Document {
+                a: 1,
+                inner: Inner { b: 2, c: None },
+                rest: HashMap::from([("d".to_owned(), 3), ("e".to_owned(), 4)]),
+            }
+        );
+
+        // the missing field of the flattened struct is reported
+        assert!(from_str::<Outer>("{a: 1}").is_err());
+    }
+
+    #[test]
+    fn test_flatten_map() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object {
+            id: i32,
+            name: String,
+            #[serde(flatten)]
+            rest: HashMap<String, AsonNode>,
+        }
+
+        let s0 = r#"{
+            id: 123
+            name: "foo"
+            score: 456
+        }"#;
+
+        assert_eq!(
+            from_str::<Object>(s0).unwrap(),
+            Object {
+                id: 123,
+                name: "foo".to_owned(),
+                rest: HashMap::from([("score".to_owned(), AsonNode::Number(Number::I32(456)))]),
+            }
+        );
+
+        // values of the flattened map can be of any type
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Config {
+            version: i32,
+            #[serde(flatten)]
+            extra: HashMap<String, Vec<(i32, char)>>,
+        }
+
+        let s1 = r#"{
+            version: 2
+            pairs: [(11, 'a'), (13, 'b')]
+            empty: []
+        }"#;
+
+        assert_eq!(
+            from_str::<Config>(s1).unwrap(),
+            Config {
+                version: 2,
+                extra: HashMap::from([
+                    ("pairs".to_owned(), vec![(11, 'a'), (13, 'b')]),
+                    ("empty".to_owned(), vec![]),
+                ]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_any_into_dynamic_value() {
+        use serde::de::{EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+
+        // a dynamic value type like `serde_json::Value`
+        #[derive(Debug, PartialEq)]
+        enum Value {
+            Null,
+            Bool(bool),
+            Int(i64),
+            UInt(u64),
+            Float(f64),
+            Char(char),
+            String(String),
+            Bytes(Vec<u8>),
+            List(Vec<Value>),
+            Map(Vec<(Value, Value)>),
+            Variant(String, Option<Box<Value>>),
+        }
+
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = Value;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("any value")
+            }
+
+            fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+                Ok(Value::Bool(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+                Ok(Value::Int(v))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+                Ok(Value::UInt(v))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+                Ok(Value::Float(v))
+            }
+
+            fn visit_char<E>(self, v: char) -> Result<Value, E> {
+                Ok(Value::Char(v))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Value, E> {
+                Ok(Value::String(v))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+                Ok(Value::String(v.to_owned()))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Value, E> {
+                Ok(Value::Bytes(v))
+            }
+
+            fn visit_none<E>(self) -> Result<Value, E> {
+                Ok(Value::Null)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                Value::deserialize(deserializer)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut items = vec![];
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(Value::List(items))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut entries = vec![];
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Value::Map(entries))
+            }
+
+            // the unit variants and the variants with a single value
+            fn visit_enum<A>(self, data: A) -> Result<Value, A::Error>
+            where
+                A: EnumAccess<'de>,
+            {
+                let (name, variant) = data.variant::<String>()?;
+                match name.as_str() {
+                    "Circle" => Ok(Value::Variant(
+                        name,
+                        Some(Box::new(variant.newtype_variant()?)),
+                    )),
+                    _ => {
+                        variant.unit_variant()?;
+                        Ok(Value::Variant(name, None))
+                    }
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Value {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_any(ValueVisitor)
+            }
+        }
+
+        let s0 = r#"{
+            name: "foo"
+            enabled: true
+            count: 11_i64
+            big: 18446744073709551615_u64
+            ratio: 0.5
+            initial: 'a'
+            data: h"01 02"
+            point: (1_i64, 2_i64)
+            scores: ["a": 1_i64
+                "b"
+                : 2_i64]
+            empty_map: [:]
+            empty_list: []
+            pairs: [(1_i64, 2_i64): "a"
+                (3_i64, 4_i64)
+                : "b"]
+            nested: [[1_i64], Shape::Circle(1.5)]
+            color: Color::Red
+            shape: Shape::Circle(1.5)
+            nickname: Option::Some("bar")
+            missing: Option::None
+            created: d"2024-06-26T16:38:50+08:00"
+        }"#;
+
+        let key = |name: &str| Value::String(name.to_owned());
+
+        assert_eq!(
+            from_str::<Value>(s0).unwrap(),
+            Value::Map(vec![
+                (key("name"), key("foo")),
+                (key("enabled"), Value::Bool(true)),
+                (key("count"), Value::Int(11)),
+                (key("big"), Value::UInt(u64::MAX)),
+                (key("ratio"), Value::Float(0.5)),
+                (key("initial"), Value::Char('a')),
+                (key("data"), Value::Bytes(vec![1, 2])),
+                (
+                    key("point"),
+                    Value::List(vec![Value::Int(1), Value::Int(2)])
+                ),
+                (
+                    key("scores"),
+                    Value::Map(vec![(key("a"), Value::Int(1)), (key("b"), Value::Int(2))])
+                ),
+                (key("empty_map"), Value::Map(vec![])),
+                (key("empty_list"), Value::List(vec![])),
+                (
+                    key("pairs"),
+                    Value::Map(vec![
+                        (Value::List(vec![Value::Int(1), Value::Int(2)]), key("a")),
+                        (Value::List(vec![Value::Int(3), Value::Int(4)]), key("b"))
+                    ])
+                ),
+                (
+                    key("nested"),
+                    Value::List(vec![
+                        Value::List(vec![Value::Int(1)]),
+                        Value::Variant("Circle".to_owned(), Some(Box::new(Value::Float(1.5))))
+                    ])
+                ),
+                (key("color"), Value::Variant("Red".to_owned(), None)),
+                (
+                    key("shape"),
+                    Value::Variant("Circle".to_owned(), Some(Box::new(Value::Float(1.5))))
+                ),
+                (key("nickname"), key("bar")),
+                (key("missing"), Value::Null),
+                (
+                    key("created"),
+                    Value::Map(vec![(
+                        key("$ason::DateTime"),
+                        key("2024-06-26T16:38:50+08:00")
+                    )])
+                ),
+            ])
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_deserialize_any_into_json_value() {
+        use serde_json::json;
+
+        assert_eq!(
+            from_str::<serde_json::Value>(
+                r#"{
+                    id: 123
+                    name: "foo"
+                    tags: ["a", "b"]
+                    attrs: ["visible": true]
+                    nickname: Option::None
+                    dates: [d"2024-01-01T00:00:00Z"]
+                }"#
+            )
+            .unwrap(),
+            json!({
+                "id": 123,
+                "name": "foo",
+                "tags": ["a", "b"],
+                "attrs": {"visible": true},
+                "nickname": null,
+                "dates": [{"$ason::DateTime": "2024-01-01T00:00:00+00:00"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_trim_strings() {
+        let options = DeserializeOptions {
+            trim_strings: true,
+            ..Default::default()
+        };
+
+        assert_eq!(from_str::<String>(r#"" foo ""#).unwrap(), " foo ");
+        assert_eq!(
+            from_str_with_options::<String>(r#"" foo ""#, &options).unwrap(),
+            "foo"
+        );
+        assert_eq!(
+            from_str_with_options::<String>(r#"r"  foo  ""#, &options).unwrap(),
+            "foo"
+        );
+
+        // the auto-trimmed strings are not affected by default
+        let s0 = r#"
+            """
+                foo
+                  bar  
+            """
+        "#;
+
+        assert_eq!(
+            from_str_with_options::<String>(s0, &options).unwrap(),
+            "foo\n  bar  "
+        );
+        assert_eq!(
+            from_str_with_options::<String>(
+                s0,
+                &DeserializeOptions {
+                    trim_strings: true,
+                    trim_auto_trimmed_strings: true,
+                    ..Default::default()
+                }
+            )
+            .unwrap(),
+            "foo\n  bar"
+        );
+
+        // the auto-trimmed strings are recognized without the source text
+        assert_eq!(
+            from_reader_with_options::<String, _>(s0.as_bytes(), &options).unwrap(),
+            "foo\n  bar  "
+        );
+        assert_eq!(
+            from_tokens_with_options::<String>(&tokenize_from_str(s0).unwrap(), &options).unwrap(),
+            "foo\n  bar  "
+        );
+
+        // the keys of objects and maps are not trimmed
+        assert_eq!(
+            from_str_with_options::<HashMap<String, String>>(r#"[" a ": " b "]"#, &options)
+                .unwrap(),
+            HashMap::from([(" a ".to_owned(), "b".to_owned())])
+        );
+        assert_eq!(
+            from_str_with_options::<HashMap<String, String>>(r#"{" a ": " b "}"#, &options)
+                .unwrap(),
+            HashMap::from([(" a ".to_owned(), "b".to_owned())])
+        );
+
+        // the strings of `deserialize_any` are trimmed as well
+        assert_eq!(
+            from_str_with_options::<AsonNode>(r#"{name: " foo ", " a ": " b "}"#, &options)
+                .unwrap(),
+            AsonNode::Object(vec![
+                KeyValuePair::new("name", AsonNode::String("foo".to_owned())),
+                KeyValuePair::new(" a ", AsonNode::String("b".to_owned())),
+            ])
+        );
+
+        // the borrowed strings are trimmed as well
+        assert_eq!(
+            from_str_with_options::<&str>(r#"" foo ""#, &options).unwrap(),
+            "foo"
+        );
+    }
+
+    #[test]
+    fn test_borrowed_strings() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object<'a> {
+            #[serde(borrow)]
+            name: Cow<'a, str>,
+            #[serde(borrow)]
+            note: Cow<'a, str>,
+            title: &'a str,
+        }
+
+        let s0 = r#"{
+            name: "文字😊"
+            note: "foo\nbar"
+            title: r"hello"
+        }"#;
+
+        let v0 = from_str::<Object>(s0).unwrap();
+        assert_eq!(v0.name, "文字😊");
+        assert_eq!(v0.note, "foo\nbar");
+        assert_eq!(v0.title, "hello");
+
+        // the string without escape chars is borrowed from the document
+        assert!(matches!(v0.name, Cow::Borrowed(_)));
+        assert!(matches!(v0.note, Cow::Owned(_)));
+
+        // err: a string with escape chars can not be borrowed
+        assert!(from_str::<&str>(r#""foo\nbar""#).is_err());
+
+        // err: even if the value happens to be part of the source text
+        assert!(from_str::<&str>(r#""\\""#).is_err());
+        assert!(from_str::<&str>(r#""a\u{61}""#).is_err());
+
+        // the raw strings
+        assert_eq!(from_str::<&str>(r#"r"foo\nbar""#).unwrap(), "foo\\nbar");
+        assert_eq!(
+            from_str::<&str>(r##"r#"say "hi""#"##).unwrap(),
+            r#"say "hi""#
+        );
+    }
+
+    #[test]
+    fn test_from_str_all() {
+        assert_eq!(from_str_all::<i32>("1\n2\n3").unwrap(), vec![1, 2, 3]);
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object {
+            id: i32,
+            name: String,
+        }
+
+        assert_eq!(
+            from_str_all::<Object>(
+                r#"
+                {id: 11, name: "foo"}
+                {
+                    id: 13
+                    name: "bar"
+                }
+                "#
+            )
+            .unwrap(),
+            vec![
+                Object {
+                    id: 11,
+                    name: "foo".to_owned()
+                },
+                Object {
+                    id: 13,
+                    name: "bar".to_owned()
+                }
+            ]
+        );
+
+        // err: mismatched type of the 2nd value
+        assert!(matches!(
+            from_str_all::<i32>("1\n'a'"),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 2,
+                    line: 1,
+                    column: 0,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_from_str_all_with_document_separator() {
+        let options = DeserializeOptions {
+            document_separator: DocumentSeparator::NewLine,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_str_all_with_options::<i32>("1\n2\n\n3", &options).unwrap(),
+            vec![1, 2, 3]
+        );
+
+        // err: missing new-line
+        assert!(matches!(
+            from_str_all_with_options::<i32>("1\n2 3", &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 4,
+                    line: 1,
+                    column: 2,
+                    length: 0
+                }
+            ))
+        ));
+
+        let options = DeserializeOptions {
+            document_separator: DocumentSeparator::Comma,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_str_all_with_options::<Vec<i32>>("[1], [2, 3]\n,\n[],", &options).unwrap(),
+            vec![vec![1], vec![2, 3], vec![]]
+        );
+
+        // err: missing comma
+        assert!(matches!(
+            from_str_all_with_options::<i32>("1, 2\n3", &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 4,
+                    line: 0,
+                    column: 4,
+                    length: 0
+                }
+            ))
+        ));
+
+        let options = DeserializeOptions {
+            document_separator: DocumentSeparator::Custom('|'),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_str_all_with_options::<Vec<i32>>("[1] | [2, 3]\n|\n[]|", &options).unwrap(),
+            vec![vec![1], vec![2, 3], vec![]]
+        );
+
+        // err: missing separator
+        assert_eq!(
+            from_str_all_with_options::<i32>("1 | 2 3", &options),
+            Err(AsonError::MessageWithLocation(
+                "Expect a '|' between documents.".to_owned(),
+                Location {
+                    index: 6,
+                    line: 0,
+                    column: 6,
+                    length: 0
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_from_str_with_env() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Config {
+            path: String,
+            greeting: String,
+            literal: String,
+        }
+
+        let env = HashMap::from([
+            ("HOME".to_owned(), "/home/yang".to_owned()),
+            ("USER".to_owned(), "yang".to_owned()),
+        ]);
+        let options = DeserializeOptions::default();
+
+        assert_eq!(
+            from_str_with_env::<Config>(
+                r#"{
+                    path: "${HOME}/.config"
+                    greeting: "Hello, ${USER}! It costs $5."
+                    literal: "$${HOME} is ${HOME}"
+                }"#,
+                &env,
+                &options
+            )
+            .unwrap(),
+            Config {
+                path: "/home/yang/.config".to_owned(),
+                greeting: "Hello, yang! It costs $5.".to_owned(),
+                literal: "${HOME} is /home/yang".to_owned(),
+            }
+        );
+
+        // the borrowed strings are interpolated too
+        assert_eq!(
+            from_str_with_env::<Cow<str>>(r#""${USER}""#, &env, &options).unwrap(),
+            "yang"
+        );
+
+        // the keys of objects and maps are not interpolated
+        assert_eq!(
+            from_str_with_env::<HashMap<String, String>>(
+                r#"{"${USER}": "${USER}"}"#,
+                &env,
+                &options
+            )
+            .unwrap(),
+            HashMap::from([("${USER}".to_owned(), "yang".to_owned())])
+        );
+        assert_eq!(
+            from_str_with_env::<HashMap<String, String>>(
+                r#"["${USER}": "${USER}"]"#,
+                &env,
+                &options
+            )
+            .unwrap(),
+            HashMap::from([("${USER}".to_owned(), "yang".to_owned())])
+        );
+
+        // strings are not interpolated without env
+        assert_eq!(
+            from_str::<String>(r#""${HOME}""#).unwrap(),
+            "${HOME}".to_owned()
+        );
+
+        // err: undefined variable
+        assert!(matches!(
+            from_str_with_env::<Vec<String>>(r#"["${HOME}", "${EDITOR}"]"#, &env, &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 12,
+                    line: 0,
+                    column: 12,
+                    length: 11
+                }
+            ))
+        ));
+
+        // err: missing the closing brace
+        assert!(from_str_with_env::<String>(r#""${HOME""#, &env, &options).is_err());
+
+        // undefined variables are replaced with empty string
+        let options = DeserializeOptions {
+            allow_undefined_env: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_str_with_env::<String>(r#""${EDITOR}-${USER}""#, &env, &options).unwrap(),
+            "-yang".to_owned()
+        );
+    }
+
+    #[test]
+    fn test_bare_variant() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum Color {
+            Red,
+            Green,
+            Rgb(u8, u8, u8),
+        }
+
+        let options = DeserializeOptions {
+            allow_bare_variant: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_str_with_options::<Color>(r#"Red"#, &options).unwrap(),
+            Color::Red
+        );
+
+        assert_eq!(
+            from_str_with_options::<Vec<Color>>(
+                r#"[Green, Color::Red, Rgb(1_u8, 2_u8, 3_u8)]"#,
+                &options
+            )
+            .unwrap(),
+            vec![Color::Green, Color::Red, Color::Rgb(1, 2, 3)]
+        );
+
+        // err: not a member
+        assert!(matches!(
+            from_str_with_options::<Color>(r#"Blue"#, &options),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 4
+                }
+            ))
+        ));
+
+        // the full form is required by default
+        assert_eq!(from_str::<Color>(r#"Color::Red"#).unwrap(), Color::Red);
+        assert!(matches!(
+            from_str::<Color>(r#"Red"#),
+            Err(AsonError::MessageWithLocation(
+                _,
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 0
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_variant_index() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum Color {
+            Red,
+            Green,
+            Blue,
+        }
+
+        let options = DeserializeOptions {
+            allow_variant_index: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_str_with_options::<Color>(r#"0"#, &options).unwrap(),
+            Color::Red
+        );
+        assert_eq!(
+            from_str_with_options::<Color>(r#"2"#, &options).unwrap(),
+            Color::Blue
+        );
+        assert_eq!(
+            from_str_with_options::<Vec<Color>>(r#"[1_u8, Color::Red]"#, &options).unwrap(),
+            vec![Color::Green, Color::Red]
+        );
+
+        // err: out of range
+        assert_eq!(
+            from_str_with_options::<Color>(r#"3"#, &options),
+            Err(AsonError::MessageWithLocation(
+                "The index is out of the members of variant \"Color\".".to_owned(),
+                Location {
+                    index: 0,
+                    line: 0,
+                    column: 0,
+                    length: 1
+                }
+            ))
+        );
+
+        // err: not a non-negative integer
+        for s in [r#"-1"#, r#"1.5"#] {
+            assert!(matches!(
+                from_str_with_options::<Color>(s, &options),
+                Err(AsonError::MessageWithLocation(message, _))
+                    if message == "The index of the member of variant \"Color\" must be \
+                    a non-negative integer."
+            ));
+        }
+
+        // the full form is required by default
+        assert!(matches!(
+            from_str::<Color>(r#"0"#),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+    }
 }
@@ -0,0 +1,235 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Wrappers that specify the notation of a number field when serializing,
+//! e.g. the `Hex(255_u8)` is serialized as `0xff_u8` instead of `255_u8`.
+//!
+//! ```rust
+//! use ason::{from_str, to_string, Hex};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Pixel {
+//!     color: Hex<u32>,
+//! }
+//!
+//! let s = to_string(&Pixel { color: Hex(0xff8000) }).unwrap();
+//! assert_eq!(s, "{\n    color: 0xff8000_u32\n}");
+//! assert_eq!(from_str::<Pixel>(&s).unwrap().color, Hex(0xff8000));
+//! ```
+//!
+//! The wrappers are transparent when deserializing, and the notation only
+//! takes effect on the ASON serializer, other serializers (e.g. JSON) write
+//! the inner value as usual.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// the wrappers are serialized as new-type structs with these names,
+// the ASON serializer recognizes the names and formats the inner number.
+const HEX_STRUCT_NAME: &str = "$ason::format::Hex";
+const BINARY_STRUCT_NAME: &str = "$ason::format::Binary";
+const OCTAL_STRUCT_NAME: &str = "$ason::format::Octal";
+const SCIENTIFIC_STRUCT_NAME: &str = "$ason::format::Scientific";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum NumberNotation {
+    Hex,
+    Binary,
+    Octal,
+    Scientific,
+}
+
+impl NumberNotation {
+    pub(crate) fn from_struct_name(name: &str) -> Option<Self> {
+        match name {
+            HEX_STRUCT_NAME => Some(Self::Hex),
+            BINARY_STRUCT_NAME => Some(Self::Binary),
+            OCTAL_STRUCT_NAME => Some(Self::Octal),
+            SCIENTIFIC_STRUCT_NAME => Some(Self::Scientific),
+            _ => None,
+        }
+    }
+
+    // formats the integer in this notation, e.g. `-0x11`, `0b1010` and `0o755`.
+    // the integers are written in decimal for the `Scientific` notation.
+    pub(crate) fn format_integer(&self, v: i128) -> String {
+        let sign = if v < 0 { "-" } else { "" };
+        format!("{}{}", sign, self.format_unsigned(v.unsigned_abs()))
+    }
+
+    // formats the unsigned integer in this notation, it is also used for
+    // the `u128` numbers which are out of the range of `i128`.
+    pub(crate) fn format_unsigned(&self, v: u128) -> String {
+        match self {
+            NumberNotation::Hex => format!("0x{:x}", v),
+            NumberNotation::Binary => format!("0b{:b}", v),
+            NumberNotation::Octal => format!("0o{:o}", v),
+            NumberNotation::Scientific => v.to_string(),
+        }
+    }
+}
+
+macro_rules! define_number_wrapper {
+    ($(#[$attr:meta])* $name:ident, $struct_name:expr) => {
+        $(#[$attr])*
+        #[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+        pub struct $name<T>(pub T);
+
+        impl<T> Serialize for $name<T>
+        where
+            T: Serialize,
+        {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_newtype_struct($struct_name, &self.0)
+            }
+        }
+
+        impl<'de, T> Deserialize<'de> for $name<T>
+        where
+            T: Deserialize<'de>,
+        {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                T::deserialize(deserializer).map($name)
+            }
+        }
+    };
+}
+
+define_number_wrapper!(
+    /// Serializes the integer in hexadecimal, e.g. `0xff_u8` and `-0x11`.
+    Hex,
+    HEX_STRUCT_NAME
+);
+
+define_number_wrapper!(
+    /// Serializes the integer in binary, e.g. `0b1010_u8` and `-0b11`.
+    Binary,
+    BINARY_STRUCT_NAME
+);
+
+define_number_wrapper!(
+    /// Serializes the integer in octal, e.g. `0o755_u16` and `-0o17`.
+    Octal,
+    OCTAL_STRUCT_NAME
+);
+
+define_number_wrapper!(
+    /// Serializes the floating-point number in the scientific notation,
+    /// e.g. `1.5e3` and `-2.5e-4_f32`.
+    Scientific,
+    SCIENTIFIC_STRUCT_NAME
+);
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    use crate::serde::{
+        de::from_str,
+        format::{Binary, Hex, Octal, Scientific},
+        ser::to_string,
+    };
+
+    #[test]
+    fn test_number_wrappers() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Object {
+            color: Hex<u32>,
+            offset: Hex<i8>,
+            flags: Binary<u8>,
+            delta: Binary<i32>,
+            mode: Octal<u16>,
+            umask: Octal<i64>,
+            mass: Scientific<f64>,
+            charge: Scientific<f32>,
+        }
+
+        let v0 = Object {
+            color: Hex(0xff8000),
+            offset: Hex(i8::MIN),
+            flags: Binary(0b1010_0101),
+            delta: Binary(-5),
+            mode: Octal(0o755),
+            umask: Octal(-0o22),
+            mass: Scientific(1500.0),
+            charge: Scientific(-1.6e-19),
+        };
+
+        let s0 = to_string(&v0).unwrap();
+        assert_eq!(
+            s0,
+            r#"{
+    color: 0xff8000_u32
+    offset: -0x80_i8
+    flags: 0b10100101_u8
+    delta: -0b101
+    mode: 0o755_u16
+    umask: -0o22_i64
+    mass: 1.5e3
+    charge: -1.6e-19_f32
+}"#
+        );
+        assert_eq!(from_str::<Object>(&s0).unwrap(), v0);
+
+        // boundary values
+        assert_eq!(to_string(&Hex(u64::MAX)).unwrap(), "0xffffffffffffffff_u64");
+        assert_eq!(
+            to_string(&Hex(i64::MIN)).unwrap(),
+            "-0x8000000000000000_i64"
+        );
+        assert_eq!(to_string(&Octal(0_i32)).unwrap(), "0o0");
+        assert_eq!(
+            to_string(&Hex(u128::MAX)).unwrap(),
+            "0xffffffffffffffffffffffffffffffff_u128"
+        );
+        assert_eq!(
+            to_string(&Hex(i128::MIN)).unwrap(),
+            "-0x80000000000000000000000000000000_i128"
+        );
+        assert_eq!(
+            to_string(&Binary(i16::MIN)).unwrap(),
+            "-0b1000000000000000_i16"
+        );
+
+        for v in [i64::MIN, -1, 0, 1, i64::MAX] {
+            assert_eq!(
+                from_str::<Hex<i64>>(&to_string(&Hex(v)).unwrap()).unwrap(),
+                Hex(v)
+            );
+            assert_eq!(
+                from_str::<Binary<i64>>(&to_string(&Binary(v)).unwrap()).unwrap(),
+                Binary(v)
+            );
+            assert_eq!(
+                from_str::<Octal<i64>>(&to_string(&Octal(v)).unwrap()).unwrap(),
+                Octal(v)
+            );
+        }
+
+        for v in [
+            0.0,
+            -0.0,
+            1.0,
+            std::f64::consts::PI,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+        ] {
+            let s = to_string(&Scientific(v)).unwrap();
+            assert_eq!(from_str::<f64>(&s).unwrap().to_bits(), v.to_bits());
+        }
+
+        // the wrappers are transparent when deserializing
+        assert_eq!(from_str::<Hex<u8>>("255_u8").unwrap(), Hex(255));
+        assert_eq!(from_str::<Scientific<f64>>("0.5").unwrap(), Scientific(0.5));
+    }
+}
@@ -0,0 +1,906 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! A deserializer over the AST, it converts a parsed `AsonNode` tree
+//! into a typed value directly, without printing the tree to text and
+//! lexing it again.
+//!
+//! It also implements `Deserialize` for `AsonNode`, so that the untyped
+//! values (e.g. the remaining entries of an object which are collected by
+//! a `#[serde(flatten)]` field) can be deserialized into nodes.
+
+use std::{fmt, slice::Iter};
+
+use chrono::DateTime;
+use serde::de::{
+    self,
+    value::{MapDeserializer, StrDeserializer},
+    EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+};
+
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant, VariantValue},
+    AsonError,
+};
+
+use super::Result;
+
+impl AsonNode {
+    /// Converts the node into a value of type `T`, it produces the same
+    /// value as `from_str` does with the text of the node.
+    pub fn deserialize_into<T>(&self) -> Result<T>
+    where
+        T: de::DeserializeOwned,
+    {
+        T::deserialize(NodeDeserializer::new(self))
+    }
+}
+
+// The key of the single-entry map which a date is surfaced as through
+// `deserialize_any`, the value is the RFC 3339 string, e.g.
+// `{"$ason::DateTime": "2024-06-26T16:38:50+08:00"}`.
+pub(super) const DATE_TIME_KEY: &str = "$ason::DateTime";
+
+/// The node is built from the values surfaced by `deserialize_any`,
+/// so it differs from the node of `parse_from_str` in a few places:
+///
+/// - a list and a tuple are both sequences, they become `AsonNode::List`.
+/// - an object and a map are both maps, a map whose keys are all strings
+///   (including the empty map `[:]`) becomes `AsonNode::Object`,
+///   otherwise `AsonNode::Map`.
+/// - `Option` becomes the `Option::None` and `Option::Some(...)` variants,
+///   the other variants are not supported since serde does not provide
+///   the type name and the style of variants.
+///
+/// A date is surfaced as a single-entry map `{"$ason::DateTime": "..."}`
+/// (e.g. it becomes an object of this entry in `serde_json::Value`),
+/// which becomes `AsonNode::DateTime` again.
+impl<'de> de::Deserialize<'de> for AsonNode {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(AsonNodeVisitor)
+    }
+}
+
+struct AsonNodeVisitor;
+
+impl<'de> de::Visitor<'de> for AsonNodeVisitor {
+    type Value = AsonNode;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an ASON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Boolean(v))
+    }
+
+    fn visit_i8<E>(self, v: i8) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::I8(v)))
+    }
+
+    fn visit_i16<E>(self, v: i16) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::I16(v)))
+    }
+
+    fn visit_i32<E>(self, v: i32) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::I32(v)))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::I64(v)))
+    }
+
+    fn visit_u8<E>(self, v: u8) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::U8(v)))
+    }
+
+    fn visit_u16<E>(self, v: u16) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::U16(v)))
+    }
+
+    fn visit_u32<E>(self, v: u32) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::U32(v)))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::U64(v)))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::I128(v)))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::U128(v)))
+    }
+
+    fn visit_f32<E>(self, v: f32) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::F32(v)))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Number(Number::F64(v)))
+    }
+
+    fn visit_char<E>(self, v: char) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Char(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::String(v.to_owned()))
+    }
+
+    fn visit_string<E>(self, v: String) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::String(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::HexByteData(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::HexByteData(v))
+    }
+
+    fn visit_none<E>(self) -> std::result::Result<AsonNode, E> {
+        Ok(AsonNode::Variant(Variant::new("Option", "None")))
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> std::result::Result<AsonNode, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let value = de::Deserialize::deserialize(deserializer)?;
+        Ok(AsonNode::Variant(Variant::with_value(
+            "Option", "Some", value,
+        )))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<AsonNode, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut items = vec![];
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(AsonNode::List(items))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<AsonNode, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut nvps = vec![];
+        while let Some((name, value)) = map.next_entry::<AsonNode, AsonNode>()? {
+            nvps.push(NameValuePair {
+                name: Box::new(name),
+                value: Box::new(value),
+            });
+        }
+
+        // the date
+        if let [NameValuePair { name, value }] = nvps.as_slice() {
+            if let (AsonNode::String(key), AsonNode::String(s)) = (name.as_ref(), value.as_ref()) {
+                if key == DATE_TIME_KEY {
+                    return DateTime::parse_from_rfc3339(s)
+                        .map(AsonNode::DateTime)
+                        .map_err(de::Error::custom);
+                }
+            }
+        }
+
+        if nvps
+            .iter()
+            .all(|nvp| matches!(nvp.name.as_ref(), AsonNode::String(_)))
+        {
+            let kvps = nvps
+                .into_iter()
+                .map(|nvp| match *nvp.name {
+                    AsonNode::String(key) => KeyValuePair {
+                        key,
+                        value: nvp.value,
+                    },
+                    _ => unreachable!(),
+                })
+                .collect();
+            Ok(AsonNode::Object(kvps))
+        } else {
+            Ok(AsonNode::Map(nvps))
+        }
+    }
+}
+
+pub(super) struct NodeDeserializer<'a> {
+    node: &'a AsonNode,
+}
+
+impl<'a> NodeDeserializer<'a> {
+    pub(super) fn new(node: &'a AsonNode) -> Self {
+        Self { node }
+    }
+
+    // the value of the extension node, the same as `deserialize_any`
+    // the extension is transparent to the typed methods.
+    fn value(&self) -> &'a AsonNode {
+        let mut node = self.node;
+        while let AsonNode::Extension(_, value) = node {
+            node = value;
+        }
+        node
+    }
+}
+
+// the typed methods accept the node of the same type only, the same as
+// the direct deserializer with the default options, e.g. the node `11_i32`
+// can not be deserialized into `i64`.
+macro_rules! deserialize_number {
+    ($method:ident, $variant:ident, $visit:ident, $message:literal) => {
+        fn $method<V>(self, visitor: V) -> Result<V::Value>
+        where
+            V: de::Visitor<'de>,
+        {
+            match self.value() {
+                AsonNode::Number(Number::$variant(v)) => visitor.$visit(*v),
+                _ => Err(AsonError::Message($message.to_owned())),
+            }
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for NodeDeserializer<'_> {
+    type Error = AsonError;
+
+    fn is_human_readable(&self) -> bool {
+        true
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // the same as the direct deserializer, the `Option` variant is surfaced
+        // as an option, e.g. the fields of a `#[serde(flatten)]` struct
+        // which are buffered by serde.
+        if matches!(self.node, AsonNode::Variant(Variant { type_name, .. }) if type_name == "Option")
+        {
+            return self.deserialize_option(visitor);
+        }
+
+        match self.node {
+            AsonNode::Number(number) => match number {
+                Number::I8(v) => visitor.visit_i8(*v),
+                Number::U8(v) => visitor.visit_u8(*v),
+                Number::I16(v) => visitor.visit_i16(*v),
+                Number::U16(v) => visitor.visit_u16(*v),
+                Number::I32(v) => visitor.visit_i32(*v),
+                Number::U32(v) => visitor.visit_u32(*v),
+                Number::I64(v) => visitor.visit_i64(*v),
+                Number::U64(v) => visitor.visit_u64(*v),
+                Number::I128(v) => visitor.visit_i128(*v),
+                Number::U128(v) => visitor.visit_u128(*v),
+                Number::F32(v) => visitor.visit_f32(*v),
+                Number::F64(v) => visitor.visit_f64(*v),
+            },
+            AsonNode::Boolean(v) => visitor.visit_bool(*v),
+            AsonNode::Char(v) => visitor.visit_char(*v),
+            AsonNode::String(v) => visitor.visit_str(v),
+            AsonNode::DateTime(v) => visitor.visit_map(MapDeserializer::new(std::iter::once((
+                DATE_TIME_KEY,
+                v.to_rfc3339(),
+            )))),
+            AsonNode::Variant(variant) => visitor.visit_enum(VariantAccessor::new(variant)),
+            AsonNode::HexByteData(v) => visitor.visit_bytes(v),
+            AsonNode::List(items) | AsonNode::Tuple(items) => {
+                visitor.visit_seq(SeqAccessor::new(items))
+            }
+            AsonNode::Object(pairs) => visitor.visit_map(ObjectAccessor::new(pairs)),
+            AsonNode::Map(pairs) => visitor.visit_map(MapAccessor::new(pairs)),
+            AsonNode::Extension(_, value) => NodeDeserializer::new(value).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.node {
+            AsonNode::Variant(Variant {
+                type_name,
+                member_name,
+                value,
+            }) if type_name == "Option" => match (member_name.as_str(), value) {
+                ("None", VariantValue::Empty) => visitor.visit_none(),
+                ("Some", VariantValue::Value(v)) => visitor.visit_some(NodeDeserializer::new(v)),
+                _ => Err(AsonError::Message(
+                    "Invalid member of variant \"Option\".".to_owned(),
+                )),
+            },
+            _ => Err(AsonError::Message(
+                "Expect the \"Option\" type of variant.".to_owned(),
+            )),
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value() {
+            AsonNode::Boolean(v) => visitor.visit_bool(*v),
+            _ => Err(AsonError::Message("Expect a \"Boolean\" value.".to_owned())),
+        }
+    }
+
+    deserialize_number!(deserialize_i8, I8, visit_i8, "Expect an \"i8\" value.");
+    deserialize_number!(deserialize_i16, I16, visit_i16, "Expect an \"i16\" value.");
+    deserialize_number!(deserialize_i32, I32, visit_i32, "Expect an \"i32\" value.");
+    deserialize_number!(deserialize_i64, I64, visit_i64, "Expect an \"i64\" value.");
+    deserialize_number!(
+        deserialize_i128,
+        I128,
+        visit_i128,
+        "Expect an \"i128\" value."
+    );
+    deserialize_number!(deserialize_u8, U8, visit_u8, "Expect an \"u8\" value.");
+    deserialize_number!(deserialize_u16, U16, visit_u16, "Expect an \"u16\" value.");
+    deserialize_number!(deserialize_u32, U32, visit_u32, "Expect an \"u32\" value.");
+    deserialize_number!(deserialize_u64, U64, visit_u64, "Expect an \"u64\" value.");
+    deserialize_number!(
+        deserialize_u128,
+        U128,
+        visit_u128,
+        "Expect an \"u128\" value."
+    );
+    deserialize_number!(deserialize_f32, F32, visit_f32, "Expect a \"f32\" value.");
+    deserialize_number!(deserialize_f64, F64, visit_f64, "Expect a \"f64\" value.");
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value() {
+            AsonNode::Char(v) => visitor.visit_char(*v),
+            _ => Err(AsonError::Message("Expect a \"Char\" value.".to_owned())),
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value() {
+            AsonNode::String(v) => visitor.visit_str(v),
+            _ => Err(AsonError::Message("Expect a \"String\" value.".to_owned())),
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value() {
+            AsonNode::HexByteData(v) => visitor.visit_bytes(v),
+            _ => Err(AsonError::Message("Expect a \"Bytes\" value.".to_owned())),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_unit<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(AsonError::Message("Does not support Unit.".to_owned()))
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(AsonError::Message(
+            "Does not support \"Unit\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value() {
+            AsonNode::List(items) => visitor.visit_seq(SeqAccessor::new(items)),
+            _ => Err(AsonError::Message("Expect a \"List\".".to_owned())),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value() {
+            AsonNode::Tuple(items) => visitor.visit_seq(SeqAccessor::new(items)),
+            _ => Err(AsonError::Message("Expect a \"Tuple\".".to_owned())),
+        }
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(AsonError::Message(
+            "Does not support \"Tuple\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value() {
+            AsonNode::Map(pairs) => visitor.visit_map(MapAccessor::new(pairs)),
+            // an object is accepted as a map with string keys, the same as `from_str`.
+            AsonNode::Object(pairs) => visitor.visit_map(ObjectAccessor::new(pairs)),
+            // the empty map may be written as `[]` by hand, which is parsed as an empty list,
+            // the same as `from_str` accepts it.
+            AsonNode::List(items) if items.is_empty() => visitor.visit_map(MapAccessor::new(&[])),
+            _ => Err(AsonError::Message("Expect a \"Map\".".to_owned())),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value() {
+            AsonNode::Object(pairs) => visitor.visit_map(ObjectAccessor::new(pairs)),
+            _ => Err(AsonError::Message("Expect an \"Object\".".to_owned())),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value() {
+            AsonNode::Variant(variant) => visitor.visit_enum(VariantAccessor::new(variant)),
+            _ => Err(AsonError::Message(format!(
+                "Expect a variant of \"{}\".",
+                name
+            ))),
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value() {
+            AsonNode::String(v) => visitor.visit_str(v),
+            _ => Err(AsonError::Message(
+                "Expect an identifier for object.".to_owned(),
+            )),
+        }
+    }
+}
+
+struct SeqAccessor<'a> {
+    iter: Iter<'a, AsonNode>,
+}
+
+impl<'a> SeqAccessor<'a> {
+    fn new(items: &'a [AsonNode]) -> Self {
+        Self { iter: items.iter() }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqAccessor<'_> {
+    type Error = AsonError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(node) => seed.deserialize(NodeDeserializer::new(node)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct ObjectAccessor<'a> {
+    iter: Iter<'a, KeyValuePair>,
+    value: Option<&'a AsonNode>,
+}
+
+impl<'a> ObjectAccessor<'a> {
+    fn new(pairs: &'a [KeyValuePair]) -> Self {
+        Self {
+            iter: pairs.iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for ObjectAccessor<'_> {
+    type Error = AsonError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(KeyValuePair { key, value }) => {
+                self.value = Some(value);
+                seed.deserialize(key.as_str().into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        // `next_value_seed` is always called after `next_key_seed` returns a key.
+        seed.deserialize(NodeDeserializer::new(self.value.take().unwrap()))
+    }
+}
+
+struct MapAccessor<'a> {
+    iter: Iter<'a, NameValuePair>,
+    value: Option<&'a AsonNode>,
+}
+
+impl<'a> MapAccessor<'a> {
+    fn new(pairs: &'a [NameValuePair]) -> Self {
+        Self {
+            iter: pairs.iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapAccessor<'_> {
+    type Error = AsonError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(NameValuePair { name, value }) => {
+                self.value = Some(value);
+                seed.deserialize(NodeDeserializer::new(name)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        // `next_value_seed` is always called after `next_key_seed` returns a key.
+        seed.deserialize(NodeDeserializer::new(self.value.take().unwrap()))
+    }
+}
+
+struct VariantAccessor<'a> {
+    variant: &'a Variant,
+}
+
+impl<'a> VariantAccessor<'a> {
+    fn new(variant: &'a Variant) -> Self {
+        Self { variant }
+    }
+}
+
+impl<'de> EnumAccess<'de> for VariantAccessor<'_> {
+    type Error = AsonError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let member_name = self.variant.member_name.as_str();
+        let deserializer: StrDeserializer<AsonError> = member_name.into_deserializer();
+        let value = seed.deserialize(deserializer)?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantAccessor<'_> {
+    type Error = AsonError;
+
+    fn unit_variant(self) -> Result<()> {
+        match &self.variant.value {
+            VariantValue::Empty => Ok(()),
+            _ => Err(AsonError::Message(format!(
+                "Expect the unit variant \"{}::{}\".",
+                self.variant.type_name, self.variant.member_name
+            ))),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match &self.variant.value {
+            VariantValue::Value(value) => seed.deserialize(NodeDeserializer::new(value)),
+            _ => Err(AsonError::Message(format!(
+                "Expect the single value variant \"{}::{}\".",
+                self.variant.type_name, self.variant.member_name
+            ))),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match &self.variant.value {
+            VariantValue::Tuple(items) => visitor.visit_seq(SeqAccessor::new(items)),
+            _ => Err(AsonError::Message(format!(
+                "Expect the tuple variant \"{}::{}\".",
+                self.variant.type_name, self.variant.member_name
+            ))),
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match &self.variant.value {
+            VariantValue::Object(pairs) => visitor.visit_map(ObjectAccessor::new(pairs)),
+            _ => Err(AsonError::Message(format!(
+                "Expect the struct variant \"{}::{}\".",
+                self.variant.type_name, self.variant.member_name
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    use chrono::DateTime;
+
+    use crate::{
+        ast::{AsonNode, KeyValuePair, NameValuePair, NodeKind, Number, Variant},
+        parser::parse_from_str,
+        serde::de::from_str,
+        AsonError,
+    };
+
+    #[test]
+    fn test_deserialize_into() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum Shape {
+            Circle(f64),
+            Rect { width: i32, height: i32 },
+            Line(i32, i32),
+            Empty,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object {
+            id: i32,
+            name: String,
+            score: Option<f32>,
+            tags: Vec<String>,
+            point: (i32, char),
+            shapes: Vec<Shape>,
+            attrs: HashMap<String, bool>,
+        }
+
+        let s0 = r#"{
+            id: 123
+            name: "foo"
+            score: Option::Some(4.5_f32)
+            tags: ["a", "b"]
+            point: (11, 'x')
+            shapes: [
+                Shape::Circle(1.5)
+                Shape::Rect{width: 3, height: 5}
+                Shape::Line(7, 9)
+                Shape::Empty
+            ]
+            attrs: ["visible": true]
+        }"#;
+
+        let node = parse_from_str(s0).unwrap();
+        let v0: Object = node.deserialize_into().unwrap();
+
+        assert_eq!(v0, from_str::<Object>(s0).unwrap());
+        assert_eq!(v0.score, Some(4.5));
+        assert_eq!(
+            v0.shapes[1],
+            Shape::Rect {
+                width: 3,
+                height: 5
+            }
+        );
+
+        // empty maps
+        assert_eq!(
+            parse_from_str(r#"[]"#)
+                .unwrap()
+                .deserialize_into::<HashMap<String, bool>>()
+                .unwrap(),
+            HashMap::new()
+        );
+        assert_eq!(
+            parse_from_str(r#"[:]"#)
+                .unwrap()
+                .deserialize_into::<HashMap<String, bool>>()
+                .unwrap(),
+            HashMap::new()
+        );
+
+        // err: mismatched type
+        assert!(matches!(
+            parse_from_str(r#""foo""#)
+                .unwrap()
+                .deserialize_into::<i32>(),
+            Err(AsonError::Message(_))
+        ));
+
+        // err: mismatched integer width, the same as `from_str`
+        assert_eq!(
+            parse_from_str(r#"11"#).unwrap().deserialize_into::<i64>(),
+            Err(AsonError::Message("Expect an \"i64\" value.".to_owned()))
+        );
+        assert!(matches!(
+            from_str::<i64>(r#"11"#),
+            Err(AsonError::MessageWithLocation(message, _)) if message == "Expect an \"i64\" value."
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_node() {
+        let s0 = r#"{
+            id: 123_u8
+            name: "foo"
+            created: d"2024-06-26T16:38:50+08:00"
+            score: Option::Some(4.5_f32)
+            tags: ["a", 'b']
+            attrs: ["visible": true]
+            ids: [11: 13]
+        }"#;
+
+        let node: AsonNode = from_str(s0).unwrap();
+        let date = DateTime::parse_from_rfc3339("2024-06-26T16:38:50+08:00").unwrap();
+
+        assert_eq!(
+            node,
+            AsonNode::Object(vec![
+                KeyValuePair::new("id", AsonNode::Number(Number::U8(123))),
+                KeyValuePair::new("name", AsonNode::String("foo".to_owned())),
+                KeyValuePair::new("created", AsonNode::DateTime(date)),
+                KeyValuePair::new(
+                    "score",
+                    AsonNode::Variant(Variant::with_value(
+                        "Option",
+                        "Some",
+                        AsonNode::Number(Number::F32(4.5))
+                    ))
+                ),
+                KeyValuePair::new(
+                    "tags",
+                    AsonNode::List(vec![AsonNode::String("a".to_owned()), AsonNode::Char('b')])
+                ),
+                KeyValuePair::new(
+                    "attrs",
+                    AsonNode::Object(vec![KeyValuePair::new("visible", AsonNode::Boolean(true))])
+                ),
+                KeyValuePair::new(
+                    "ids",
+                    AsonNode::Map(vec![NameValuePair {
+                        name: Box::new(AsonNode::Number(Number::I32(11))),
+                        value: Box::new(AsonNode::Number(Number::I32(13))),
+                    }])
+                ),
+            ])
+        );
+
+        // the date is preserved when converting the node
+        assert_eq!(
+            parse_from_str(r#"d"2024-06-26T16:38:50+08:00""#)
+                .unwrap()
+                .deserialize_into::<AsonNode>()
+                .unwrap(),
+            AsonNode::DateTime(date)
+        );
+
+        // the remaining entries of an object
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Object {
+            id: i32,
+            #[serde(flatten)]
+            rest: HashMap<String, AsonNode>,
+        }
+
+        assert_eq!(
+            from_str::<Object>(r#"{id: 123, created: d"2024-06-26T16:38:50+08:00"}"#).unwrap(),
+            Object {
+                id: 123,
+                rest: HashMap::from([("created".to_owned(), AsonNode::DateTime(date))]),
+            }
+        );
+
+        // unlike `parse_from_str`, the maps whose keys are all strings become objects
+        assert_eq!(
+            parse_from_str(r#"["a": 1, "b": 2]"#).unwrap().kind(),
+            NodeKind::Map
+        );
+        assert_eq!(
+            from_str::<AsonNode>(r#"["a": 1, "b": 2]"#).unwrap(),
+            AsonNode::Object(vec![
+                KeyValuePair::new("a", AsonNode::Number(Number::I32(1))),
+                KeyValuePair::new("b", AsonNode::Number(Number::I32(2))),
+            ])
+        );
+        assert_eq!(
+            from_str::<AsonNode>("[:]").unwrap(),
+            AsonNode::Object(vec![])
+        );
+    }
+}
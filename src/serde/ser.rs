@@ -6,19 +6,144 @@
 
 use std::io::Write;
 
-use super::Result;
-use crate::AsonError;
+use super::{format::NumberNotation, Result};
+use crate::{lexer::is_valid_identifier, AsonError};
 
 use serde::{ser, Serialize};
 
 const DEFAULT_INDEXT_CHARS: &str = "    ";
 
+/// Options for serializing.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SerializeOptions {
+    /// Serialize `char` as a one-character string, e.g. `"a"` instead of `'a'`.
+    pub char_as_string: bool,
+
+    /// Escape all non-ASCII chars of chars and strings
+    /// as unicode escape sequence, e.g. `"\u{6587}"` instead of `"文"`.
+    pub ascii_only: bool,
+
+    /// Omit the type suffixes of numbers, e.g. `17` instead of `17_i8`.
+    ///
+    /// The type of numbers is lost when the output is parsed, so
+    /// the output should be deserialized with the `lenient` option of
+    /// `DeserializeOptions`, which converts the numbers to the target types.
+    /// Note that the suffixes of integers which are out of the range of `i32`
+    /// are kept, since the bare literals can not be parsed.
+    pub omit_number_suffixes: bool,
+
+    /// Serialize byte data in the hexdump-style layout, see `HexDumpConfig`.
+    pub hex_dump: Option<HexDumpConfig>,
+
+    /// The notation of floating-point numbers, see `FloatFormat`.
+    pub float_format: FloatFormat,
+
+    /// Serialize unit variants as the bare member names, e.g. `Red`
+    /// instead of `Color::Red`.
+    ///
+    /// The output should be deserialized with the `allow_bare_variant`
+    /// option of `DeserializeOptions`.
+    pub bare_unit_variants: bool,
+
+    /// The indentation of one level, e.g. two spaces or a tab `"\t"`,
+    /// `None` for four spaces.
+    /// It can consist of spaces and tabs only, otherwise the serialization
+    /// fails since the output could not be parsed.
+    pub indent: Option<String>,
+
+    /// Write the lists and maps which have fewer elements than this number
+    /// in a single line, e.g. `[1, 2, 3]`, the collections (and objects)
+    /// nested in them are written in a single line too.
+    /// The default `0` writes all collections one element per line.
+    pub inline_collections_below: usize,
+
+    /// Append a comma to each element of the collections and objects
+    /// which are written one element per line, e.g. `[\n    1,\n    2,\n]`.
+    pub trailing_comma: bool,
+}
+
+/// The notation of floating-point numbers.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum FloatFormat {
+    /// The shortest decimal literal which round-trips, e.g. `3.141592653589793`.
+    #[default]
+    Decimal,
+
+    /// The hexadecimal floating-point literal, e.g. `0x1.921fb54442d18p+1_f64`,
+    /// which represents the exact bits of the number and does not depend
+    /// on the decimal conversion of the reader.
+    /// `NaN` and `Inf` are still written as the identifiers.
+    HexFloat,
+}
+
+/// The hexdump-style layout of byte data, the offset of each line
+/// (and optionally the ASCII text) is written as block comments,
+/// so the output can still be parsed, e.g.
+///
+/// ```text
+/// h"
+///     /* 0000 */ 48 65 6c 6c 6f 2c 20 41 53 4f 4e 21 0a 00 01 ff /* Hello, ASON!.... */
+///     /* 0010 */ 62 79 74 65 73                                  /* bytes */
+/// "
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct HexDumpConfig {
+    /// The number of bytes of each line.
+    pub bytes_per_line: usize,
+
+    /// Append the ASCII text of each line, the non-printable chars
+    /// (as well as `*` and `/`, which may break the comment) are shown as `.`.
+    pub show_ascii: bool,
+}
+
+impl Default for HexDumpConfig {
+    fn default() -> Self {
+        Self {
+            bytes_per_line: 16,
+            show_ascii: true,
+        }
+    }
+}
+
+impl SerializeOptions {
+    /// Returns the names of the options which differ from the default,
+    /// it is useful for logging and debugging the configuration.
+    pub fn describe(&self) -> Vec<&'static str> {
+        let flags = [
+            (self.char_as_string, "char_as_string"),
+            (self.ascii_only, "ascii_only"),
+            (self.omit_number_suffixes, "omit_number_suffixes"),
+            (self.hex_dump.is_some(), "hex_dump"),
+            (self.float_format != FloatFormat::Decimal, "float_format"),
+            (self.bare_unit_variants, "bare_unit_variants"),
+            (self.indent.is_some(), "indent"),
+            (
+                self.inline_collections_below > 0,
+                "inline_collections_below",
+            ),
+            (self.trailing_comma, "trailing_comma"),
+        ];
+
+        flags
+            .into_iter()
+            .filter_map(|(enabled, name)| enabled.then_some(name))
+            .collect()
+    }
+}
+
 pub fn to_string<T>(value: &T) -> Result<String>
+where
+    T: Serialize,
+{
+    to_string_with_options(value, &SerializeOptions::default())
+}
+
+pub fn to_string_with_options<T>(value: &T, options: &SerializeOptions) -> Result<String>
 where
     T: Serialize,
 {
     let mut buf: Vec<u8> = vec![];
-    to_writer(value, &mut buf)?;
+    to_writer_with_options(value, &mut buf, options)?;
     let s = String::from_utf8(buf).unwrap();
     Ok(s)
 }
@@ -27,7 +152,25 @@ pub fn to_writer<T, W: Write>(value: &T, writer: &mut W) -> Result<()>
 where
     T: Serialize,
 {
-    let mut serializer = Serializer::new(DEFAULT_INDEXT_CHARS, writer);
+    to_writer_with_options(value, writer, &SerializeOptions::default())
+}
+
+pub fn to_writer_with_options<T, W: Write>(
+    value: &T,
+    writer: &mut W,
+    options: &SerializeOptions,
+) -> Result<()>
+where
+    T: Serialize,
+{
+    let indent_chars = options.indent.as_deref().unwrap_or(DEFAULT_INDEXT_CHARS);
+    if !indent_chars.chars().all(|c| c == ' ' || c == '\t') {
+        return Err(AsonError::Message(
+            "The indent can consist of spaces and tabs only.".to_owned(),
+        ));
+    }
+
+    let mut serializer = Serializer::new(indent_chars, writer, options.clone());
     value.serialize(&mut serializer)
     // Ok(serializer.buffer.join(""))
 }
@@ -41,27 +184,171 @@ where
     indent_chars: String,
 
     is_first_element: bool,
+    options: SerializeOptions,
+
+    // the number of the open collections which are written in a single line,
+    // see the option `inline_collections_below`.
+    inline_depth: usize,
+
+    // the notation of the numbers which are wrapped in
+    // `Hex`, `Binary`, `Octal` or `Scientific`.
+    number_notation: Option<NumberNotation>,
 }
 
 impl<'a, W> Serializer<'a, W>
 where
     W: Write,
 {
-    fn new(indent_chars: &str, writer: &'a mut W) -> Self {
+    fn new(indent_chars: &str, writer: &'a mut W, options: SerializeOptions) -> Self {
         Self {
             writer,
             indent_level: 0,
             indent_chars: indent_chars.to_owned(),
             is_first_element: false,
+            options,
+            inline_depth: 0,
+            number_notation: None,
         }
     }
 
     // append the text content
     fn append(&mut self, s: String) -> Result<()> {
-        match write!(self.writer, "{}", s) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(AsonError::Message(e.to_string())),
+        self.writer.write_all(s.as_bytes())?;
+        Ok(())
+    }
+
+    // append the integer with the type suffix, e.g. `17_i8`.
+    // the suffix is omitted when the option `omit_number_suffixes` is set,
+    // unless the number is out of the range of the default integer type `i32`,
+    // because a bare literal of such number can not be parsed.
+    fn append_integer<T>(&mut self, v: T, type_name: &str) -> Result<()>
+    where
+        T: std::fmt::Display + Copy + Into<i128> + TryInto<i32>,
+    {
+        let s = match self.number_notation {
+            Some(notation) => notation.format_integer(v.into()),
+            None => format!("{}", v),
+        };
+        if self.options.omit_number_suffixes && v.try_into().is_ok() {
+            self.append(s)
+        } else {
+            self.append(format!("{}_{}", s, type_name))
+        }
+    }
+
+    // append the key of object (and the colon), the key which is not a valid
+    // identifier (e.g. the field renamed to "has space") is quoted.
+    fn append_key(&mut self, key: &str) -> Result<()> {
+        if is_valid_identifier(key) {
+            self.append(format!("{}: ", key))
+        } else {
+            self.append(format!("{}: ", quote_str(key, self.options.ascii_only)))
+        }
+    }
+
+    // append the byte data in the hexdump-style layout, e.g.
+    //
+    // h"
+    //     /* 0000 */ 61 62 63 /* abc */
+    // "
+    fn append_hex_dump(&mut self, data: &[u8], config: &HexDumpConfig) -> Result<()> {
+        let bytes_per_line = config.bytes_per_line.max(1);
+
+        self.append("h\"".to_owned())?;
+        self.increase_level();
+
+        for (idx, chunk) in data.chunks(bytes_per_line).enumerate() {
+            self.append("\n".to_owned())?;
+            self.append_indent()?;
+
+            let offset = idx * bytes_per_line;
+            let hex = chunk
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<String>>()
+                .join(" ");
+
+            if config.show_ascii {
+                let ascii = chunk
+                    .iter()
+                    .map(|byte| match byte {
+                        b'*' | b'/' => '.',
+                        0x20..=0x7e => *byte as char,
+                        _ => '.',
+                    })
+                    .collect::<String>();
+
+                // pad the last line to align the ASCII text
+                self.append(format!(
+                    "/* {:04x} */ {:<width$} /* {} */",
+                    offset,
+                    hex,
+                    ascii,
+                    width = bytes_per_line * 3 - 1
+                ))?;
+            } else {
+                self.append(format!("/* {:04x} */ {}", offset, hex))?;
+            }
+        }
+
+        self.decrease_level();
+        self.append("\n".to_owned())?;
+        self.append_indent()?;
+        self.append("\"".to_owned())
+    }
+
+    // append the opening bracket (or brace) of the collection (or object),
+    // the length is `None` for objects, which are written in a single line only
+    // when they are nested in an inline collection.
+    fn enter_container(&mut self, opening: &str, len: Option<usize>) -> Result<()> {
+        if self.inline_depth > 0
+            || len.is_some_and(|len| len < self.options.inline_collections_below)
+        {
+            self.inline_depth += 1;
+        }
+
+        self.append(opening.to_owned())?;
+        self.is_first_element = true;
+        self.increase_level();
+        Ok(())
+    }
+
+    fn leave_container(&mut self, closing: &str) -> Result<()> {
+        self.decrease_level();
+
+        if self.inline_depth > 0 {
+            self.inline_depth -= 1;
+        } else if !self.is_first_element {
+            self.append("\n".to_owned())?;
+            self.append_indent()?;
+        }
+
+        // the container itself is an element of its parent
+        self.is_first_element = false;
+        self.append(closing.to_owned())
+    }
+
+    // append the separator before an element of collection or a field of object.
+    fn append_element_separator(&mut self) -> Result<()> {
+        if self.inline_depth > 0 {
+            if !self.is_first_element {
+                self.append(", ".to_owned())?;
+            }
+        } else {
+            self.append("\n".to_owned())?;
+            self.append_indent()?;
+        }
+
+        self.is_first_element = false;
+        Ok(())
+    }
+
+    // append the comma after an element when the option `trailing_comma` is set.
+    fn append_trailing_comma(&mut self) -> Result<()> {
+        if self.inline_depth == 0 && self.options.trailing_comma {
+            self.append(",".to_owned())?;
         }
+        Ok(())
     }
 
     // append the leading whitespaces
@@ -94,6 +381,12 @@ where
     type SerializeStruct = Self;
     type SerializeStructVariant = Self;
 
+    // ASON is a text format, so the types which have a compact form (e.g. `IpAddr`)
+    // are written in their readable form.
+    fn is_human_readable(&self) -> bool {
+        true
+    }
+
     fn serialize_bool(self, v: bool) -> Result<()> {
         match v {
             true => self.append("true".to_owned()),
@@ -102,51 +395,97 @@ where
     }
 
     fn serialize_i8(self, v: i8) -> Result<()> {
-        self.append(format!("{}_i8", v))
+        self.append_integer(v, "i8")
     }
 
     fn serialize_i16(self, v: i16) -> Result<()> {
-        self.append(format!("{}_i16", v))
+        self.append_integer(v, "i16")
     }
 
     fn serialize_i32(self, v: i32) -> Result<()> {
         // 'i32' is the default type for integer numbers,
         // so no explicit type name is needed.
-        self.append(format!("{}", v))
+        match self.number_notation {
+            Some(notation) => self.append(notation.format_integer(v.into())),
+            None => self.append(format!("{}", v)),
+        }
     }
 
     fn serialize_i64(self, v: i64) -> Result<()> {
-        self.append(format!("{}_i64", v))
+        self.append_integer(v, "i64")
     }
 
     fn serialize_u8(self, v: u8) -> Result<()> {
-        self.append(format!("{}_u8", v))
+        self.append_integer(v, "u8")
     }
 
     fn serialize_u16(self, v: u16) -> Result<()> {
-        self.append(format!("{}_u16", v))
+        self.append_integer(v, "u16")
     }
 
     fn serialize_u32(self, v: u32) -> Result<()> {
-        self.append(format!("{}_u32", v))
+        self.append_integer(v, "u32")
     }
 
     fn serialize_u64(self, v: u64) -> Result<()> {
-        self.append(format!("{}_u64", v))
+        self.append_integer(v, "u64")
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.append_integer(v, "i128")
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        match i128::try_from(v) {
+            Ok(v) => self.append_integer(v, "u128"),
+            Err(_) => {
+                // out of the range of `i128` (and `i32`),
+                // so the suffix is always required.
+                let s = match self.number_notation {
+                    Some(notation) => notation.format_unsigned(v),
+                    None => v.to_string(),
+                };
+                self.append(format!("{}_u128", s))
+            }
+        }
     }
 
     fn serialize_f32(self, v: f32) -> Result<()> {
         let s = if v.is_nan() {
-            "NaN_f32".to_owned()
+            "NaN".to_owned()
         } else if v == f32::INFINITY {
-            "Inf_f32".to_owned()
+            "Inf".to_owned()
         } else if v == f32::NEG_INFINITY {
-            "-Inf_f32".to_owned()
+            "-Inf".to_owned()
+        } else if self.number_notation == Some(NumberNotation::Scientific) {
+            format!("{:e}", v)
+        } else if self.options.float_format == FloatFormat::HexFloat {
+            // the 23-bit mantissa is shifted to align to 6 hex digits.
+            let (mantissa, exponent) = (v.to_bits() & 0x7f_ffff, (v.to_bits() >> 23) & 0xff);
+            format_hex_float(
+                v.is_sign_negative(),
+                exponent,
+                (mantissa as u64) << 1,
+                6,
+                127,
+            )
+        } else if is_extreme_magnitude(v.abs() as f64) {
+            format!("{:e}", v)
         } else {
-            format!("{}_f32", v)
+            format!("{}", v)
         };
 
-        self.append(s)
+        if self.options.omit_number_suffixes {
+            // the bare literal is parsed as `f64`, so a decimal point
+            // needs to be appended if there is no decimal point or exponent.
+            if s.contains(['.', 'e', 'N', 'I']) {
+                self.append(s)
+            } else {
+                self.append(format!("{}.0", s))
+            }
+        } else {
+            self.append(format!("{}_f32", s))
+        }
     }
 
     fn serialize_f64(self, v: f64) -> Result<()> {
@@ -159,6 +498,19 @@ where
             "Inf".to_owned()
         } else if v == f64::NEG_INFINITY {
             "-Inf".to_owned()
+        } else if self.number_notation == Some(NumberNotation::Scientific) {
+            format!("{:e}", v)
+        } else if self.options.float_format == FloatFormat::HexFloat {
+            // the type suffix is kept, so the literal does not depend on
+            // the default floating-point type of the reader.
+            let (mantissa, exponent) =
+                (v.to_bits() & 0xf_ffff_ffff_ffff, v.to_bits() >> 52 & 0x7ff);
+            let s = format_hex_float(v.is_sign_negative(), exponent as u32, mantissa, 13, 1023);
+            format!("{}_f64", s)
+        } else if is_extreme_magnitude(v.abs()) {
+            // the exponent marks the literal as a floating-point number,
+            // so there is no need to append a decimal point.
+            format!("{:e}", v)
         } else {
             // a decimal point needs to be appended if there is no decimal point
             // in the literal.
@@ -172,56 +524,27 @@ where
     }
 
     fn serialize_char(self, v: char) -> Result<()> {
-        let s = match v {
-            '\\' => "\\\\".to_owned(),
-            '\'' => "\\'".to_owned(),
-            '\t' => {
-                // horizontal tabulation
-                "\\t".to_owned()
-            }
-            '\r' => {
-                // carriage return, jump to the beginning of the line (CR)
-                "\\r".to_owned()
-            }
-            '\n' => {
-                // new line/line feed (LF)
-                "\\n".to_owned()
-            }
-            '\0' => {
-                // null char
-                "\\0".to_owned()
-            }
-            _ => v.to_string(),
-        };
+        if self.options.char_as_string {
+            return self.serialize_str(v.encode_utf8(&mut [0_u8; 4]));
+        }
 
-        self.append(format!("'{}'", s))
+        self.append(quote_char(v, self.options.ascii_only))
     }
 
     fn serialize_str(self, v: &str) -> Result<()> {
-        let s = format!(
-            "\"{}\"",
-            v.chars()
-                .map(|c| match c {
-                    '\\' => "\\\\".to_owned(),
-                    '"' => "\\\"".to_owned(),
-                    // null char is allowed in the ASON string, it is used for represent the string resource.
-                    '\0' => "\\0".to_owned(),
-                    // some text editors automatically remove the tab when
-                    // it is at the end of a line, so it is best to escape the tab char.
-                    // therefor it should be escaped
-                    '\t' => "\\t".to_owned(),
-                    _ => c.to_string(),
-                })
-                .collect::<Vec<String>>()
-                .join("")
-        );
-        self.append(s)
+        self.append(quote_str(v, self.options.ascii_only))
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
         // [u8]
         // Similar to strings, during deserialization byte arrays can be transient,
         // owned, or borrowed.
+        if let Some(config) = self.options.hex_dump.clone() {
+            if !v.is_empty() {
+                return self.append_hex_dump(v, &config);
+            }
+        }
+
         let s = format!(
             "h\"{}\"",
             v.iter()
@@ -266,13 +589,25 @@ where
         variant: &'static str,
     ) -> Result<()> {
         // For example the` E::A` and `E::B` in `enum E { A, B }`.
-        self.append(format!("{}::{}", name, variant))
+        if self.options.bare_unit_variants {
+            self.append(variant.to_owned())
+        } else {
+            self.append(format!("{}::{}", name, variant))
+        }
     }
 
-    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<()>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
+        // the number wrappers, e.g. `Hex(255_u8)`.
+        if let Some(notation) = NumberNotation::from_struct_name(name) {
+            let previous_notation = self.number_notation.replace(notation);
+            let result = value.serialize(&mut *self);
+            self.number_notation = previous_notation;
+            return result;
+        }
+
         // For example `struct Millimeters(u8)`.
         Err(AsonError::Message(
             "Does not support \"New-Type\" style Struct.".to_owned(),
@@ -295,10 +630,8 @@ where
         self.append(")".to_owned())
     }
 
-    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
-        self.append("[".to_owned())?;
-        self.is_first_element = true;
-        self.increase_level();
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        self.enter_container("[", len)?;
         Ok(self)
     }
 
@@ -340,23 +673,19 @@ where
         Ok(self)
     }
 
-    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
         // A variably sized heterogeneous key-value pairing,
         // for example `BTreeMap<K, V>`.
         // When serializing, the length may or may not be known before
         // iterating through all the entries. When deserializing,
         // the length is determined by looking at the serialized data.
 
-        self.append("[".to_owned())?;
-        self.is_first_element = true;
-        self.increase_level();
+        self.enter_container("[", len)?;
         Ok(self)
     }
 
     fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
-        self.append("{".to_owned())?;
-        self.is_first_element = true;
-        self.increase_level();
+        self.enter_container("{", None)?;
         Ok(self)
     }
 
@@ -370,9 +699,7 @@ where
         // For example the `E::S` in `enum E { S { r: u8, g: u8, b: u8 } }`.
 
         self.append(format!("{}::{}", name, variant))?;
-        self.append("{".to_owned())?;
-        self.is_first_element = true;
-        self.increase_level();
+        self.enter_container("{", None)?;
         Ok(self)
     }
 }
@@ -388,18 +715,13 @@ where
     where
         T: ?Sized + Serialize,
     {
-        self.is_first_element = false;
-
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        value.serialize(&mut **self)
+        self.append_element_separator()?;
+        value.serialize(&mut **self)?;
+        self.append_trailing_comma()
     }
 
     fn end(self) -> Result<()> {
-        self.decrease_level();
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        self.append("]".to_owned())
+        self.leave_container("]")
     }
 }
 
@@ -424,6 +746,8 @@ where
     }
 
     fn end(self) -> Result<()> {
+        // the tuple itself is an element of its parent
+        self.is_first_element = false;
         self.append(")".to_owned())
     }
 }
@@ -468,6 +792,8 @@ where
     }
 
     fn end(self) -> Result<()> {
+        // the tuple itself is an element of its parent
+        self.is_first_element = false;
         self.append(")".to_owned())
     }
 }
@@ -483,10 +809,7 @@ where
     where
         T: ?Sized + Serialize,
     {
-        self.is_first_element = false;
-
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
+        self.append_element_separator()?;
         key.serialize(&mut **self)
     }
 
@@ -495,14 +818,16 @@ where
         T: ?Sized + Serialize,
     {
         self.append(": ".to_owned())?;
-        value.serialize(&mut **self)
+        value.serialize(&mut **self)?;
+        self.append_trailing_comma()
     }
 
     fn end(self) -> Result<()> {
-        self.decrease_level();
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        self.append("]".to_owned())
+        // the empty map is written as `[:]` to distinguish from the empty list.
+        if self.is_first_element {
+            self.append(":".to_owned())?;
+        }
+        self.leave_container("]")
     }
 }
 
@@ -517,19 +842,14 @@ where
     where
         T: ?Sized + Serialize,
     {
-        self.is_first_element = false;
-
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        self.append(format!("{}: ", key))?;
-        value.serialize(&mut **self)
+        self.append_element_separator()?;
+        self.append_key(key)?;
+        value.serialize(&mut **self)?;
+        self.append_trailing_comma()
     }
 
     fn end(self) -> Result<()> {
-        self.decrease_level();
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        self.append("}".to_owned())
+        self.leave_container("}")
     }
 }
 
@@ -544,31 +864,146 @@ where
     where
         T: ?Sized + Serialize,
     {
-        self.is_first_element = false;
-
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        self.append(format!("{}: ", key))?;
-        value.serialize(&mut **self)
+        self.append_element_separator()?;
+        self.append_key(key)?;
+        value.serialize(&mut **self)?;
+        self.append_trailing_comma()
     }
 
     fn end(self) -> Result<()> {
-        self.decrease_level();
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        self.append("}".to_owned())
+        self.leave_container("}")
+    }
+}
+
+/// Escapes the string and encloses it with double quotes, e.g.
+/// `a"b` => `"a\"b"`, it is useful for writing ASON text by hand,
+/// e.g. with templates.
+pub fn escape_string(s: &str) -> String {
+    quote_str(s, false)
+}
+
+/// Escapes the char and encloses it with single quotes, e.g. `'` => `'\''`.
+pub fn escape_char(c: char) -> String {
+    quote_char(c, false)
+}
+
+fn quote_char(v: char, ascii_only: bool) -> String {
+    let s = match v {
+        '\\' => "\\\\".to_owned(),
+        '\'' => "\\'".to_owned(),
+        '\t' => {
+            // horizontal tabulation
+            "\\t".to_owned()
+        }
+        '\r' => {
+            // carriage return, jump to the beginning of the line (CR)
+            "\\r".to_owned()
+        }
+        '\n' => {
+            // new line/line feed (LF)
+            "\\n".to_owned()
+        }
+        '\0' => {
+            // null char
+            "\\0".to_owned()
+        }
+        _ if ascii_only && !v.is_ascii() => escape_unicode(v),
+        _ => v.to_string(),
+    };
+
+    format!("'{}'", s)
+}
+
+fn quote_str(v: &str, ascii_only: bool) -> String {
+    format!(
+        "\"{}\"",
+        v.chars()
+            .map(|c| match c {
+                '\\' => "\\\\".to_owned(),
+                '"' => "\\\"".to_owned(),
+                // null char is allowed in the ASON string, it is used for represent the string resource.
+                '\0' => "\\0".to_owned(),
+                // some text editors automatically remove the tab when
+                // it is at the end of a line, so it is best to escape the tab char.
+                // therefor it should be escaped
+                '\t' => "\\t".to_owned(),
+                _ if ascii_only && !c.is_ascii() => escape_unicode(c),
+                _ => c.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join("")
+    )
+}
+
+// e.g. '文' => `\u{6587}`
+fn escape_unicode(c: char) -> String {
+    format!("\\u{{{:x}}}", c as u32)
+}
+
+// the plain decimal form of a very large or very small floating-point
+// number is too long, e.g. `1e300` has 301 digits, so these numbers
+// are written in the scientific notation, e.g. `1e300` and `1e-300`.
+fn is_extreme_magnitude(abs: f64) -> bool {
+    abs != 0.0 && !(1e-5..1e16).contains(&abs)
+}
+
+// formats the sign, the biased exponent and the mantissa (which is aligned
+// to `digits` hex digits) of a finite floating-point number as the
+// hexadecimal floating-point literal, e.g.
+//
+// - normal numbers: `0x1.921fb54442d18p+1`
+// - subnormal numbers: `0x0.0000000000001p-1022`
+// - zero: `0x0.0p+0`
+fn format_hex_float(
+    negative: bool,
+    exponent: u32,
+    mantissa: u64,
+    digits: usize,
+    bias: i32,
+) -> String {
+    let sign = if negative { "-" } else { "" };
+
+    if exponent == 0 && mantissa == 0 {
+        return format!("{}0x0.0p+0", sign);
+    }
+
+    let fraction = format!("{:0width$x}", mantissa, width = digits);
+    let fraction = match fraction.trim_end_matches('0') {
+        "" => "0",
+        trimmed => trimmed,
+    };
+
+    if exponent == 0 {
+        format!("{}0x0.{}p{:+}", sign, fraction, 1 - bias)
+    } else {
+        format!("{}0x1.{}p{:+}", sign, fraction, exponent as i32 - bias)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{
+        collections::{BTreeMap, BTreeSet, HashMap, LinkedList, VecDeque},
+        net::{IpAddr, Ipv6Addr, SocketAddr},
+        path::PathBuf,
+        rc::Rc,
+        sync::Arc,
+    };
 
     use pretty_assertions::assert_eq;
-    use serde::Serialize;
+    use serde::{Deserialize, Serialize};
     use serde_bytes::ByteBuf;
 
-    use crate::serde::ser::to_string;
+    use crate::{ast::AsonNode, parser::parse_from_str, printer::print_to_string, AsonError};
+
+    use crate::serde::{
+        de::{from_str, from_str_with_options, DeserializeOptions},
+        ser::{
+            escape_char, escape_string, to_string, to_string_with_options, to_writer, FloatFormat,
+            HexDumpConfig, SerializeOptions,
+        },
+        serde_date::Date,
+    };
 
     #[test]
     fn test_primitive_types() {
@@ -682,6 +1117,196 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_128_bit_integers() {
+        let v0 = i128::MAX;
+        let s0 = to_string(&v0).unwrap();
+        assert_eq!(s0, "170141183460469231731687303715884105727_i128");
+        assert_eq!(from_str::<i128>(&s0).unwrap(), v0);
+
+        for v in [i128::MIN, -1, 0, 1, i128::MAX] {
+            assert_eq!(from_str::<i128>(&to_string(&v).unwrap()).unwrap(), v);
+        }
+
+        for v in [0, 1, i128::MAX as u128, u128::MAX] {
+            assert_eq!(from_str::<u128>(&to_string(&v).unwrap()).unwrap(), v);
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Object {
+            mask: u128,
+            id: Option<i128>,
+        }
+
+        let v1 = Object {
+            mask: u128::MAX,
+            id: Some(i128::MIN),
+        };
+        let s1 = to_string(&v1).unwrap();
+        assert_eq!(
+            s1,
+            r#"{
+    mask: 340282366920938463463374607431768211455_u128
+    id: Option::Some(-170141183460469231731687303715884105728_i128)
+}"#
+        );
+        assert_eq!(from_str::<Object>(&s1).unwrap(), v1);
+
+        // through the AST
+        let node = parse_from_str(&s1).unwrap();
+        assert_eq!(print_to_string(&node), s1);
+        assert_eq!(node.deserialize_into::<Object>().unwrap(), v1);
+
+        // the suffix of the number in the range of `i32` can be omitted
+        let options = SerializeOptions {
+            omit_number_suffixes: true,
+            ..Default::default()
+        };
+        assert_eq!(to_string_with_options(&17_u128, &options).unwrap(), "17");
+        assert_eq!(
+            to_string_with_options(&u128::MAX, &options).unwrap(),
+            "340282366920938463463374607431768211455_u128"
+        );
+
+        // err: mismatched type
+        assert!(from_str::<u128>("17_u64").is_err());
+    }
+
+    #[test]
+    fn test_to_writer() {
+        #[derive(Serialize)]
+        struct Object {
+            name: String,
+            tags: Vec<char>,
+        }
+
+        let v0 = Object {
+            name: "文字 and 😊".to_owned(),
+            tags: vec!['a', '中'],
+        };
+
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&v0, &mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), to_string(&v0).unwrap());
+
+        // the errors of the writer are surfaced as `AsonError::Io`
+        struct FailingWriter {
+            remaining: usize,
+        }
+
+        impl std::io::Write for FailingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                if self.remaining == 0 {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::BrokenPipe,
+                        "pipe closed",
+                    ));
+                }
+                let len = buf.len().min(self.remaining);
+                self.remaining -= len;
+                Ok(len)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer = FailingWriter { remaining: 8 };
+        assert_eq!(
+            to_writer(&v0, &mut writer),
+            Err(AsonError::Io(
+                std::io::ErrorKind::BrokenPipe,
+                "pipe closed".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_float_scientific_notation() {
+        assert_eq!(to_string(&1e300_f64).unwrap(), r#"1e300"#);
+        assert_eq!(to_string(&1e-300_f64).unwrap(), r#"1e-300"#);
+        assert_eq!(to_string(&-1.5e20_f64).unwrap(), r#"-1.5e20"#);
+        assert_eq!(to_string(&f32::MAX).unwrap(), r#"3.4028235e38_f32"#);
+
+        // round-trip to the exact same bits
+        for v in [
+            1e300_f64,
+            1e-300_f64,
+            f64::MIN_POSITIVE,
+            f64::MAX,
+            -f64::MIN_POSITIVE,
+        ] {
+            let s = to_string(&v).unwrap();
+            assert_eq!(from_str::<f64>(&s).unwrap().to_bits(), v.to_bits());
+        }
+
+        for v in [f32::MAX, f32::MIN_POSITIVE, 1e-30_f32] {
+            let s = to_string(&v).unwrap();
+            assert_eq!(from_str::<f32>(&s).unwrap().to_bits(), v.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_float_format_hex_float() {
+        let options = SerializeOptions {
+            float_format: FloatFormat::HexFloat,
+            ..Default::default()
+        };
+
+        let to_hex = |v: f64| to_string_with_options(&v, &options).unwrap();
+        let to_hex_f32 = |v: f32| to_string_with_options(&v, &options).unwrap();
+
+        assert_eq!(to_hex(std::f64::consts::PI), "0x1.921fb54442d18p+1_f64");
+        assert_eq!(to_hex(std::f64::consts::E), "0x1.5bf0a8b145769p+1_f64");
+        assert_eq!(to_hex(1.0), "0x1.0p+0_f64");
+        assert_eq!(to_hex(-0.5), "-0x1.0p-1_f64");
+        assert_eq!(to_hex(0.0), "0x0.0p+0_f64");
+        assert_eq!(to_hex(-0.0), "-0x0.0p+0_f64");
+        assert_eq!(to_hex(5e-324), "0x0.0000000000001p-1022_f64");
+        assert_eq!(to_hex(f64::NAN), "NaN");
+        assert_eq!(to_hex(f64::NEG_INFINITY), "-Inf");
+
+        assert_eq!(to_hex_f32(std::f32::consts::PI), "0x1.921fb6p+1_f32");
+        assert_eq!(to_hex_f32(f32::MAX), "0x1.fffffep+127_f32");
+        assert_eq!(to_hex_f32(1e-45), "0x0.000002p-126_f32");
+        assert_eq!(to_hex_f32(f32::INFINITY), "Inf_f32");
+
+        // round-trip to the exact same bits
+        for v in [
+            std::f64::consts::PI,
+            std::f64::consts::E,
+            0.1,
+            -0.0,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            5e-324,
+            -2.5e-310,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ] {
+            let s = to_hex(v);
+            assert_eq!(from_str::<f64>(&s).unwrap().to_bits(), v.to_bits(), "{}", s);
+        }
+
+        for v in [
+            std::f32::consts::PI,
+            std::f32::consts::E,
+            -0.0,
+            f32::MAX,
+            f32::MIN_POSITIVE,
+            1e-45,
+            -3e-40,
+            f32::NEG_INFINITY,
+        ] {
+            let s = to_hex_f32(v);
+            assert_eq!(from_str::<f32>(&s).unwrap().to_bits(), v.to_bits(), "{}", s);
+        }
+
+        assert!(from_str::<f64>(&to_hex(f64::NAN)).unwrap().is_nan());
+        assert!(from_str::<f32>(&to_hex_f32(f32::NAN)).unwrap().is_nan());
+    }
+
     #[test]
     fn test_hex_byte_data() {
         let v0 = vec![11u8, 13, 17, 19];
@@ -694,30 +1319,127 @@ mod tests {
     }
 
     #[test]
-    fn test_option() {
-        let v0: Option<i32> = None;
-        assert_eq!(to_string(&v0).unwrap(), r#"Option::None"#);
+    fn test_escape_string_and_char() {
+        assert_eq!(escape_string(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(escape_string(r"C:\Windows"), r#""C:\\Windows""#);
+        assert_eq!(escape_char('\''), r"'\''");
+        assert_eq!(escape_char('\n'), r"'\n'");
+
+        for s in [
+            "",
+            "foo",
+            r#"a "quoted" text"#,
+            r"back\slash\",
+            "line 1\nline 2\n",
+            "tab\tnull\0",
+            "crlf\r\n",
+            "文字😊",
+            "'single' \"double\" \\ mixed\n\t",
+        ] {
+            assert_eq!(from_str::<String>(&escape_string(s)).unwrap(), s);
+        }
 
-        let v1: Option<i32> = Some(123);
-        assert_eq!(to_string(&v1).unwrap(), r#"Option::Some(123)"#);
+        for c in ['a', '\'', '"', '\\', '\n', '\r', '\t', '\0', '文', '😊'] {
+            assert_eq!(from_str::<char>(&escape_char(c)).unwrap(), c);
+        }
     }
 
     #[test]
-    fn test_list() {
+    fn test_hex_dump() {
+        let options = SerializeOptions {
+            hex_dump: Some(HexDumpConfig::default()),
+            ..Default::default()
+        };
+
+        let v0 = ByteBuf::from(b"Hello, ASON! /* hex */ dump!\x00\x01\x7f\xff".to_vec());
+        assert_eq!(v0.len(), 32);
+
+        let s0 = to_string_with_options(&v0, &options).unwrap();
         assert_eq!(
-            to_string(&vec![11, 13, 17, 19]).unwrap(),
-            r#"[
-    11
-    13
-    17
-    19
-]"#
+            s0,
+            r#"h"
+    /* 0000 */ 48 65 6c 6c 6f 2c 20 41 53 4f 4e 21 20 2f 2a 20 /* Hello, ASON! ..  */
+    /* 0010 */ 68 65 78 20 2a 2f 20 64 75 6d 70 21 00 01 7f ff /* hex .. dump!.... */
+""#
         );
+        assert_eq!(from_str::<ByteBuf>(&s0).unwrap(), v0);
 
+        // empty
         assert_eq!(
-            to_string(&"abc".as_bytes()).unwrap(),
-            r#"[
-    97_u8
+            to_string_with_options(&ByteBuf::new(), &options).unwrap(),
+            r#"h"""#
+        );
+
+        // within an object, without ASCII text
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Packet {
+            id: i32,
+            data: ByteBuf,
+        }
+
+        let options = SerializeOptions {
+            hex_dump: Some(HexDumpConfig {
+                bytes_per_line: 8,
+                show_ascii: false,
+            }),
+            ..Default::default()
+        };
+
+        let v1 = Packet {
+            id: 11,
+            data: ByteBuf::from((0u8..10).collect::<Vec<u8>>()),
+        };
+
+        let s1 = to_string_with_options(&v1, &options).unwrap();
+        assert_eq!(
+            s1,
+            r#"{
+    id: 11
+    data: h"
+        /* 0000 */ 00 01 02 03 04 05 06 07
+        /* 0008 */ 08 09
+    "
+}"#
+        );
+        assert_eq!(from_str::<Packet>(&s1).unwrap(), v1);
+    }
+
+    #[test]
+    fn test_option() {
+        let v0: Option<i32> = None;
+        assert_eq!(to_string(&v0).unwrap(), r#"Option::None"#);
+
+        let v1: Option<i32> = Some(123);
+        assert_eq!(to_string(&v1).unwrap(), r#"Option::Some(123)"#);
+
+        // nested options are not collapsed
+        for (v, expected) in [
+            (None, "Option::None"),
+            (Some(None), "Option::Some(Option::None)"),
+            (Some(Some(5)), "Option::Some(Option::Some(5))"),
+        ] {
+            let s = to_string(&v).unwrap();
+            assert_eq!(s, expected);
+            assert_eq!(from_str::<Option<Option<i32>>>(&s).unwrap(), v);
+        }
+    }
+
+    #[test]
+    fn test_list() {
+        assert_eq!(
+            to_string(&vec![11, 13, 17, 19]).unwrap(),
+            r#"[
+    11
+    13
+    17
+    19
+]"#
+        );
+
+        assert_eq!(
+            to_string(&"abc".as_bytes()).unwrap(),
+            r#"[
+    97_u8
     98_u8
     99_u8
 ]"#
@@ -870,6 +1592,230 @@ mod tests {
         assert!(s1.contains(r#"229: Option::Some("world")"#));
     }
 
+    #[test]
+    fn test_map_with_special_string_keys() {
+        let mut m0 = BTreeMap::<String, i32>::new();
+        m0.insert("a b".to_owned(), 1);
+        m0.insert("c:d".to_owned(), 2);
+        m0.insert("e\"f".to_owned(), 3);
+
+        let s0 = to_string(&m0).unwrap();
+        assert_eq!(
+            s0,
+            r#"[
+    "a b": 1
+    "c:d": 2
+    "e\"f": 3
+]"#
+        );
+        assert_eq!(from_str::<BTreeMap<String, i32>>(&s0).unwrap(), m0);
+    }
+
+    #[test]
+    fn test_ascii_only() {
+        let options = SerializeOptions {
+            ascii_only: true,
+            ..Default::default()
+        };
+
+        let v0 = "abc文字🍒".to_owned();
+
+        let s0 = to_string_with_options(&v0, &options).unwrap();
+        assert_eq!(s0, r#""abc\u{6587}\u{5b57}\u{1f352}""#);
+        assert_eq!(from_str::<String>(&s0).unwrap(), v0);
+
+        let s1 = to_string_with_options(&'🍒', &options).unwrap();
+        assert_eq!(s1, r#"'\u{1f352}'"#);
+        assert_eq!(from_str::<char>(&s1).unwrap(), '🍒');
+
+        // raw UTF-8 by default
+        let s2 = to_string(&v0).unwrap();
+        assert_eq!(s2, r#""abc文字🍒""#);
+        assert_eq!(from_str::<String>(&s2).unwrap(), v0);
+    }
+
+    #[test]
+    fn test_omit_number_suffixes() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Numbers {
+            a: i8,
+            b: u16,
+            c: i64,
+            d: u64,
+            e: f32,
+            f: f64,
+            g: u64,
+        }
+
+        let v0 = Numbers {
+            a: -17,
+            b: 19,
+            c: 23,
+            d: 29,
+            e: 3.5,
+            f: 1.25,
+            g: u64::MAX,
+        };
+
+        assert_eq!(
+            to_string(&v0).unwrap(),
+            r#"{
+    a: -17_i8
+    b: 19_u16
+    c: 23_i64
+    d: 29_u64
+    e: 3.5_f32
+    f: 1.25
+    g: 18446744073709551615_u64
+}"#
+        );
+
+        let options = SerializeOptions {
+            omit_number_suffixes: true,
+            ..Default::default()
+        };
+
+        // the suffix of number out of the range of `i32` is kept
+        let s1 = to_string_with_options(&v0, &options).unwrap();
+        assert_eq!(
+            s1,
+            r#"{
+    a: -17
+    b: 19
+    c: 23
+    d: 29
+    e: 3.5
+    f: 1.25
+    g: 18446744073709551615_u64
+}"#
+        );
+
+        let deserialize_options = DeserializeOptions {
+            lenient: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            from_str_with_options::<Numbers>(&s1, &deserialize_options).unwrap(),
+            v0
+        );
+
+        // err: the types of numbers are lost in strict mode
+        assert!(from_str::<Numbers>(&s1).is_err());
+
+        assert_eq!(
+            to_string_with_options(&(3_f32, f32::NAN), &options).unwrap(),
+            r#"(3.0, NaN)"#
+        );
+    }
+
+    #[test]
+    fn test_skipped_field() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Object {
+            id: i32,
+            #[serde(skip)]
+            cache: Vec<i32>,
+            name: String,
+        }
+
+        let v0 = Object {
+            id: 123,
+            cache: vec![11, 13],
+            name: "foo".to_owned(),
+        };
+
+        let s0 = to_string(&v0).unwrap();
+        assert_eq!(
+            s0,
+            r#"{
+    id: 123
+    name: "foo"
+}"#
+        );
+
+        assert_eq!(
+            from_str::<Object>(&s0).unwrap(),
+            Object {
+                id: 123,
+                cache: vec![],
+                name: "foo".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_nested_map() {
+        // string-keyed maps are always written in the Map form `[...]`,
+        // and structs in the Object form `{...}`, which is what the
+        // deserializer expects.
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Data {
+            data: HashMap<String, Vec<i32>>,
+        }
+
+        let v0 = Data {
+            data: HashMap::from([("foo".to_owned(), vec![11, 13])]),
+        };
+        let s0 = to_string(&v0).unwrap();
+        assert_eq!(
+            s0,
+            r#"{
+    data: [
+        "foo": [
+            11
+            13
+        ]
+    ]
+}"#
+        );
+        assert_eq!(from_str::<Data>(&s0).unwrap(), v0);
+
+        let v1 = Data {
+            data: HashMap::from([
+                ("foo".to_owned(), vec![11, 13]),
+                ("bar".to_owned(), vec![]),
+                ("baz".to_owned(), vec![17]),
+            ]),
+        };
+        assert_eq!(from_str::<Data>(&to_string(&v1).unwrap()).unwrap(), v1);
+
+        let v2 = BTreeMap::from([
+            ("foo".to_owned(), BTreeMap::from([("a".to_owned(), 11)])),
+            (
+                "bar".to_owned(),
+                BTreeMap::from([("b".to_owned(), 13), ("c".to_owned(), 17)]),
+            ),
+        ]);
+        let s2 = to_string(&v2).unwrap();
+        assert_eq!(
+            s2,
+            r#"[
+    "bar": [
+        "b": 13
+        "c": 17
+    ]
+    "foo": [
+        "a": 11
+    ]
+]"#
+        );
+        assert_eq!(
+            from_str::<BTreeMap<String, BTreeMap<String, i32>>>(&s2).unwrap(),
+            v2
+        );
+
+        let v3 = HashMap::from([
+            ("foo".to_owned(), HashMap::from([("a".to_owned(), 11)])),
+            ("bar".to_owned(), HashMap::new()),
+        ]);
+        assert_eq!(
+            from_str::<HashMap<String, HashMap<String, i32>>>(&to_string(&v3).unwrap()).unwrap(),
+            v3
+        );
+    }
+
     #[test]
     fn test_variant_with_single_value() {
         #[derive(Serialize)]
@@ -1132,4 +2078,742 @@ mod tests {
 ]"#
         );
     }
+
+    #[test]
+    fn test_path() {
+        // Windows-style path
+        let p0 = PathBuf::from(r#"C:\Users\x"#);
+        let s0 = to_string(&p0).unwrap();
+        assert_eq!(s0, r#""C:\\Users\\x""#);
+        assert_eq!(from_str::<PathBuf>(&s0).unwrap(), p0);
+
+        // Unix-style path
+        let p1 = PathBuf::from("/home/yang/hello world.txt");
+        let s1 = to_string(&p1).unwrap();
+        assert_eq!(s1, r#""/home/yang/hello world.txt""#);
+        assert_eq!(from_str::<PathBuf>(&s1).unwrap(), p1);
+
+        // paths as map keys, note that the Windows path contains a colon
+        let mut m0 = BTreeMap::<PathBuf, i32>::new();
+        m0.insert(PathBuf::from(r#"C:\Windows"#), 11);
+        m0.insert(PathBuf::from("/usr/bin"), 13);
+
+        let s2 = to_string(&m0).unwrap();
+        assert_eq!(
+            s2,
+            r#"[
+    "/usr/bin": 13
+    "C:\\Windows": 11
+]"#
+        );
+        assert_eq!(from_str::<BTreeMap<PathBuf, i32>>(&s2).unwrap(), m0);
+    }
+
+    #[test]
+    fn test_describe_options() {
+        assert!(SerializeOptions::default().describe().is_empty());
+
+        let options = SerializeOptions {
+            ascii_only: true,
+            omit_number_suffixes: true,
+            float_format: FloatFormat::HexFloat,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            options.describe(),
+            vec!["ascii_only", "omit_number_suffixes", "float_format"]
+        );
+    }
+
+    #[test]
+    fn test_layout_options() {
+        let v0 = vec![1, 2, 3];
+
+        let inline_options = SerializeOptions {
+            inline_collections_below: 4,
+            ..Default::default()
+        };
+        assert_eq!(
+            to_string_with_options(&v0, &inline_options).unwrap(),
+            "[1, 2, 3]"
+        );
+
+        let expanded_options = SerializeOptions {
+            inline_collections_below: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            to_string_with_options(&v0, &expanded_options).unwrap(),
+            "[\n    1\n    2\n    3\n]"
+        );
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Object {
+            name: String,
+            points: Vec<Point>,
+            scores: BTreeMap<String, Vec<i32>>,
+            tags: Vec<String>,
+        }
+
+        let v1 = Object {
+            name: "foo".to_owned(),
+            points: vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }],
+            scores: BTreeMap::from([("a".to_owned(), vec![11, 13]), ("b".to_owned(), vec![])]),
+            tags: vec!["x".to_owned(), "y".to_owned(), "z".to_owned()],
+        };
+
+        let options = SerializeOptions {
+            indent: Some("\t".to_owned()),
+            inline_collections_below: 3,
+            trailing_comma: true,
+            ..Default::default()
+        };
+
+        let s1 = to_string_with_options(&v1, &options).unwrap();
+        assert_eq!(
+            s1,
+            "{
+\tname: \"foo\",
+\tpoints: [{x: 1, y: 2}, {x: 3, y: 4}],
+\tscores: [\"a\": [11, 13], \"b\": []],
+\ttags: [
+\t\t\"x\",
+\t\t\"y\",
+\t\t\"z\",
+\t],
+}"
+        );
+
+        assert_eq!(from_str::<Object>(&s1).unwrap(), v1);
+
+        // the default layout is unchanged
+        assert_eq!(to_string(&v0).unwrap(), "[\n    1\n    2\n    3\n]");
+        assert_eq!(
+            to_string(&v1).unwrap(),
+            r#"{
+    name: "foo"
+    points: [
+        {
+            x: 1
+            y: 2
+        }
+        {
+            x: 3
+            y: 4
+        }
+    ]
+    scores: [
+        "a": [
+            11
+            13
+        ]
+        "b": []
+    ]
+    tags: [
+        "x"
+        "y"
+        "z"
+    ]
+}"#
+        );
+        assert_eq!(
+            options.describe(),
+            vec!["indent", "inline_collections_below", "trailing_comma"]
+        );
+
+        // the indent which can not be parsed
+        for indent in ["--", " \n", "\u{a0}"] {
+            let options = SerializeOptions {
+                indent: Some(indent.to_owned()),
+                ..Default::default()
+            };
+            assert_eq!(
+                to_string_with_options(&v0, &options),
+                Err(AsonError::Message(
+                    "The indent can consist of spaces and tabs only.".to_owned()
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn test_shared_strings() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Object {
+            boxed: Box<str>,
+            rc: Rc<str>,
+            arc: Arc<str>,
+        }
+
+        let v0 = Object {
+            boxed: "foo".into(),
+            rc: "文字".into(),
+            arc: "a\"b".into(),
+        };
+
+        let s0 = to_string(&v0).unwrap();
+        assert_eq!(
+            s0,
+            r#"{
+    boxed: "foo"
+    rc: "文字"
+    arc: "a\"b"
+}"#
+        );
+        assert_eq!(from_str::<Object>(&s0).unwrap(), v0);
+
+        let v1: Vec<Arc<str>> = vec!["foo".into(), "bar".into()];
+        let s1 = to_string(&v1).unwrap();
+        assert_eq!(from_str::<Vec<Arc<str>>>(&s1).unwrap(), v1);
+    }
+
+    #[test]
+    fn test_non_identifier_keys() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Object {
+            #[serde(rename = "has space")]
+            has_space: i32,
+            #[serde(rename = "true")]
+            keyword: bool,
+            #[serde(rename = "1st")]
+            first: String,
+            plain: char,
+        }
+
+        let v0 = Object {
+            has_space: 11,
+            keyword: true,
+            first: "foo".to_owned(),
+            plain: 'a',
+        };
+
+        let s0 = to_string(&v0).unwrap();
+        assert_eq!(
+            s0,
+            r#"{
+    "has space": 11
+    "true": true
+    "1st": "foo"
+    plain: 'a'
+}"#
+        );
+        assert_eq!(from_str::<Object>(&s0).unwrap(), v0);
+        assert_eq!(print_to_string(&parse_from_str(&s0).unwrap()), s0);
+
+        // the map is serialized in the map form
+        let v1 = BTreeMap::from([("has space".to_owned(), 11), ("key".to_owned(), 13)]);
+        let s1 = to_string(&v1).unwrap();
+        assert_eq!(
+            s1,
+            r#"[
+    "has space": 11
+    "key": 13
+]"#
+        );
+        assert_eq!(from_str::<BTreeMap<String, i32>>(&s1).unwrap(), v1);
+    }
+
+    #[test]
+    fn test_bare_unit_variants() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        enum Color {
+            Red,
+            Green,
+            Rgb(u8, u8, u8),
+        }
+
+        let v0 = vec![Color::Red, Color::Rgb(1, 2, 3), Color::Green];
+
+        let s0 = to_string(&v0).unwrap();
+        assert_eq!(
+            s0,
+            r#"[
+    Color::Red
+    Color::Rgb(1_u8, 2_u8, 3_u8)
+    Color::Green
+]"#
+        );
+
+        let options = SerializeOptions {
+            bare_unit_variants: true,
+            ..Default::default()
+        };
+
+        let s1 = to_string_with_options(&v0, &options).unwrap();
+        assert_eq!(
+            s1,
+            r#"[
+    Red
+    Color::Rgb(1_u8, 2_u8, 3_u8)
+    Green
+]"#
+        );
+
+        let de_options = DeserializeOptions {
+            allow_bare_variant: true,
+            ..Default::default()
+        };
+
+        // both forms are deserialized to the same value
+        assert_eq!(
+            from_str_with_options::<Vec<Color>>(&s0, &de_options).unwrap(),
+            v0
+        );
+        assert_eq!(
+            from_str_with_options::<Vec<Color>>(&s1, &de_options).unwrap(),
+            v0
+        );
+
+        // the bare names require the `allow_bare_variant` option
+        assert!(from_str::<Vec<Color>>(&s1).is_err());
+    }
+
+    #[test]
+    fn test_collect_str() {
+        struct Label(&'static str, u32);
+
+        impl std::fmt::Display for Label {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "\"{}\"\t#{}", self.0, self.1)
+            }
+        }
+
+        impl Serialize for Label {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.collect_str(self)
+            }
+        }
+
+        let s0 = to_string(&Label("文字", 11)).unwrap();
+        assert_eq!(s0, r#""\"文字\"\t#11""#);
+        assert_eq!(from_str::<String>(&s0).unwrap(), "\"文字\"\t#11");
+
+        let v1: Ipv6Addr = "2001:db8::ff00:42:8329".parse().unwrap();
+        let s1 = to_string(&v1).unwrap();
+        assert_eq!(s1, r#""2001:db8::ff00:42:8329""#);
+        assert_eq!(from_str::<Ipv6Addr>(&s1).unwrap(), v1);
+
+        let v2: SocketAddr = "[::1]:8080".parse().unwrap();
+        let s2 = to_string(&v2).unwrap();
+        assert_eq!(s2, r#""[::1]:8080""#);
+        assert_eq!(from_str::<SocketAddr>(&s2).unwrap(), v2);
+
+        // the compact form of `IpAddr` is a variant with the octets,
+        // e.g. `IpAddr::V4([127, 0, 0, 1])`.
+        let v3: IpAddr = "127.0.0.1".parse().unwrap();
+        let s3 = to_string(&v3).unwrap();
+        assert_eq!(s3, r#""127.0.0.1""#);
+        assert_eq!(from_str::<IpAddr>(&s3).unwrap(), v3);
+        assert_eq!(
+            parse_from_str(&s3)
+                .unwrap()
+                .deserialize_into::<IpAddr>()
+                .unwrap(),
+            v3
+        );
+    }
+
+    #[test]
+    fn test_empty_collections() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Empty {}
+
+        assert_eq!(to_string(&Vec::<i32>::new()).unwrap(), "[]");
+        assert_eq!(to_string(&Empty {}).unwrap(), "{}");
+        assert_eq!(to_string(&BTreeMap::<i32, String>::new()).unwrap(), "[:]");
+        assert_eq!(
+            parse_from_str(&to_string(&HashMap::<String, i32>::new()).unwrap()).unwrap(),
+            AsonNode::Map(vec![])
+        );
+
+        // empty collections are always inline, even when they are nested
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Object {
+            list: Vec<i32>,
+            object: Empty,
+            map: BTreeMap<i32, String>,
+            nested: Vec<Vec<i32>>,
+        }
+
+        let v0 = Object {
+            list: vec![],
+            object: Empty {},
+            map: BTreeMap::new(),
+            nested: vec![vec![], vec![11]],
+        };
+
+        let s0 = to_string(&v0).unwrap();
+        assert_eq!(
+            s0,
+            r#"{
+    list: []
+    object: {}
+    map: [:]
+    nested: [
+        []
+        [
+            11
+        ]
+    ]
+}"#
+        );
+        assert_eq!(from_str::<Object>(&s0).unwrap(), v0);
+    }
+
+    #[test]
+    fn test_empty_struct_and_variant() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Empty {}
+
+        let s0 = to_string(&Empty {}).unwrap();
+        assert_eq!(s0, "{}");
+        assert_eq!(from_str::<Empty>(&s0).unwrap(), Empty {});
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        enum Shape {
+            Unit {},
+            Pair(),
+            Rect { width: i32, height: i32 },
+        }
+
+        let s1 = to_string(&Shape::Unit {}).unwrap();
+        assert_eq!(s1, "Shape::Unit{}");
+        assert_eq!(from_str::<Shape>(&s1).unwrap(), Shape::Unit {});
+
+        let s2 = to_string(&Shape::Pair()).unwrap();
+        assert_eq!(s2, "Shape::Pair()");
+        assert_eq!(from_str::<Shape>(&s2).unwrap(), Shape::Pair());
+
+        // empty composite values nested in other values
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Object {
+            empty: Empty,
+            shapes: Vec<Shape>,
+            items: Vec<i32>,
+            id: i32,
+        }
+
+        let v3 = Object {
+            empty: Empty {},
+            shapes: vec![
+                Shape::Unit {},
+                Shape::Rect {
+                    width: 11,
+                    height: 13,
+                },
+            ],
+            items: vec![],
+            id: 17,
+        };
+
+        let s3 = to_string(&v3).unwrap();
+        assert_eq!(
+            s3,
+            r#"{
+    empty: {}
+    shapes: [
+        Shape::Unit{}
+        Shape::Rect{
+            width: 11
+            height: 13
+        }
+    ]
+    items: []
+    id: 17
+}"#
+        );
+        assert_eq!(from_str::<Object>(&s3).unwrap(), v3);
+    }
+
+    #[test]
+    fn test_char_as_string() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Object {
+            c: char,
+            s: String,
+        }
+
+        let v0 = Object {
+            c: '\'',
+            s: "'".to_owned(),
+        };
+
+        // default
+        let s0 = to_string(&v0).unwrap();
+        assert_eq!(
+            s0,
+            r#"{
+    c: '\''
+    s: "'"
+}"#
+        );
+        assert_eq!(from_str::<Object>(&s0).unwrap(), v0);
+
+        // char as string
+        let options = SerializeOptions {
+            char_as_string: true,
+            ..Default::default()
+        };
+
+        assert_eq!(to_string_with_options(&'a', &options).unwrap(), r#""a""#);
+        assert_eq!(to_string_with_options(&'"', &options).unwrap(), r#""\"""#);
+        assert_eq!(to_string_with_options(&'🍒', &options).unwrap(), r#""🍒""#);
+
+        let s1 = to_string_with_options(&v0, &options).unwrap();
+        assert_eq!(
+            s1,
+            r#"{
+    c: "'"
+    s: "'"
+}"#
+        );
+
+        // the one-character string is accepted by `char` under the lenient mode
+        assert!(from_str::<Object>(&s1).is_err());
+
+        let lenient = DeserializeOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        assert_eq!(from_str_with_options::<Object>(&s1, &lenient).unwrap(), v0);
+    }
+
+    fn assert_round_trip<T>(v: &T)
+    where
+        T: Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+    {
+        let s = to_string(v).unwrap();
+        assert_eq!(&from_str::<T>(&s).unwrap(), v, "round-trip of: {}", s);
+    }
+
+    #[test]
+    fn test_round_trip_primitive_types() {
+        // the bare integer defaults to `i32` and the bare floating-point
+        // number defaults to `f64`, so the other types rely on the type suffixes.
+        assert_round_trip(&i8::MIN);
+        assert_round_trip(&i8::MAX);
+        assert_round_trip(&u8::MAX);
+        assert_round_trip(&i16::MIN);
+        assert_round_trip(&u16::MAX);
+        assert_round_trip(&i32::MIN);
+        assert_round_trip(&i32::MAX);
+        assert_round_trip(&u32::MAX);
+        assert_round_trip(&i64::MIN);
+        assert_round_trip(&i64::MAX);
+        assert_round_trip(&u64::MAX);
+        assert_round_trip(&0_u8);
+        assert_round_trip(&-1_i8);
+
+        assert_round_trip(&0.0_f32);
+        assert_round_trip(&1.5_f32);
+        assert_round_trip(&-3.25_f32);
+        assert_round_trip(&f32::MAX);
+        assert_round_trip(&f32::MIN_POSITIVE);
+        assert_round_trip(&f32::INFINITY);
+        assert_round_trip(&f32::NEG_INFINITY);
+        assert_round_trip(&0.0_f64);
+        assert_round_trip(&-0.1_f64);
+        assert_round_trip(&std::f64::consts::PI);
+        assert_round_trip(&f64::MAX);
+        assert_round_trip(&f64::MIN_POSITIVE);
+        assert_round_trip(&f64::INFINITY);
+        assert_round_trip(&f64::NEG_INFINITY);
+        assert_round_trip(&1e20_f64);
+        assert_round_trip(&1e-20_f64);
+
+        // NaN is not equal to itself
+        assert!(from_str::<f32>(&to_string(&f32::NAN).unwrap())
+            .unwrap()
+            .is_nan());
+        assert!(from_str::<f64>(&to_string(&f64::NAN).unwrap())
+            .unwrap()
+            .is_nan());
+
+        assert_round_trip(&true);
+        assert_round_trip(&false);
+
+        assert_round_trip(&'a');
+        assert_round_trip(&'\'');
+        assert_round_trip(&'\\');
+        assert_round_trip(&'\n');
+        assert_round_trip(&'\0');
+        assert_round_trip(&'文');
+        assert_round_trip(&'😊');
+
+        assert_round_trip(&String::new());
+        assert_round_trip(&"abc文字😊".to_owned());
+        assert_round_trip(&"\"foo\" 'bar' \\ \t\r\n\0".to_owned());
+        assert_round_trip(&"line 1\nline 2\r\n".to_owned());
+
+        assert_round_trip(&ByteBuf::from(vec![]));
+        assert_round_trip(&ByteBuf::from(vec![0, 1, 0x7f, 0x80, 0xff]));
+
+        assert_round_trip(&Date::from_rfc3339("2024-06-26T16:38:50+08:00").unwrap());
+    }
+
+    #[test]
+    fn test_round_trip_collections() {
+        assert_round_trip(&Vec::<i8>::new());
+        assert_round_trip(&vec![11_i8, -13]);
+        assert_round_trip(&vec![11_u8, 13]);
+        assert_round_trip(&vec![1.5_f32, 2.0]);
+        assert_round_trip(&vec!["foo".to_owned(), "bar".to_owned()]);
+        assert_round_trip(&vec![vec![1_i64], vec![], vec![2, 3]]);
+
+        assert_round_trip(&(11_i8, 13_u16, 1.5_f32, 'a', "foo".to_owned(), true));
+        assert_round_trip(&((1_u8, 2_u8), vec![(3_i16, 4_u64)]));
+        assert_round_trip(&[1_u32, 2, 3, 4]);
+
+        assert_round_trip(&Some(11_i8));
+        assert_round_trip(&None::<i8>);
+        assert_round_trip(&Some(Some(1.5_f32)));
+        assert_round_trip(&Some(None::<f32>));
+        assert_round_trip(&vec![Some(1_u8), None]);
+
+        assert_round_trip(&BTreeMap::<u8, i8>::new());
+        assert_round_trip(&BTreeMap::from([(1_u8, -1_i8), (2, -2)]));
+        assert_round_trip(&BTreeMap::from([
+            ("foo".to_owned(), vec![1.5_f32]),
+            ("bar".to_owned(), vec![]),
+        ]));
+        assert_round_trip(&HashMap::from([('a', 11_u64), ('b', 13)]));
+        assert_round_trip(&HashMap::from([((1_i8, 2_i8), "foo".to_owned())]));
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Empty {}
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Inner {
+            id: u16,
+            ratio: f32,
+        }
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Object {
+            small: i8,
+            byte: u8,
+            half: f32,
+            big: u64,
+            name: String,
+            data: ByteBuf,
+            inner: Inner,
+            empty: Empty,
+            inners: Vec<Inner>,
+            lookup: BTreeMap<String, Inner>,
+            pair: (i16, Option<Inner>),
+        }
+
+        assert_round_trip(&Empty {});
+        assert_round_trip(&Object {
+            small: -128,
+            byte: 255,
+            half: 0.5,
+            big: u64::MAX,
+            name: "foo".to_owned(),
+            data: ByteBuf::from(vec![1, 2, 3]),
+            inner: Inner { id: 7, ratio: 1.5 },
+            empty: Empty {},
+            inners: vec![Inner { id: 1, ratio: 0.0 }, Inner { id: 2, ratio: -1.0 }],
+            lookup: BTreeMap::from([("a".to_owned(), Inner { id: 3, ratio: 2.5 })]),
+            pair: (-1, Some(Inner { id: 4, ratio: 3.5 })),
+        });
+    }
+
+    #[test]
+    fn test_round_trip_sequence_types() {
+        let v0 = VecDeque::from([17, 11, 13, -19]);
+        let s0 = to_string(&v0).unwrap();
+        assert_eq!(
+            s0,
+            r#"[
+    17
+    11
+    13
+    -19
+]"#
+        );
+        assert_eq!(from_str::<VecDeque<i32>>(&s0).unwrap(), v0);
+
+        let v1 = LinkedList::from(["foo".to_owned(), "bar".to_owned(), "baz".to_owned()]);
+        let s1 = to_string(&v1).unwrap();
+        assert_eq!(
+            s1,
+            r#"[
+    "foo"
+    "bar"
+    "baz"
+]"#
+        );
+        assert_eq!(from_str::<LinkedList<String>>(&s1).unwrap(), v1);
+
+        assert_round_trip(&VecDeque::<i32>::new());
+        assert_round_trip(&LinkedList::<String>::new());
+        assert_round_trip(&VecDeque::from([
+            LinkedList::from([1_u8, 2]),
+            LinkedList::new(),
+        ]));
+        assert_round_trip(&BTreeSet::from(["foo".to_owned(), "bar".to_owned()]));
+
+        // the sequence types accept the same text
+        assert_eq!(
+            from_str::<LinkedList<i32>>("[1, 2, 3]").unwrap(),
+            from_str::<VecDeque<i32>>("[1, 2, 3]")
+                .unwrap()
+                .into_iter()
+                .collect::<LinkedList<i32>>()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_variants() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        enum Shape {
+            Unit,
+            EmptyObject {},
+            EmptyTuple(),
+            Circle(f32),
+            Bytes(u8),
+            Point(i8, u8),
+            Rect { width: u16, height: f32 },
+            Nested(Box<Shape>),
+            Items(Vec<Shape>),
+        }
+
+        assert_round_trip(&Shape::Unit);
+        assert_round_trip(&Shape::EmptyObject {});
+        assert_round_trip(&Shape::EmptyTuple());
+        assert_round_trip(&Shape::Circle(1.5));
+        assert_round_trip(&Shape::Bytes(255));
+        assert_round_trip(&Shape::Point(-1, 1));
+        assert_round_trip(&Shape::Rect {
+            width: 11,
+            height: 13.5,
+        });
+        assert_round_trip(&Shape::Nested(Box::new(Shape::Circle(2.0))));
+        assert_round_trip(&Shape::Items(vec![
+            Shape::Unit,
+            Shape::Point(1, 2),
+            Shape::Items(vec![]),
+        ]));
+
+        assert_round_trip(&vec![Some(Shape::Unit), None]);
+        assert_round_trip(&BTreeMap::from([(1_i8, Shape::Circle(0.5))]));
+        assert_round_trip(&(
+            Shape::Bytes(1),
+            Some(Shape::Rect {
+                width: 1,
+                height: 2.0,
+            }),
+        ));
+    }
 }
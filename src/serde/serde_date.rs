@@ -16,11 +16,14 @@
 //! While the date value remains serialized as a plain string, encasing it within
 //! a variant allows for proper deserialization into `chrono::DateTime` rather
 //! than String.
+//!
+//! The `Date` is ordered by the instant (regardless of the time zone offset),
+//! so it can also be the key of maps, e.g. `BTreeMap<Date, i32>`.
 
 use chrono::{DateTime, FixedOffset, ParseResult};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Serialize, Deserialize)]
 pub enum Date {
     Rfc3339(DateTime<FixedOffset>),
 }
@@ -37,6 +40,8 @@ impl Date {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use pretty_assertions::assert_eq;
 
     use crate::serde::{de::from_str, ser::to_string, serde_date::Date};
@@ -65,4 +70,23 @@ mod tests {
             Date::from_rfc3339("2024-06-26T16:38:50Z").unwrap()
         );
     }
+
+    #[test]
+    fn test_map_with_date_keys() {
+        let m0 = BTreeMap::from([
+            (Date::from_rfc3339("2024-06-26T16:38:50+08:00").unwrap(), 11),
+            (Date::from_rfc3339("2024-12-31T23:59:59Z").unwrap(), 13),
+        ]);
+
+        let s0 = to_string(&m0).unwrap();
+        assert_eq!(
+            s0,
+            r#"[
+    Date::Rfc3339("2024-06-26T16:38:50+08:00"): 11
+    Date::Rfc3339("2024-12-31T23:59:59Z"): 13
+]"#
+        );
+
+        assert_eq!(from_str::<BTreeMap<Date, i32>>(&s0).unwrap(), m0);
+    }
 }
@@ -0,0 +1,99 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Helpers for testing ASON documents, available with the `testutil` feature.
+
+use crate::{
+    ast::AsonNode, parser::parse_from_str, token::Token, tokens::normalize_tokens, AsonError,
+};
+
+/// Returns the normalized tokens (without location) of the document,
+/// panics if the document can not be tokenized.
+pub fn tokenize(s: &str) -> Vec<Token> {
+    match normalize_tokens(s) {
+        Ok(tokens) => tokens
+            .into_iter()
+            .map(|token_with_range| token_with_range.token)
+            .collect(),
+        Err(e) => panic!("Failed to tokenize {:?}: {:?}", s, e),
+    }
+}
+
+/// Parses the document and returns the root node,
+/// panics if the document can not be parsed.
+pub fn assert_parses(s: &str) -> AsonNode {
+    match parse_from_str(s) {
+        Ok(node) => node,
+        Err(e) => panic!("Failed to parse {:?}: {:?}", s, e),
+    }
+}
+
+/// Asserts that parsing the document fails at the specified position.
+///
+/// The `line` and `column` start from 0, the same as `Location`.
+pub fn assert_parse_error_at(s: &str, line: usize, column: usize) {
+    match parse_from_str(s) {
+        Ok(node) => panic!("Expect an error when parsing {:?}, got {:?}", s, node),
+        Err(AsonError::MessageWithLocation(message, location)) => {
+            assert!(
+                location.line == line && location.column == column,
+                "Expect the error of parsing {:?} at line {}, column {}, got {:?} at line {}, column {}.",
+                s,
+                line,
+                column,
+                message,
+                location.line,
+                location.column
+            );
+        }
+        Err(e) => panic!(
+            "Expect an error with location when parsing {:?}, got {:?}",
+            s, e
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::{AsonNode, Number},
+        token::{NumberToken, Token},
+    };
+
+    use super::{assert_parse_error_at, assert_parses, tokenize};
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(
+            tokenize("[11,\n13]"),
+            vec![
+                Token::LeftBracket,
+                Token::Number(NumberToken::I32(11)),
+                Token::Comma,
+                Token::Number(NumberToken::I32(13)),
+                Token::RightBracket,
+            ]
+        );
+
+        assert_eq!(
+            assert_parses("(11, 13)"),
+            AsonNode::Tuple(vec![
+                AsonNode::Number(Number::I32(11)),
+                AsonNode::Number(Number::I32(13)),
+            ])
+        );
+
+        assert_parse_error_at("[11, 13\n17 19]", 1, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_parse_error_at_wrong_position() {
+        assert_parse_error_at("[11, 13\n17 19]", 0, 0);
+    }
+}
@@ -10,7 +10,7 @@ use chrono::{DateTime, FixedOffset};
 
 use crate::location::Location;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     // includes `\n` and `\r\n`
     NewLine,
@@ -39,6 +39,14 @@ pub enum Token {
     // `-`
     Minus,
 
+    // `@`
+    // leads an extension literal, e.g. `@name(...)`
+    At,
+
+    // the custom separator between top-level documents, e.g. `;`,
+    // see `LexerOptions::document_separator`.
+    DocumentSeparator,
+
     // [a-zA-Z0-9_] and '\u{a0}' - '\u{d7ff}' and '\u{e000}' - '\u{10ffff}'
     // used for object field/key name
     Identifier(String),
@@ -57,14 +65,87 @@ pub enum Token {
 
     Number(NumberToken),
     Char(char),
-    String(String),
+    String(String, StringStyle),
     Date(DateTime<FixedOffset>),
     HexByteData(Vec<u8>),
 
     Comment(Comment),
 }
 
-#[derive(Debug, PartialEq)]
+/// The literal style of `Token::String`, the value of the token is the same
+/// for all styles, but the consumers may treat them differently, e.g.
+/// the option `trim_strings` of deserializer leaves the auto-trimmed
+/// strings alone.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StringStyle {
+    /// `"..."`, which may contain escape chars.
+    Normal,
+
+    /// `r"..."` and `r#"..."#`.
+    Raw,
+
+    /// `"""..."""`.
+    AutoTrimmed,
+}
+
+/// The kind of a `Token` without its value, e.g. `TokenTag::String`
+/// for the `Token::String("foo")`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum TokenTag {
+    NewLine,
+    Comma,
+    Colon,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    LeftParen,
+    RightParen,
+    Plus,
+    Minus,
+    At,
+    DocumentSeparator,
+    Identifier,
+    Boolean,
+    Variant,
+    Number,
+    Char,
+    String,
+    Date,
+    HexByteData,
+    Comment,
+}
+
+impl Token {
+    pub fn tag(&self) -> TokenTag {
+        match self {
+            Token::NewLine => TokenTag::NewLine,
+            Token::Comma => TokenTag::Comma,
+            Token::Colon => TokenTag::Colon,
+            Token::LeftBrace => TokenTag::LeftBrace,
+            Token::RightBrace => TokenTag::RightBrace,
+            Token::LeftBracket => TokenTag::LeftBracket,
+            Token::RightBracket => TokenTag::RightBracket,
+            Token::LeftParen => TokenTag::LeftParen,
+            Token::RightParen => TokenTag::RightParen,
+            Token::Plus => TokenTag::Plus,
+            Token::Minus => TokenTag::Minus,
+            Token::At => TokenTag::At,
+            Token::DocumentSeparator => TokenTag::DocumentSeparator,
+            Token::Identifier(_) => TokenTag::Identifier,
+            Token::Boolean(_) => TokenTag::Boolean,
+            Token::Variant(_, _) => TokenTag::Variant,
+            Token::Number(_) => TokenTag::Number,
+            Token::Char(_) => TokenTag::Char,
+            Token::String(..) => TokenTag::String,
+            Token::Date(_) => TokenTag::Date,
+            Token::HexByteData(_) => TokenTag::HexByteData,
+            Token::Comment(_) => TokenTag::Comment,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum NumberToken {
     // it is possible for literal to overflow for signed numbers,
     // such as `-128`, which consists of a negative/minus sign
@@ -80,30 +161,113 @@ pub enum NumberToken {
     U32(u32),
     I64(u64),
     U64(u64),
+    I128(u128),
+    U128(u128),
     F32(f32),
     F64(f64),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Comment {
-    // `//...`
+    // `//...` (or `#...` when the `CommentStyle::Hash` is enabled),
+    // the text does not include the opener.
     // note that the trailing '\n' or '\r\n' does not belong to line comment
-    Line(String),
+    Line(String, LineCommentOpener),
 
     // `/*...*/`
     Block(String),
 }
 
-#[derive(Debug, PartialEq)]
+/// The opener of a line comment, it is kept so that the comment
+/// can be printed back as it is written.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LineCommentOpener {
+    /// `//`
+    DoubleSlash,
+
+    /// `#`, when the `CommentStyle::Hash` is enabled.
+    Hash,
+
+    /// `#!`, the shebang line, when the `allow_shebang` is enabled.
+    Shebang,
+
+    /// `---`, the document marker line, when the `allow_document_marker` is enabled.
+    DocumentMarker,
+}
+
+impl LineCommentOpener {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineCommentOpener::DoubleSlash => "//",
+            LineCommentOpener::Hash => "#",
+            LineCommentOpener::Shebang => "#!",
+            LineCommentOpener::DocumentMarker => "---",
+        }
+    }
+}
+
+/// A line comment which starts with the directive prefix, e.g.
+/// `//! include: "other.ason"`, see `parse_with_directives`.
+///
+/// The directives are only collected, not executed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Directive {
+    /// The text after the prefix with the surrounding whitespaces
+    /// removed, e.g. `include: "other.ason"`.
+    pub text: String,
+
+    /// The location of the whole comment.
+    pub location: Location,
+}
+
+/// The style of line comments.
+///
+/// The block comment `/* ... */` is always available.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum CommentStyle {
+    /// `// ...`
+    #[default]
+    DoubleSlash,
+
+    /// `# ...`, the shell/TOML style.
+    /// When lexing, the `// ...` is still accepted.
+    Hash,
+}
+
+/// The type of the integer literals without type suffix, e.g. `123`.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum IntegerType {
+    #[default]
+    I32,
+
+    /// Useful for documents containing large integers,
+    /// e.g. `3000000000` overflows `i32`.
+    I64,
+}
+
+/// The type of the floating-point literals without type suffix,
+/// e.g. `1.5`, `NaN` and `Inf`.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum FloatType {
+    #[default]
+    F64,
+
+    /// Useful for the embedded targets which prefer `f32`.
+    F32,
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum NumberType {
     I8,
     I16,
     I32,
     I64,
+    I128,
     U8,
     U16,
     U32,
     U64,
+    U128,
     F32,
     F64,
 }
@@ -115,10 +279,12 @@ impl NumberType {
             "i16" => NumberType::I16,
             "i32" => NumberType::I32,
             "i64" => NumberType::I64,
+            "i128" => NumberType::I128,
             "u8" => NumberType::U8,
             "u16" => NumberType::U16,
             "u32" => NumberType::U32,
             "u64" => NumberType::U64,
+            "u128" => NumberType::U128,
             "f32" => NumberType::F32,
             "f64" => NumberType::F64,
             _ => {
@@ -137,17 +303,19 @@ impl Display for NumberType {
             NumberType::I16 => write!(f, "i16"),
             NumberType::I32 => write!(f, "i32"),
             NumberType::I64 => write!(f, "i64"),
+            NumberType::I128 => write!(f, "i128"),
             NumberType::U8 => write!(f, "u8"),
             NumberType::U16 => write!(f, "u16"),
             NumberType::U32 => write!(f, "u32"),
             NumberType::U64 => write!(f, "u64"),
+            NumberType::U128 => write!(f, "u128"),
             NumberType::F32 => write!(f, "f32"),
             NumberType::F64 => write!(f, "f64"),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct TokenWithRange {
     pub token: Token,
     pub range: Location,
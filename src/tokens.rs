@@ -0,0 +1,378 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::io::Read;
+
+use crate::{
+    charstream::CharStream,
+    charwithposition::CharsWithPositionIter,
+    lexer::{Lexer, LEXER_PEEK_CHAR_MAX_COUNT},
+    location::Location,
+    normalizer::{
+        ClearTokenIter, NormalizedTokenIter, TrimmedTokenIter, NORMALIZER_PEEK_TOKEN_MAX_COUNT,
+    },
+    peekableiter::PeekableIter,
+    token::{Token, TokenTag, TokenWithRange},
+    AsonError,
+};
+
+/// The normalized tokens of a document.
+///
+/// Tokenize the document once and then pass the `Tokens` to
+/// `parse_from_tokens` and/or `from_tokens` as many times as needed,
+/// the source text is not lexed again.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Tokens {
+    tokens: Vec<TokenWithRange>,
+}
+
+impl Tokens {
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Returns an iterator that yields the tokens in the form
+    /// that the parser and deserializer consume.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Result<TokenWithRange, AsonError>> + '_ {
+        self.tokens
+            .iter()
+            .map(|token_with_range| Ok(token_with_range.clone()))
+    }
+}
+
+pub fn tokenize_from_str(s: &str) -> Result<Tokens, AsonError> {
+    let mut chars = s.chars();
+    tokenize_from_char_stream(&mut chars)
+}
+
+pub fn tokenize_from_reader<R: Read>(mut r: R) -> Result<Tokens, AsonError> {
+    let mut char_stream = CharStream::new(&mut r);
+    tokenize_from_char_stream(&mut char_stream)
+}
+
+/// Lexes the document and runs only the comment clearing and the
+/// normalization stages, without trimming the leading and trailing
+/// newlines, so the collapsing of commas and newlines can be observed
+/// in isolation.
+///
+/// ```
+/// use ason::{normalize_tokens, NumberToken, Token};
+///
+/// let tokens = normalize_tokens("1, \n , \n 2")
+///     .unwrap()
+///     .into_iter()
+///     .map(|token_with_range| token_with_range.token)
+///     .collect::<Vec<Token>>();
+///
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         Token::Number(NumberToken::I32(1)),
+///         Token::Comma,
+///         Token::Comma,
+///         Token::Number(NumberToken::I32(2)),
+///     ]
+/// );
+/// ```
+pub fn normalize_tokens(s: &str) -> Result<Vec<TokenWithRange>, AsonError> {
+    let mut chars = s.chars();
+    let mut char_position_iter = CharsWithPositionIter::new(&mut chars);
+    let mut peekable_char_position_iter =
+        PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
+    let mut lexer = Lexer::new(&mut peekable_char_position_iter);
+    let mut clear_iter = ClearTokenIter::new(&mut lexer);
+    let mut peekable_clear_iter =
+        PeekableIter::new(&mut clear_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
+    let normalized_iter = NormalizedTokenIter::new(&mut peekable_clear_iter);
+
+    normalized_iter.collect::<Result<Vec<TokenWithRange>, AsonError>>()
+}
+
+pub fn tokenize_from_char_stream(
+    char_stream: &mut dyn Iterator<Item = char>,
+) -> Result<Tokens, AsonError> {
+    let mut char_position_iter = CharsWithPositionIter::new(char_stream);
+    let mut peekable_char_position_iter =
+        PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
+    let mut lexer = Lexer::new(&mut peekable_char_position_iter);
+    let mut clear_iter = ClearTokenIter::new(&mut lexer);
+    let mut peekable_clear_iter =
+        PeekableIter::new(&mut clear_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
+    let mut normalized_iter = NormalizedTokenIter::new(&mut peekable_clear_iter);
+    let mut peekable_normalized_iter =
+        PeekableIter::new(&mut normalized_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
+    let trimmed_iter = TrimmedTokenIter::new(&mut peekable_normalized_iter);
+
+    let tokens = trimmed_iter.collect::<Result<Vec<TokenWithRange>, AsonError>>()?;
+    Ok(Tokens { tokens })
+}
+
+/// Tokenizes the document the same as `tokenize_from_str`, but yields
+/// only the kind and the range of each token, e.g. for tools that only
+/// need the positions of delimiters.
+///
+/// The value of each token (e.g. the content of strings) is dropped as
+/// soon as it is lexed, instead of being kept for the whole document.
+pub fn tokenize_tags(s: &str) -> Result<Vec<(TokenTag, Location)>, AsonError> {
+    let mut chars = s.chars();
+    let mut char_position_iter = CharsWithPositionIter::new(&mut chars);
+    let mut peekable_char_position_iter =
+        PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
+    let mut lexer = Lexer::new(&mut peekable_char_position_iter);
+    let mut clear_iter = ClearTokenIter::new(&mut lexer);
+    let mut peekable_clear_iter =
+        PeekableIter::new(&mut clear_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
+    let mut normalized_iter = NormalizedTokenIter::new(&mut peekable_clear_iter);
+    let mut peekable_normalized_iter =
+        PeekableIter::new(&mut normalized_iter, NORMALIZER_PEEK_TOKEN_MAX_COUNT);
+    let trimmed_iter = TrimmedTokenIter::new(&mut peekable_normalized_iter);
+
+    trimmed_iter
+        .map(|result| {
+            result.map(|token_with_range| (token_with_range.token.tag(), token_with_range.range))
+        })
+        .collect()
+}
+
+/// Returns the ranges of the trailing spaces and tabs of lines,
+/// the whitespace inside (multi-line) strings is part of the value
+/// and is not reported.
+///
+/// The lexer discards the whitespace between tokens, so the raw
+/// source text is scanned, and the document is tokenized only to
+/// locate the strings.
+pub fn check_whitespace(s: &str) -> Result<Vec<Location>, AsonError> {
+    let string_ranges = normalize_tokens(s)?
+        .into_iter()
+        .filter(|token_with_range| matches!(token_with_range.token, Token::String(..)))
+        .map(|token_with_range| {
+            let range = token_with_range.range;
+            range.index..range.index + range.length
+        })
+        .collect::<Vec<_>>();
+
+    let mut ranges = vec![];
+    let mut push_range = |start: Option<Location>, end_index: usize| {
+        if let Some(start) = start {
+            if !string_ranges
+                .iter()
+                .any(|range| range.contains(&start.index))
+            {
+                ranges.push(Location::from_position_and_length(
+                    &start,
+                    end_index - start.index,
+                ));
+            }
+        }
+    };
+
+    // the start position of the whitespace run of the current line
+    let mut whitespace_start: Option<Location> = None;
+    let (mut line, mut column) = (0, 0);
+    let mut chars = s.chars().enumerate().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            ' ' | '\t' => {
+                whitespace_start.get_or_insert(Location::new_position(index, line, column));
+            }
+            '\r' if matches!(chars.peek(), Some((_, '\n'))) => {
+                // the '\r' of "\r\n" is part of the line ending
+                push_range(whitespace_start.take(), index);
+            }
+            '\n' => {
+                push_range(whitespace_start.take(), index);
+                line += 1;
+                column = 0;
+                continue;
+            }
+            _ => {
+                whitespace_start = None;
+            }
+        }
+
+        column += 1;
+    }
+
+    push_range(whitespace_start, s.chars().count());
+
+    Ok(ranges)
+}
+
+/// Returns the ranges of the lines which are wider than `max_line_width`,
+/// each range starts at the first char beyond the width and ends at the
+/// end of the line.
+///
+/// The width is counted in chars, i.e. a multi-byte char counts as one,
+/// and the line ending (`\n` or `\r\n`) is not counted.
+pub fn check_line_width(s: &str, max_line_width: usize) -> Vec<Location> {
+    let mut ranges = vec![];
+    let mut index = 0;
+
+    for (line, text) in s.split('\n').enumerate() {
+        let width = text.strip_suffix('\r').unwrap_or(text).chars().count();
+
+        if width > max_line_width {
+            ranges.push(Location::new_range(
+                index + max_line_width,
+                line,
+                max_line_width,
+                width - max_line_width,
+            ));
+        }
+
+        // the '\n' is counted
+        index += text.chars().count() + 1;
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        location::Location,
+        token::{NumberToken, Token, TokenTag},
+        AsonError,
+    };
+
+    use super::{
+        check_line_width, check_whitespace, normalize_tokens, tokenize_from_str, tokenize_tags,
+    };
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize_from_str("[11, 13]").unwrap();
+        assert_eq!(tokens.len(), 5);
+        assert!(tokenize_from_str("").unwrap().is_empty());
+
+        let first = tokens.iter().next().unwrap().unwrap();
+        assert_eq!(first.token, Token::LeftBracket);
+        assert_eq!(first.range, Location::new_range(0, 0, 0, 1));
+    }
+
+    #[test]
+    fn test_tokenize_tags() {
+        let s0 = r#"{
+            id: 123 // comment
+            name: "foo"
+            tags: ["a", 'b', Option::None]
+            data: h"11 13"
+            date: d"2024-03-17 10:01:11Z"
+            items: [(true, -1.5)]
+        }"#;
+
+        let tags = tokenize_tags(s0).unwrap();
+        let tokens = tokenize_from_str(s0).unwrap();
+
+        assert_eq!(
+            tags,
+            tokens
+                .iter()
+                .map(|result| {
+                    let token_with_range = result.unwrap();
+                    (token_with_range.token.tag(), token_with_range.range)
+                })
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            tags[..4],
+            [
+                (TokenTag::LeftBrace, Location::new_range(0, 0, 0, 1)),
+                (TokenTag::NewLine, Location::new_range(1, 0, 1, 1)),
+                (TokenTag::Identifier, Location::new_range(14, 1, 12, 2)),
+                (TokenTag::Colon, Location::new_range(16, 1, 14, 1)),
+            ]
+        );
+
+        // lexical errors are reported
+        assert!(tokenize_tags("[\"foo").is_err());
+    }
+
+    #[test]
+    fn test_normalize_tokens() {
+        // the leading and trailing newlines are not trimmed
+        assert_eq!(
+            normalize_tokens("\n[11,\n\n13]\n")
+                .unwrap()
+                .into_iter()
+                .map(|e| e.token)
+                .collect::<Vec<Token>>(),
+            vec![
+                Token::NewLine,
+                Token::LeftBracket,
+                Token::Number(NumberToken::I32(11)),
+                Token::Comma,
+                Token::Number(NumberToken::I32(13)),
+                Token::RightBracket,
+                Token::NewLine,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_whitespace() {
+        assert_eq!(check_whitespace("[11, 13]\n").unwrap(), vec![]);
+
+        let s =
+            "{  \n    id: 11\t\n    name: \"foo\" \t // comment  \r\n    text: \"a  \n  b\"\n}  ";
+        assert_eq!(
+            check_whitespace(s).unwrap(),
+            vec![
+                Location::new_range(1, 0, 1, 2),
+                Location::new_range(14, 1, 10, 1),
+                Location::new_range(44, 2, 28, 2),
+                Location::new_range(69, 5, 1, 2),
+            ]
+        );
+
+        // the whitespace after a multi-line string is reported
+        assert_eq!(
+            check_whitespace("\"a  \nb\" \n").unwrap(),
+            vec![Location::new_range(7, 1, 2, 1)]
+        );
+
+        // err: invalid document
+        assert!(check_whitespace("[11, 'a]").is_err());
+    }
+
+    #[test]
+    fn test_check_line_width() {
+        // just under and at the limit
+        assert_eq!(check_line_width("[11, 13]\n[1]", 8), vec![]);
+
+        // just over the limit
+        assert_eq!(
+            check_line_width("[1]\n[11, 13]\r\n[11, 13, 17]", 7),
+            vec![
+                Location::new_range(11, 1, 7, 1),
+                Location::new_range(21, 2, 7, 5),
+            ]
+        );
+
+        // the multi-byte chars count as one
+        assert_eq!(check_line_width("\"文字文字\"", 6), vec![]);
+        assert_eq!(
+            check_line_width("\"文字文字\"", 5),
+            vec![Location::new_range(5, 0, 5, 1)]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_error() {
+        assert!(matches!(
+            tokenize_from_str("[11, 'a]"),
+            Err(AsonError::MessageWithLocation(..))
+        ));
+    }
+}